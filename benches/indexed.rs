@@ -0,0 +1,42 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rust_too_many_lists::indexed::IndexedList;
+use rust_too_many_lists::sixth::LinkedList;
+
+const LEN: usize = 100_000;
+
+// Reads spread over the whole list, through the index with several strides,
+// and by walking the plain list; a smaller stride means more handles to keep
+fn get(c: &mut Criterion) {
+    let positions: Vec<usize> = (0..100).map(|i| i * 997 % LEN).collect();
+    let mut group = c.benchmark_group("get");
+    let list: LinkedList<usize> = (0..LEN).collect();
+    group.bench_function("walk", |b| {
+        b.iter(|| {
+            positions
+                .iter()
+                .map(|&at| list.iter().nth(black_box(at)).unwrap())
+                .sum::<usize>()
+        })
+    });
+    for stride in [16, 256, 4096] {
+        let mut list = IndexedList::from_list((0..LEN).collect(), stride);
+        list.build_index();
+        group.bench_with_input(BenchmarkId::new("stride", stride), &list, |b, list| {
+            b.iter(|| {
+                positions
+                    .iter()
+                    .map(|&at| list.get(black_box(at)).unwrap())
+                    .sum::<usize>()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn build_index(c: &mut Criterion) {
+    let mut list = IndexedList::from_list((0..LEN).collect::<LinkedList<usize>>(), 256);
+    c.bench_function("build_index", |b| b.iter(|| list.build_index()));
+}
+
+criterion_group!(benches, get, build_index);
+criterion_main!(benches);
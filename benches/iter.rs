@@ -0,0 +1,82 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rust_too_many_lists::{fifth, first, sixth};
+
+const LEN: u64 = 10_000;
+
+// The adapters below drive the iteration through fold or try_fold, and sum is
+// compared with the same for loop, which goes through next. On nodes allocated
+// one after the other, both are limited by following the pointers.
+fn sixth(c: &mut Criterion) {
+    let list: sixth::LinkedList<u64> = (0..LEN).collect();
+    let mut group = c.benchmark_group("sixth");
+    group.bench_function("sum", |b| b.iter(|| black_box(&list).iter().sum::<u64>()));
+    group.bench_function("sum by next", |b| {
+        b.iter(|| {
+            let mut sum = 0;
+            for v in black_box(&list).iter() {
+                sum += v;
+            }
+            sum
+        })
+    });
+    group.bench_function("find", |b| {
+        b.iter(|| black_box(&list).iter().find(|&&v| v == LEN - 1).copied())
+    });
+    group.bench_function("all", |b| {
+        b.iter(|| black_box(&list).iter().all(|&v| v < LEN))
+    });
+    group.finish();
+}
+
+fn first(c: &mut Criterion) {
+    let mut list = first::List::new();
+    for v in 0..LEN {
+        list.push_front(v);
+    }
+    let mut group = c.benchmark_group("first");
+    group.bench_function("sum", |b| b.iter(|| black_box(&list).iter().sum::<u64>()));
+    group.bench_function("sum by next", |b| {
+        b.iter(|| {
+            let mut sum = 0;
+            for v in black_box(&list).iter() {
+                sum += v;
+            }
+            sum
+        })
+    });
+    group.bench_function("find", |b| {
+        b.iter(|| black_box(&list).iter().find(|&&v| v == 0).copied())
+    });
+    group.bench_function("all", |b| {
+        b.iter(|| black_box(&list).iter().all(|&v| v < LEN))
+    });
+    group.finish();
+}
+
+fn fifth(c: &mut Criterion) {
+    let mut list = fifth::List::new();
+    for v in 0..LEN {
+        list.push(v);
+    }
+    let mut group = c.benchmark_group("fifth");
+    group.bench_function("sum", |b| b.iter(|| black_box(&list).iter().sum::<u64>()));
+    group.bench_function("sum by next", |b| {
+        b.iter(|| {
+            let mut sum = 0;
+            for v in black_box(&list).iter() {
+                sum += v;
+            }
+            sum
+        })
+    });
+    group.bench_function("find", |b| {
+        b.iter(|| black_box(&list).iter().find(|&&v| v == LEN - 1).copied())
+    });
+    group.bench_function("all", |b| {
+        b.iter(|| black_box(&list).iter().all(|&v| v < LEN))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, sixth, first, fifth);
+criterion_main!(benches);
@@ -0,0 +1,63 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rust_too_many_lists::sixth::LinkedList;
+use rust_too_many_lists::small_list::SmallList;
+
+// Most lists in practice hold a handful of elements
+fn build_and_sum(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build_and_sum");
+    for len in [1, 3, 8, 32] {
+        group.bench_with_input(BenchmarkId::new("SmallList<_, 4>", len), &len, |b, &len| {
+            b.iter(|| {
+                let list: SmallList<u64, 4> = (0..len).collect();
+                black_box(list.iter().sum::<u64>())
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("sixth::LinkedList", len),
+            &len,
+            |b, &len| {
+                b.iter(|| {
+                    let list: LinkedList<u64> = (0..len).collect();
+                    black_box(list.iter().sum::<u64>())
+                })
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("Vec", len), &len, |b, &len| {
+            b.iter(|| {
+                let list: Vec<u64> = (0..len).collect();
+                black_box(list.iter().sum::<u64>())
+            })
+        });
+    }
+    group.finish();
+}
+
+fn queue(c: &mut Criterion) {
+    let mut group = c.benchmark_group("queue");
+    group.bench_function("SmallList<_, 4>", |b| {
+        b.iter(|| {
+            let mut list: SmallList<u64, 4> = SmallList::new();
+            for i in 0..100 {
+                list.push_back(i);
+                if list.len() > 3 {
+                    black_box(list.pop_front());
+                }
+            }
+        })
+    });
+    group.bench_function("sixth::LinkedList", |b| {
+        b.iter(|| {
+            let mut list: LinkedList<u64> = LinkedList::new();
+            for i in 0..100 {
+                list.push_back(i);
+                if list.len() > 3 {
+                    black_box(list.pop_front());
+                }
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, build_and_sum, queue);
+criterion_main!(benches);
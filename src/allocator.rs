@@ -0,0 +1,25 @@
+// With the allocator-api feature, which needs a nightly compiler, sixth takes
+// any of std's allocators. Otherwise, it can only use the global one, which
+// this stands for; the trait is sealed so that nothing else can take its
+// place, and the nodes are allocated as they always were.
+#[cfg(feature = "allocator-api")]
+pub use std::alloc::{Allocator, Global};
+
+#[cfg(not(feature = "allocator-api"))]
+pub use stand_in::{Allocator, Global};
+
+#[cfg(not(feature = "allocator-api"))]
+mod stand_in {
+    mod sealed {
+        pub trait Sealed {}
+    }
+
+    pub trait Allocator: sealed::Sealed {}
+
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct Global;
+
+    impl sealed::Sealed for Global {}
+
+    impl Allocator for Global {}
+}
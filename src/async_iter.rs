@@ -0,0 +1,31 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+
+// The values are already there, so the stream is always ready. It wraps the
+// iterator instead of the iterators being streams themselves, since methods
+// such as next and map would then be ambiguous.
+pub struct IntoStream<I>(I);
+
+impl<I> IntoStream<I> {
+    pub fn new(iter: I) -> Self {
+        IntoStream(iter)
+    }
+
+    pub fn into_inner(self) -> I {
+        self.0
+    }
+}
+
+impl<I: Iterator + Unpin> Stream for IntoStream<I> {
+    type Item = I::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<I::Item>> {
+        Poll::Ready(self.0.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
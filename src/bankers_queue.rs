@@ -0,0 +1,165 @@
+use crate::stream::Stream;
+use crate::third::List;
+
+// Elements are popped from the front stream and pushed on the rear list, which
+// is kept no longer than the front one by moving it to the end of the front
+// stream when needed. The move is lazy: the rear list is only reversed once
+// the front stream reaches it, and the result is memoized, so old versions of
+// the queue that pop through the same rebuild share its cost.
+pub struct Queue<T> {
+    front: Stream<T>,
+    front_len: usize,
+    rear: List<T>,
+    rear_len: usize,
+}
+
+impl<T> Queue<T> {
+    pub fn new() -> Self {
+        Queue {
+            front: Stream::empty(),
+            front_len: 0,
+            rear: List::new(),
+            rear_len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.front_len + self.rear_len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.front_len == 0
+    }
+
+    pub fn peek_front(&self) -> Option<&T> {
+        self.front.head()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let rear: Vec<_> = self.rear.iter().collect();
+        self.front.iter().chain(rear.into_iter().rev())
+    }
+}
+
+impl<T: Clone + 'static> Queue<T> {
+    pub fn push_back(&self, value: T) -> Self {
+        Queue {
+            front: self.front.clone(),
+            front_len: self.front_len,
+            rear: self.rear.prepend(value),
+            rear_len: self.rear_len + 1,
+        }
+        .check()
+    }
+
+    pub fn pop_front(&self) -> Self {
+        if self.is_empty() {
+            return self.clone();
+        }
+        Queue {
+            front: self.front.tail(),
+            front_len: self.front_len - 1,
+            rear: self.rear.clone(),
+            rear_len: self.rear_len,
+        }
+        .check()
+    }
+
+    fn check(self) -> Self {
+        if self.rear_len <= self.front_len {
+            return self;
+        }
+        let rear = self.rear;
+        let front = self.front.append_with(move || {
+            let mut reversed = Stream::empty();
+            for value in rear.iter() {
+                let tail = reversed;
+                reversed = Stream::cons(value.clone(), move || tail);
+            }
+            reversed
+        });
+        Queue {
+            front,
+            front_len: self.front_len + self.rear_len,
+            rear: List::new(),
+            rear_len: 0,
+        }
+    }
+}
+
+impl<T> Default for Queue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Queue<T> {
+    fn clone(&self) -> Self {
+        Queue {
+            front: self.front.clone(),
+            front_len: self.front_len,
+            rear: self.rear.clone(),
+            rear_len: self.rear_len,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Queue;
+
+    #[test]
+    fn basics() {
+        let queue = Queue::new();
+        assert!(queue.is_empty());
+        assert_eq!(queue.peek_front(), None);
+        let queue = queue.pop_front();
+        assert!(queue.is_empty());
+
+        let queue = queue.push_back(1).push_back(2).push_back(3);
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.peek_front(), Some(&1));
+
+        let queue = queue.pop_front();
+        assert_eq!(queue.peek_front(), Some(&2));
+        let queue = queue.push_back(4).push_back(5);
+        assert_eq!(queue.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4, 5]);
+
+        let queue = queue.pop_front().pop_front().pop_front();
+        assert_eq!(queue.peek_front(), Some(&5));
+        let queue = queue.pop_front();
+        assert!(queue.is_empty());
+        assert_eq!(queue.len(), 0);
+    }
+
+    #[test]
+    fn persistence() {
+        let base = Queue::new().push_back(1).push_back(2);
+        let a = base.push_back(3);
+        let b = base.pop_front().push_back(10);
+        assert_eq!(base.iter().copied().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(a.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(b.iter().copied().collect::<Vec<_>>(), vec![2, 10]);
+        drop(base);
+        assert_eq!(a.pop_front().peek_front(), Some(&2));
+        assert_eq!(b.pop_front().peek_front(), Some(&10));
+    }
+
+    #[test]
+    fn long() {
+        let mut queue = Queue::new();
+        for i in 0..10_000 {
+            queue = queue.push_back(i);
+            if i % 3 == 0 {
+                queue = queue.pop_front();
+            }
+        }
+        let mut expected = 3334;
+        while let Some(&value) = queue.peek_front() {
+            assert_eq!(value, expected);
+            expected += 1;
+            queue = queue.pop_front();
+        }
+        assert_eq!(expected, 10_000);
+    }
+}
@@ -0,0 +1,169 @@
+use std::sync::{Condvar, Mutex};
+
+use crate::fifth;
+
+struct State<T> {
+    queue: fifth::List<T>,
+    len: usize,
+}
+
+pub struct BoundedQueue<T> {
+    state: Mutex<State<T>>,
+    capacity: usize,
+    not_empty: Condvar,
+    not_full: Condvar,
+}
+
+impl<T> BoundedQueue<T> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity should be > 0");
+        BoundedQueue {
+            state: Mutex::new(State {
+                queue: fifth::List::new(),
+                len: 0,
+            }),
+            capacity,
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity
+    }
+
+    // Blocks while the queue is full
+    pub fn send(&self, value: T) {
+        let state = self.state.lock().unwrap();
+        let mut state = self
+            .not_full
+            .wait_while(state, |state| state.len == self.capacity)
+            .unwrap();
+        state.queue.push(value);
+        state.len += 1;
+        self.not_empty.notify_one();
+    }
+
+    // Gives the value back when the queue is full
+    pub fn try_send(&self, value: T) -> Result<(), T> {
+        let mut state = self.state.lock().unwrap();
+        if state.len == self.capacity {
+            return Err(value);
+        }
+        state.queue.push(value);
+        state.len += 1;
+        self.not_empty.notify_one();
+        Ok(())
+    }
+
+    // Blocks while the queue is empty
+    pub fn recv(&self) -> T {
+        let state = self.state.lock().unwrap();
+        let mut state = self
+            .not_empty
+            .wait_while(state, |state| state.len == 0)
+            .unwrap();
+        let value = state.queue.pop().unwrap();
+        state.len -= 1;
+        self.not_full.notify_one();
+        value
+    }
+
+    pub fn try_recv(&self) -> Option<T> {
+        let mut state = self.state.lock().unwrap();
+        let value = state.queue.pop()?;
+        state.len -= 1;
+        self.not_full.notify_one();
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BoundedQueue;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn basics() {
+        let queue = BoundedQueue::new(2);
+        assert_eq!(queue.capacity(), 2);
+        assert!(queue.is_empty());
+        assert_eq!(queue.try_recv(), None);
+        assert_eq!(queue.try_send(1), Ok(()));
+        queue.send(2);
+        assert!(queue.is_full());
+        assert_eq!(queue.try_send(3), Err(3));
+        assert_eq!(queue.recv(), 1);
+        assert_eq!(queue.try_send(3), Ok(()));
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.try_recv(), Some(2));
+        assert_eq!(queue.recv(), 3);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_capacity() {
+        BoundedQueue::<i32>::new(0);
+    }
+
+    #[test]
+    fn producer_blocks_when_full() {
+        let queue = Arc::new(BoundedQueue::new(1));
+        queue.send(1);
+        let producer = {
+            let queue = queue.clone();
+            thread::spawn(move || queue.send(2))
+        };
+        thread::sleep(Duration::from_millis(20));
+        assert!(!producer.is_finished());
+        assert_eq!(queue.recv(), 1);
+        producer.join().unwrap();
+        assert_eq!(queue.recv(), 2);
+    }
+
+    #[test]
+    fn producers_and_consumers() {
+        let queue = Arc::new(BoundedQueue::new(8));
+        let producers: Vec<_> = (0..4)
+            .map(|p| {
+                let queue = queue.clone();
+                thread::spawn(move || {
+                    for i in 0..1000 {
+                        queue.send(p * 1000 + i);
+                    }
+                })
+            })
+            .collect();
+        let consumers: Vec<_> = (0..4)
+            .map(|_| {
+                let queue = queue.clone();
+                thread::spawn(move || (0..1000).map(|_| queue.recv()).collect::<Vec<_>>())
+            })
+            .collect();
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        let mut received: Vec<_> = consumers
+            .into_iter()
+            .flat_map(|c| c.join().unwrap())
+            .collect();
+        received.sort();
+        assert_eq!(received, (0..4000).collect::<Vec<_>>());
+        assert!(queue.is_empty());
+    }
+}
@@ -0,0 +1,148 @@
+use std::marker::PhantomData;
+
+use crate::sixth::{self, LinkedList, NodeHandle};
+
+type Brand<'id> = PhantomData<fn(&'id ()) -> &'id ()>;
+
+// Exactly one list is created for each brand, and a handle can only be used
+// with the list of its brand: using it with another list does not compile.
+// Handles cannot be copied and removing a node consumes its handle, so a
+// handle always points to a node of its list, and no check is needed at
+// runtime. For that reason, nodes can only be removed through their handles.
+pub struct BrandedList<'id, T> {
+    list: LinkedList<T>,
+    _brand: Brand<'id>,
+}
+
+pub struct Handle<'id, T> {
+    node: NodeHandle<T>,
+    _brand: Brand<'id>,
+}
+
+impl<T> BrandedList<'_, T> {
+    pub fn scope<R>(f: impl for<'id> FnOnce(BrandedList<'id, T>) -> R) -> R {
+        f(BrandedList {
+            list: LinkedList::new(),
+            _brand: PhantomData,
+        })
+    }
+}
+
+impl<'id, T> BrandedList<'id, T> {
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.list.front()
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.list.back()
+    }
+
+    pub fn push_front(&mut self, value: T) -> Handle<'id, T> {
+        Handle {
+            node: self.list.push_front_handle(value),
+            _brand: PhantomData,
+        }
+    }
+
+    pub fn push_back(&mut self, value: T) -> Handle<'id, T> {
+        Handle {
+            node: self.list.push_back_handle(value),
+            _brand: PhantomData,
+        }
+    }
+
+    pub fn get(&self, handle: &Handle<'id, T>) -> &T {
+        unsafe { self.list.get_handle(handle.node) }
+    }
+
+    pub fn get_mut(&mut self, handle: &Handle<'id, T>) -> &mut T {
+        unsafe { self.list.get_handle_mut(handle.node) }
+    }
+
+    pub fn remove(&mut self, handle: Handle<'id, T>) -> T {
+        unsafe { self.list.remove_handle(handle.node) }
+    }
+
+    pub fn move_to_front(&mut self, handle: &Handle<'id, T>) {
+        unsafe { self.list.move_handle_to_front(handle.node) }
+    }
+
+    pub fn iter(&self) -> sixth::Iter<'_, T> {
+        self.list.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> sixth::IterMut<'_, T> {
+        self.list.iter_mut()
+    }
+
+    // The handles cannot be used anymore since the list of their brand is gone
+    pub fn into_list(self) -> LinkedList<T> {
+        self.list
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BrandedList;
+
+    #[test]
+    fn basics() {
+        BrandedList::scope(|mut list| {
+            assert!(list.is_empty());
+            let b = list.push_back('b');
+            let a = list.push_front('a');
+            let c = list.push_back('c');
+            assert_eq!(list.len(), 3);
+            assert_eq!(list.get(&a), &'a');
+            assert_eq!(list.get(&c), &'c');
+            *list.get_mut(&b) = 'B';
+
+            assert_eq!(list.remove(b), 'B');
+            assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec!['a', 'c']);
+            list.move_to_front(&c);
+            assert_eq!(list.front(), Some(&'c'));
+            assert_eq!(list.back(), Some(&'a'));
+            assert_eq!(list.remove(a), 'a');
+            assert_eq!(list.remove(c), 'c');
+            assert!(list.is_empty());
+        });
+    }
+
+    #[test]
+    fn into_list() {
+        let values = BrandedList::scope(|mut list| {
+            let handles: Vec<_> = (0..10).map(|i| list.push_back(i)).collect();
+            for handle in handles.into_iter().step_by(3) {
+                list.remove(handle);
+            }
+            for value in list.iter_mut() {
+                *value *= 10;
+            }
+            list.into_list().into_iter().collect::<Vec<_>>()
+        });
+        assert_eq!(values, vec![10, 20, 40, 50, 70, 80]);
+    }
+
+    #[test]
+    fn nested_scopes() {
+        // each scope has its own brand, so mixing up their handles would not
+        // compile
+        BrandedList::scope(|mut outer| {
+            let x = outer.push_back(1);
+            BrandedList::scope(|mut inner| {
+                let y = inner.push_back(2);
+                *inner.get_mut(&y) += outer.get(&x);
+                assert_eq!(inner.remove(y), 3);
+            });
+            assert_eq!(outer.remove(x), 1);
+        });
+    }
+}
@@ -0,0 +1,187 @@
+use std::io::{self, BufRead, IoSlice, Read, Write};
+
+use crate::sixth::LinkedList;
+
+const CHUNK_CAPACITY: usize = 4096;
+
+// Bytes are written at the back and read from the front. Each chunk is
+// allocated with CHUNK_CAPACITY bytes and never grows past it, so writing
+// never moves the bytes already buffered.
+pub struct ByteList {
+    chunks: LinkedList<Vec<u8>>,
+    // how many bytes of the front chunk were already read
+    read: usize,
+    len: usize,
+}
+
+impl ByteList {
+    pub fn new() -> Self {
+        ByteList {
+            chunks: LinkedList::new(),
+            read: 0,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn chunks(&self) -> impl Iterator<Item = &[u8]> {
+        let read = self.read;
+        self.chunks
+            .iter()
+            .enumerate()
+            .map(move |(i, chunk)| if i == 0 { &chunk[read..] } else { &chunk[..] })
+    }
+
+    // For writing the whole buffer with a single write_vectored
+    pub fn io_slices(&self) -> Vec<IoSlice<'_>> {
+        self.chunks().map(IoSlice::new).collect()
+    }
+
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.len);
+        for chunk in self.chunks() {
+            bytes.extend_from_slice(chunk);
+        }
+        bytes
+    }
+}
+
+impl Default for ByteList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Write for ByteList {
+    fn write(&mut self, mut buf: &[u8]) -> io::Result<usize> {
+        let written = buf.len();
+        while !buf.is_empty() {
+            let chunk = match self.chunks.back_mut() {
+                Some(chunk) if chunk.len() < CHUNK_CAPACITY => chunk,
+                _ => {
+                    self.chunks.push_back(Vec::with_capacity(CHUNK_CAPACITY));
+                    self.chunks.back_mut().unwrap()
+                }
+            };
+            let n = buf.len().min(CHUNK_CAPACITY - chunk.len());
+            chunk.extend_from_slice(&buf[..n]);
+            buf = &buf[n..];
+        }
+        self.len += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl BufRead for ByteList {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(self.chunks().next().unwrap_or(&[]))
+    }
+
+    fn consume(&mut self, amt: usize) {
+        assert!(amt <= self.len, "cannot consume more than was written");
+        self.len -= amt;
+        self.read += amt;
+        // the front chunk is freed as soon as it is fully read
+        while let Some(front) = self.chunks.front() {
+            if self.read < front.len() {
+                break;
+            }
+            self.read -= front.len();
+            self.chunks.pop_front();
+        }
+    }
+}
+
+impl Read for ByteList {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut n = 0;
+        while n < buf.len() && !self.is_empty() {
+            let available = self.fill_buf()?;
+            let m = available.len().min(buf.len() - n);
+            buf[n..n + m].copy_from_slice(&available[..m]);
+            self.consume(m);
+            n += m;
+        }
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{BufRead, Read, Write};
+
+    use super::{ByteList, CHUNK_CAPACITY};
+
+    #[test]
+    fn basics() {
+        let mut list = ByteList::new();
+        assert!(list.is_empty());
+        list.write_all(b"hello ").unwrap();
+        let place = "world";
+        write!(list, "{place}!").unwrap();
+        assert_eq!(list.len(), 12);
+        assert_eq!(list.to_vec(), b"hello world!");
+
+        let mut buf = [0; 5];
+        list.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"hello");
+        assert_eq!(list.len(), 7);
+        let mut rest = String::new();
+        list.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, " world!");
+        assert!(list.is_empty());
+        assert_eq!(list.read(&mut buf).unwrap(), 0);
+    }
+
+    #[test]
+    fn across_chunks() {
+        let bytes: Vec<u8> = (0..3 * CHUNK_CAPACITY + 10).map(|i| i as u8).collect();
+        let mut list = ByteList::new();
+        // written in pieces that do not line up with the chunks
+        for piece in bytes.chunks(1000) {
+            list.write_all(piece).unwrap();
+        }
+        assert_eq!(list.chunks().count(), 4);
+        assert!(list.chunks().all(|chunk| chunk.len() <= CHUNK_CAPACITY));
+        assert_eq!(list.to_vec(), bytes);
+
+        let mut buf = vec![0; CHUNK_CAPACITY + 1];
+        list.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, bytes[..CHUNK_CAPACITY + 1]);
+        // the first chunk was read entirely, so it is gone
+        assert_eq!(list.chunks().count(), 3);
+        assert_eq!(list.fill_buf().unwrap().len(), CHUNK_CAPACITY - 1);
+        assert_eq!(list.to_vec(), bytes[CHUNK_CAPACITY + 1..]);
+
+        // writing after reading appends at the back
+        list.write_all(b"end").unwrap();
+        let mut rest = Vec::new();
+        list.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest[..rest.len() - 3], bytes[CHUNK_CAPACITY + 1..]);
+        assert_eq!(&rest[rest.len() - 3..], b"end");
+        assert_eq!(list.chunks().count(), 0);
+    }
+
+    #[test]
+    fn io_slices() {
+        let mut list = ByteList::new();
+        list.write_all(&[1; CHUNK_CAPACITY + 5]).unwrap();
+        list.consume(3);
+        let slices = list.io_slices();
+        assert_eq!(slices.len(), 2);
+        let mut out = Vec::new();
+        assert_eq!(out.write_vectored(&slices).unwrap(), CHUNK_CAPACITY + 2);
+        assert_eq!(out, list.to_vec());
+    }
+}
@@ -0,0 +1,208 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+type Link<T> = Option<Rc<Node<T>>>;
+
+struct Node<T> {
+    value: T,
+    parent: Link<T>,
+    depth: usize,
+    // live nodes having this one as parent
+    children: Cell<usize>,
+}
+
+impl<T> Node<T> {
+    fn detach(&mut self) -> Link<T> {
+        let parent = self.parent.take();
+        if let Some(parent) = &parent {
+            parent.children.set(parent.children.get() - 1);
+        }
+        parent
+    }
+}
+
+impl<T> Drop for Node<T> {
+    fn drop(&mut self) {
+        self.detach();
+    }
+}
+
+// A stack where pushing does not modify the original, so that many stacks can
+// grow from the same prefix: each stack is a path from a node up to the root
+// of a tree whose edges point to the parents
+pub struct Cactus<T> {
+    node: Link<T>,
+}
+
+impl<T> Cactus<T> {
+    pub fn new() -> Self {
+        Cactus { node: None }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.node.is_none()
+    }
+
+    pub fn len(&self) -> usize {
+        self.node.as_ref().map_or(0, |node| node.depth)
+    }
+
+    pub fn value(&self) -> Option<&T> {
+        self.node.as_ref().map(|node| &node.value)
+    }
+
+    pub fn child(&self, value: T) -> Self {
+        if let Some(node) = &self.node {
+            node.children.set(node.children.get() + 1);
+        }
+        Cactus {
+            node: Some(Rc::new(Node {
+                value,
+                parent: self.node.clone(),
+                depth: self.len() + 1,
+                children: Cell::new(0),
+            })),
+        }
+    }
+
+    pub fn parent(&self) -> Option<Self> {
+        self.node.as_ref().map(|node| Cactus {
+            node: node.parent.clone(),
+        })
+    }
+
+    // From this value up to the root
+    pub fn iter_ancestors(&self) -> Ancestors<'_, T> {
+        Ancestors {
+            current: self.node.as_deref(),
+        }
+    }
+
+    // Number of stacks directly extending this one that are still alive
+    pub fn branches(&self) -> usize {
+        self.node.as_ref().map_or(0, |node| node.children.get())
+    }
+
+    // Whether other stacks are built on this one or hold the same node
+    pub fn is_shared(&self) -> bool {
+        self.node
+            .as_ref()
+            .is_some_and(|node| Rc::strong_count(node) > 1)
+    }
+
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.node, &other.node) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T> Default for Cactus<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Cactus<T> {
+    fn clone(&self) -> Self {
+        Cactus {
+            node: self.node.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Cactus<T> {
+    fn drop(&mut self) {
+        let mut current = self.node.take();
+        while let Some(rc) = current {
+            if let Ok(mut node) = Rc::try_unwrap(rc) {
+                current = node.detach();
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+pub struct Ancestors<'a, T> {
+    current: Option<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Ancestors<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.current.map(|node| {
+            self.current = node.parent.as_deref();
+            &node.value
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.current.map_or(0, |node| node.depth);
+        (len, Some(len))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Cactus;
+
+    #[test]
+    fn basics() {
+        let root = Cactus::new();
+        assert!(root.is_empty());
+        assert_eq!(root.value(), None);
+        assert!(root.parent().is_none());
+
+        let a = root.child(1);
+        let b = a.child(2);
+        assert_eq!(b.len(), 2);
+        assert_eq!(b.value(), Some(&2));
+        assert_eq!(b.iter_ancestors().copied().collect::<Vec<_>>(), vec![2, 1]);
+        assert!(b.parent().unwrap().ptr_eq(&a));
+        assert!(b.parent().unwrap().parent().unwrap().is_empty());
+    }
+
+    #[test]
+    fn branches() {
+        // nested scopes sharing their enclosing ones
+        let global = Cactus::new().child("global");
+        let function = global.child("function");
+        let then_block = function.child("then");
+        let else_block = function.child("else");
+        assert_eq!(global.branches(), 1);
+        assert_eq!(function.branches(), 2);
+        assert!(function.is_shared());
+        assert!(!then_block.is_shared());
+        assert_eq!(
+            then_block.iter_ancestors().copied().collect::<Vec<_>>(),
+            vec!["then", "function", "global"]
+        );
+        assert_eq!(
+            else_block.iter_ancestors().copied().collect::<Vec<_>>(),
+            vec!["else", "function", "global"]
+        );
+
+        drop(then_block);
+        assert_eq!(function.branches(), 1);
+        drop(function);
+        // still reachable from else_block
+        assert_eq!(global.branches(), 1);
+        drop(else_block);
+        assert_eq!(global.branches(), 0);
+    }
+
+    #[test]
+    fn long_drop() {
+        let mut stack = Cactus::new();
+        for i in 0..100_000 {
+            stack = stack.child(i);
+        }
+        let branch = stack.parent().unwrap().child(-1);
+        assert_eq!(branch.len(), 100_000);
+        drop(stack);
+        drop(branch);
+    }
+}
@@ -0,0 +1,185 @@
+use std::mem;
+
+use crate::first;
+use crate::sixth::LinkedList;
+
+// Appending only records the concatenation in a tree; the elements are laid
+// out in a real list once, when the builder is turned into one
+enum Node<T> {
+    Leaf(T),
+    Concat(Box<Node<T>>, Box<Node<T>>),
+}
+
+pub struct DList<T> {
+    root: Option<Node<T>>,
+    len: usize,
+}
+
+impl<T> DList<T> {
+    pub fn new() -> Self {
+        DList { root: None, len: 0 }
+    }
+
+    pub fn singleton(value: T) -> Self {
+        DList {
+            root: Some(Node::Leaf(value)),
+            len: 1,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn cons(&mut self, value: T) {
+        self.prepend(DList::singleton(value));
+    }
+
+    pub fn snoc(&mut self, value: T) {
+        self.append(DList::singleton(value));
+    }
+
+    pub fn append(&mut self, mut other: DList<T>) {
+        self.root = match (self.root.take(), other.root.take()) {
+            (None, root) | (root, None) => root,
+            (Some(left), Some(right)) => Some(Node::Concat(Box::new(left), Box::new(right))),
+        };
+        self.len += mem::take(&mut other.len);
+    }
+
+    pub fn prepend(&mut self, mut other: DList<T>) {
+        mem::swap(self, &mut other);
+        self.append(other);
+    }
+
+    pub fn into_first(self) -> first::List<T> {
+        let mut list = first::List::new();
+        self.drain(true, |value| list.push_front(value));
+        list
+    }
+
+    pub fn into_linked_list(self) -> LinkedList<T> {
+        let mut list = LinkedList::new();
+        self.drain(false, |value| list.push_back(value));
+        list
+    }
+
+    // Visits the leaves in order, or in reverse order, without recursing
+    fn drain(mut self, reverse: bool, mut f: impl FnMut(T)) {
+        let mut stack: Vec<Node<T>> = self.root.take().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            match node {
+                Node::Leaf(value) => f(value),
+                Node::Concat(left, right) if reverse => {
+                    stack.push(*left);
+                    stack.push(*right);
+                }
+                Node::Concat(left, right) => {
+                    stack.push(*right);
+                    stack.push(*left);
+                }
+            }
+        }
+    }
+}
+
+impl<T> Default for DList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for DList<T> {
+    fn drop(&mut self) {
+        // a long chain of appends would overflow the stack if dropped
+        // recursively
+        let mut stack: Vec<Node<T>> = self.root.take().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            if let Node::Concat(left, right) = node {
+                stack.push(*left);
+                stack.push(*right);
+            }
+        }
+    }
+}
+
+impl<T> Extend<T> for DList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.snoc(value);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for DList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = DList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DList;
+
+    #[test]
+    fn basics() {
+        let mut list = DList::new();
+        assert!(list.is_empty());
+        list.snoc(2);
+        list.snoc(3);
+        list.cons(1);
+        assert_eq!(list.len(), 3);
+        assert_eq!(
+            list.into_linked_list().into_iter().collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+
+        let empty: DList<i32> = DList::new();
+        assert!(empty.into_first().peek().is_none());
+    }
+
+    #[test]
+    fn fragments() {
+        let mut list: DList<_> = (3..6).collect();
+        list.prepend((0..3).collect());
+        list.append(DList::new());
+        list.append((6..9).collect());
+        list.prepend(DList::new());
+        assert_eq!(list.len(), 9);
+
+        let mut first = list.into_first();
+        for i in 0..9 {
+            assert_eq!(first.pop_front(), Some(i));
+        }
+        assert_eq!(first.pop_front(), None);
+    }
+
+    #[test]
+    fn many_appends() {
+        // building by appending at the end of a first::List would be quadratic
+        let mut list = DList::new();
+        for i in 0..100_000 {
+            let mut fragment = DList::singleton(2 * i);
+            fragment.snoc(2 * i + 1);
+            list.append(fragment);
+        }
+        assert_eq!(list.len(), 200_000);
+        let linked = list.into_linked_list();
+        assert!(linked.iter().copied().eq(0..200_000));
+    }
+
+    #[test]
+    fn long_drop() {
+        let mut list = DList::new();
+        for i in 0..100_000 {
+            list.cons(i);
+        }
+        drop(list);
+    }
+}
@@ -0,0 +1,73 @@
+use std::collections::HashSet;
+use std::io::{self, Write};
+
+// What the lists know about one of their nodes, nodes being identified by
+// their addresses
+pub(crate) struct DotNode {
+    pub(crate) id: usize,
+    pub(crate) label: String,
+    pub(crate) next: Option<usize>,
+    pub(crate) prev: Option<usize>,
+}
+
+// Writes the nodes reached from the first one, with their next and prev
+// edges. The walk stops at a node already written, so that a list whose links
+// were broken into a cycle can still be drawn.
+pub(crate) fn write_graph(
+    w: &mut impl Write,
+    first: Option<usize>,
+    last: Option<usize>,
+    nodes: impl Iterator<Item = DotNode>,
+) -> io::Result<()> {
+    writeln!(w, "digraph list {{")?;
+    writeln!(w, "    node [shape=record];")?;
+    writeln!(w, "    first [shape=plaintext];")?;
+    writeln!(w, "    last [shape=plaintext];")?;
+    if let Some(first) = first {
+        writeln!(w, "    first -> n{first:x};")?;
+    }
+    if let Some(last) = last {
+        writeln!(w, "    last -> n{last:x};")?;
+    }
+    let mut seen = HashSet::new();
+    for node in nodes {
+        if !seen.insert(node.id) {
+            break;
+        }
+        let label = escape(&node.label);
+        writeln!(
+            w,
+            "    n{:x} [label=\"{{0x{:x} | {}}}\"];",
+            node.id, node.id, label
+        )?;
+        if let Some(next) = node.next {
+            writeln!(w, "    n{:x} -> n{next:x} [label=next];", node.id)?;
+        }
+        if let Some(prev) = node.prev {
+            writeln!(
+                w,
+                "    n{:x} -> n{prev:x} [label=prev, style=dashed];",
+                node.id
+            )?;
+        }
+    }
+    writeln!(w, "}}")
+}
+
+// Record labels give a meaning to these characters
+fn escape(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+    for c in label.chars() {
+        if "\"{}|<>\\".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+pub(crate) fn to_string(write: impl FnOnce(&mut Vec<u8>) -> io::Result<()>) -> String {
+    let mut buffer = Vec::new();
+    write(&mut buffer).expect("writing to a Vec does not fail");
+    String::from_utf8(buffer).expect("the graph is valid UTF-8")
+}
@@ -0,0 +1,381 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::atomic::{fence, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+// Epoch-based reclamation: a thread pins itself before reading shared nodes,
+// and nodes unlinked from a structure are only freed once every thread that
+// was pinned at that time has unpinned.
+//
+// The global epoch only advances when all pinned threads have observed the
+// current one. A node retired in epoch e may still be seen by threads pinned
+// in epoch e (or e - 1, if they pinned just before the epoch advanced), so it
+// is freed once the global epoch reaches e + 2.
+
+struct Deferred {
+    data: *mut (),
+    call: unsafe fn(*mut ()),
+}
+
+// Whoever defers a call vouches that it can run on any thread
+unsafe impl Send for Deferred {}
+
+struct Local {
+    // 0 when not pinned, the epoch shifted left by one and tagged otherwise
+    epoch: AtomicUsize,
+}
+
+// Collecting takes both locks and goes over all the garbage, so a deferred
+// call only pushes to it, and every so many of them collect
+const COLLECT_EVERY: usize = 16;
+
+struct Global {
+    epoch: AtomicUsize,
+    locals: Mutex<Vec<Arc<Local>>>,
+    garbage: Mutex<Vec<(usize, Deferred)>>,
+    defers: AtomicUsize,
+}
+
+impl Global {
+    fn try_advance(&self) -> usize {
+        let epoch = self.epoch.load(Ordering::Relaxed);
+        fence(Ordering::SeqCst);
+        for local in self.locals.lock().unwrap().iter() {
+            let state = local.epoch.load(Ordering::Relaxed);
+            if state & 1 == 1 && state >> 1 != epoch {
+                return epoch;
+            }
+        }
+        fence(Ordering::Acquire);
+        match self
+            .epoch
+            .compare_exchange(epoch, epoch + 1, Ordering::Release, Ordering::Relaxed)
+        {
+            Ok(_) => epoch + 1,
+            Err(current) => current,
+        }
+    }
+
+    fn collect(&self) {
+        let epoch = self.try_advance();
+        let expired: Vec<_> = {
+            let mut garbage = self.garbage.lock().unwrap();
+            let (expired, kept) = garbage
+                .drain(..)
+                .partition(|(retired, _)| retired + 2 <= epoch);
+            *garbage = kept;
+            expired
+        };
+        // run outside of the lock, in case the calls defer more garbage
+        for (_, deferred) in expired {
+            unsafe { (deferred.call)(deferred.data) };
+        }
+    }
+}
+
+impl Drop for Global {
+    fn drop(&mut self) {
+        for (_, deferred) in self.garbage.get_mut().unwrap().drain(..) {
+            unsafe { (deferred.call)(deferred.data) };
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Collector {
+    global: Arc<Global>,
+}
+
+impl Collector {
+    pub fn new() -> Self {
+        Collector {
+            global: Arc::new(Global {
+                epoch: AtomicUsize::new(0),
+                locals: Mutex::new(Vec::new()),
+                garbage: Mutex::new(Vec::new()),
+                defers: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    // Each thread needs its own handle to pin itself
+    pub fn register(&self) -> Handle {
+        let local = Arc::new(Local {
+            epoch: AtomicUsize::new(0),
+        });
+        self.global.locals.lock().unwrap().push(local.clone());
+        Handle(Rc::new(Participant {
+            global: self.global.clone(),
+            local,
+            guards: Cell::new(0),
+        }))
+    }
+
+    pub fn collect(&self) {
+        self.global.collect();
+    }
+
+    // Number of deferred calls not run yet
+    pub fn pending(&self) -> usize {
+        self.global.garbage.lock().unwrap().len()
+    }
+}
+
+impl Default for Collector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct Participant {
+    global: Arc<Global>,
+    local: Arc<Local>,
+    guards: Cell<usize>,
+}
+
+impl Drop for Participant {
+    fn drop(&mut self) {
+        let mut locals = self.global.locals.lock().unwrap();
+        locals.retain(|local| !Arc::ptr_eq(local, &self.local));
+    }
+}
+
+#[derive(Clone)]
+pub struct Handle(Rc<Participant>);
+
+impl Handle {
+    // Guards can be nested; the thread stays pinned until the last one is
+    // dropped
+    pub fn pin(&self) -> Guard {
+        let participant = &self.0;
+        let guards = participant.guards.get();
+        participant.guards.set(guards + 1);
+        if guards == 0 {
+            let epoch = participant.global.epoch.load(Ordering::Relaxed);
+            participant
+                .local
+                .epoch
+                .store(epoch << 1 | 1, Ordering::Relaxed);
+            fence(Ordering::SeqCst);
+        }
+        Guard {
+            handle: self.clone(),
+        }
+    }
+}
+
+pub struct Guard {
+    handle: Handle,
+}
+
+impl Guard {
+    /// Runs `free(data)` once no thread can still be reading `data`.
+    ///
+    /// # Safety
+    ///
+    /// `data` must already be unreachable for threads that pin from now on,
+    /// and `free(data)` must be safe to call from any thread.
+    pub unsafe fn defer(&self, data: *mut (), free: unsafe fn(*mut ())) {
+        let global = &self.handle.0.global;
+        let epoch = global.epoch.load(Ordering::SeqCst);
+        global
+            .garbage
+            .lock()
+            .unwrap()
+            .push((epoch, Deferred { data, call: free }));
+        if global.defers.fetch_add(1, Ordering::Relaxed) % COLLECT_EVERY == COLLECT_EVERY - 1 {
+            global.collect();
+        }
+    }
+
+    /// Drops the box behind `ptr` once no thread can still be reading it.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Guard::defer`], and `ptr` must come from `Box::into_raw`.
+    pub unsafe fn defer_destroy<T>(&self, ptr: *mut T) {
+        self.defer(ptr as *mut (), drop_box::<T>);
+    }
+}
+
+unsafe fn drop_box<T>(data: *mut ()) {
+    drop(Box::from_raw(data as *mut T));
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        let participant = &self.handle.0;
+        let guards = participant.guards.get() - 1;
+        participant.guards.set(guards);
+        if guards == 0 {
+            participant.local.epoch.store(0, Ordering::Release);
+        }
+    }
+}
+
+fn default_collector() -> &'static Collector {
+    static COLLECTOR: OnceLock<Collector> = OnceLock::new();
+    COLLECTOR.get_or_init(Collector::new)
+}
+
+thread_local! {
+    static HANDLE: Handle = default_collector().register();
+}
+
+// Pins the current thread with the collector shared by the whole crate
+pub fn pin() -> Guard {
+    HANDLE.with(Handle::pin)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Collector, COLLECT_EVERY};
+    use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    struct Counted(Arc<AtomicUsize>);
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn waits_for_pinned_threads() {
+        let collector = Collector::new();
+        let reader = collector.register();
+        let writer = collector.register();
+        let drops = Arc::new(AtomicUsize::new(0));
+
+        let guard = reader.pin();
+        let ptr = Box::into_raw(Box::new(Counted(drops.clone())));
+        unsafe { writer.pin().defer_destroy(ptr) };
+        for _ in 0..10 {
+            collector.collect();
+        }
+        assert_eq!(drops.load(Ordering::Relaxed), 0);
+        assert_eq!(collector.pending(), 1);
+
+        drop(guard);
+        for _ in 0..3 {
+            collector.collect();
+        }
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+        assert_eq!(collector.pending(), 0);
+    }
+
+    #[test]
+    fn nested_guards() {
+        let collector = Collector::new();
+        let handle = collector.register();
+        let other = collector.register();
+        let drops = Arc::new(AtomicUsize::new(0));
+
+        let outer = handle.pin();
+        let inner = handle.pin();
+        drop(inner);
+        let ptr = Box::into_raw(Box::new(Counted(drops.clone())));
+        unsafe { other.pin().defer_destroy(ptr) };
+        for _ in 0..10 {
+            collector.collect();
+        }
+        assert_eq!(drops.load(Ordering::Relaxed), 0);
+        drop(outer);
+        for _ in 0..3 {
+            collector.collect();
+        }
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn collects_every_few_defers() {
+        let collector = Collector::new();
+        let handle = collector.register();
+        let drops = Arc::new(AtomicUsize::new(0));
+        let defer = || {
+            let ptr = Box::into_raw(Box::new(Counted(drops.clone())));
+            unsafe { handle.pin().defer_destroy(ptr) };
+        };
+
+        for _ in 1..COLLECT_EVERY {
+            defer();
+        }
+        assert_eq!(collector.pending(), COLLECT_EVERY - 1);
+
+        // each collection moves the epoch on by one, and the garbage is freed
+        // two epochs after it was deferred
+        for _ in 0..10 * COLLECT_EVERY {
+            defer();
+        }
+        assert!(collector.pending() <= 3 * COLLECT_EVERY);
+        assert!(drops.load(Ordering::Relaxed) >= 8 * COLLECT_EVERY);
+    }
+
+    #[test]
+    fn dropping_collector_frees_garbage() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        {
+            let collector = Collector::new();
+            let handle = collector.register();
+            let _pinned = collector.register().pin();
+            for _ in 0..10 {
+                let ptr = Box::into_raw(Box::new(Counted(drops.clone())));
+                unsafe { handle.pin().defer_destroy(ptr) };
+            }
+            assert_eq!(drops.load(Ordering::Relaxed), 0);
+        }
+        assert_eq!(drops.load(Ordering::Relaxed), 10);
+    }
+
+    #[test]
+    fn stress() {
+        // readers keep dereferencing a shared pointer that the writer keeps
+        // replacing; freed memory would show up as a bad value
+        const WRITES: usize = if cfg!(miri) { 200 } else { 20_000 };
+        const READERS: usize = 4;
+
+        let collector = Collector::new();
+        let shared = Arc::new(AtomicPtr::new(Box::into_raw(Box::new(0usize))));
+        let done = Arc::new(AtomicBool::new(false));
+        let readers: Vec<_> = (0..READERS)
+            .map(|_| {
+                let collector = collector.clone();
+                let shared = shared.clone();
+                let done = done.clone();
+                thread::spawn(move || {
+                    let handle = collector.register();
+                    let mut last = 0;
+                    while !done.load(Ordering::Acquire) {
+                        let _guard = handle.pin();
+                        let value = unsafe { *shared.load(Ordering::Acquire) };
+                        assert!(value >= last && value <= WRITES);
+                        last = value;
+                    }
+                })
+            })
+            .collect();
+
+        let handle = collector.register();
+        let mut max_pending = 0;
+        for i in 1..=WRITES {
+            let new = Box::into_raw(Box::new(i));
+            let guard = handle.pin();
+            let old = shared.swap(new, Ordering::AcqRel);
+            unsafe { guard.defer_destroy(old) };
+            drop(guard);
+            max_pending = max_pending.max(collector.pending());
+        }
+        done.store(true, Ordering::Release);
+        for reader in readers {
+            reader.join().unwrap();
+        }
+
+        for _ in 0..3 {
+            collector.collect();
+        }
+        assert_eq!(collector.pending(), 0);
+        assert!(max_pending < WRITES / 2, "garbage piled up: {max_pending}");
+        unsafe { drop(Box::from_raw(shared.load(Ordering::Relaxed))) };
+    }
+}
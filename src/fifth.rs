@@ -1,4 +1,18 @@
+use std::marker::PhantomData;
+use std::mem;
 use std::ptr::null_mut;
+#[cfg(feature = "pool")]
+use std::ptr::NonNull;
+
+use crate::guard;
+use crate::no_panic;
+#[cfg(feature = "pool")]
+use crate::pool;
+#[cfg(feature = "serde")]
+use crate::serde_seq::PushVisitor;
+#[cfg(feature = "stats")]
+use crate::stats::Stats;
+use crate::traits::{FromValues, HeapSize, Queue};
 
 type Link<T> = *mut Node<T>;
 
@@ -7,53 +21,316 @@ struct Node<T> {
     next: Link<T>,
 }
 
+// The nodes go through the pool of the thread with the pool feature
 impl<T> Node<T> {
-    fn new(value: T) -> Box<Node<T>> {
-        Box::new(Node {
+    #[cfg(not(feature = "pool"))]
+    fn alloc(value: T) -> *mut Node<T> {
+        no_panic::boxed(Node {
+            value,
+            next: null_mut(),
+        })
+        .as_ptr()
+    }
+
+    #[cfg(feature = "pool")]
+    fn alloc(value: T) -> *mut Node<T> {
+        pool::alloc(Node {
             value,
             next: null_mut(),
         })
+        .as_ptr()
+    }
+
+    #[cfg(not(feature = "pool"))]
+    unsafe fn free(node: *mut Node<T>) -> Node<T> {
+        *Box::from_raw(node)
+    }
+
+    #[cfg(feature = "pool")]
+    unsafe fn free(node: *mut Node<T>) -> Node<T> {
+        pool::free(NonNull::new_unchecked(node))
     }
 }
 
 pub struct List<T> {
     first: Link<T>,
     last: *mut Node<T>,
+    _boo: PhantomData<T>,
+    #[cfg(feature = "stats")]
+    stats: Stats,
 }
 
 impl<T> List<T> {
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         List {
             first: null_mut(),
             last: null_mut(),
+            _boo: PhantomData,
+            #[cfg(feature = "stats")]
+            stats: Stats::new(),
         }
     }
 
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
     pub fn push(&mut self, value: T) {
-        let new_node = Box::into_raw(Node::new(value));
-        let last = self.last;
-        self.last = new_node;
-        if last.is_null() {
-            self.first = new_node;
-        } else {
-            unsafe {
-                (*last).next = new_node;
+        no_panic::check(|| {
+            let new_node = Node::alloc(value);
+            let last = self.last;
+            self.last = new_node;
+            if last.is_null() {
+                self.first = new_node;
+            } else {
+                unsafe {
+                    (*last).next = new_node;
+                }
             }
-        }
+            #[cfg(feature = "stats")]
+            self.stats.allocated();
+            #[cfg(feature = "tracing")]
+            tracing::trace!("push");
+        })
     }
 
     pub fn pop(&mut self) -> Option<T> {
-        let first = self.first;
-        if first.is_null() {
-            None
-        } else {
-            let node = unsafe { Box::from_raw(first) };
-            self.first = node.next;
+        no_panic::check(|| {
             if self.first.is_null() {
-                self.last = null_mut();
+                None
+            } else {
+                Some(unsafe { self.free_first() })
+            }
+        })
+    }
+
+    // Frees the first node, which must exist, and gives back its value
+    unsafe fn free_first(&mut self) -> T {
+        let node = Node::free(self.first);
+        self.first = node.next;
+        if self.first.is_null() {
+            self.last = null_mut();
+        }
+        #[cfg(feature = "stats")]
+        self.stats.freed();
+        #[cfg(feature = "tracing")]
+        tracing::trace!("pop");
+        node.value
+    }
+}
+
+impl<T> List<T> {
+    // Each value is written in the node at the front, which is then moved to
+    // the back, so the nodes left at the front in the end are the ones that
+    // were not needed
+    pub fn recycle_from<I: IntoIterator<Item = T>>(mut old: List<T>, iter: I) -> List<T> {
+        let mut recycled: Link<T> = null_mut();
+        for value in iter {
+            let node = old.first;
+            if node.is_null() || node == recycled {
+                old.push(value);
+                // an empty list has nothing to recycle, but the new nodes
+                // must not be popped at the end either
+                if recycled.is_null() {
+                    recycled = old.last;
+                }
+                continue;
+            }
+            let previous = unsafe {
+                old.first = (*node).next;
+                (*node).next = null_mut();
+                if old.first.is_null() {
+                    old.first = node;
+                } else {
+                    (*old.last).next = node;
+                }
+                old.last = node;
+                mem::replace(&mut (*node).value, value)
+            };
+            if recycled.is_null() {
+                recycled = node;
             }
-            Some(node.value)
+            drop(previous);
+        }
+        while old.first != recycled {
+            old.pop();
+        }
+        old
+    }
+}
+
+// The chain from first is only reachable through the list, and last is just a
+// shortcut to its end, so sending the list sends the values and nothing shared
+unsafe impl<T: Send> Send for List<T> {}
+
+// Dequeues the values
+pub struct IntoIter<T>(List<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop()
+    }
+
+    // The nodes are freed one after the other without going through next, and
+    // the list keeps those not reached yet in case f panics
+    fn fold<B, F: FnMut(B, Self::Item) -> B>(mut self, init: B, mut f: F) -> B {
+        let mut acc = init;
+        while !self.0.first.is_null() {
+            acc = f(acc, unsafe { self.0.free_first() });
+        }
+        acc
+    }
+
+    #[cfg(feature = "nightly")]
+    fn try_fold<B, F, R>(&mut self, init: B, mut f: F) -> R
+    where
+        F: FnMut(B, Self::Item) -> R,
+        R: std::ops::Try<Output = B>,
+    {
+        let mut acc = init;
+        while !self.0.first.is_null() {
+            acc = f(acc, unsafe { self.0.free_first() })?;
+        }
+        R::from_output(acc)
+    }
+}
+
+impl<T> IntoIterator for List<T> {
+    type IntoIter = IntoIter<T>;
+    type Item = T;
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+// Visits the values from the front to the back without dequeuing them
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+}
+
+pub struct IterMut<'a, T> {
+    next: Option<&'a mut Node<T>>,
+}
+
+// Share the values like a &'a T and a &'a mut T would
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
+impl<T> List<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: unsafe { self.first.as_ref() },
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            next: unsafe { self.first.as_mut() },
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a List<T> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut List<T> {
+    type IntoIter = IterMut<'a, T>;
+    type Item = &'a mut T;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        no_panic::check(|| {
+            self.next.map(|node| {
+                self.next = unsafe { node.next.as_ref() };
+                &node.value
+            })
+        })
+    }
+
+    // Adapters such as sum drive the iteration themselves, so the nodes can be
+    // walked without going through next
+    fn fold<B, F: FnMut(B, Self::Item) -> B>(self, init: B, mut f: F) -> B {
+        let mut acc = init;
+        let mut current = self.next;
+        while let Some(node) = current {
+            current = unsafe { node.next.as_ref() };
+            acc = f(acc, &node.value);
         }
+        acc
+    }
+
+    #[cfg(feature = "nightly")]
+    fn try_fold<B, F, R>(&mut self, init: B, mut f: F) -> R
+    where
+        F: FnMut(B, Self::Item) -> R,
+        R: std::ops::Try<Output = B>,
+    {
+        let mut acc = init;
+        while let Some(node) = self.next {
+            self.next = unsafe { node.next.as_ref() };
+            acc = f(acc, &node.value)?;
+        }
+        R::from_output(acc)
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+    fn next(&mut self) -> Option<Self::Item> {
+        no_panic::check(|| {
+            self.next.take().map(|node| {
+                self.next = unsafe { node.next.as_mut() };
+                &mut node.value
+            })
+        })
+    }
+
+    fn fold<B, F: FnMut(B, Self::Item) -> B>(self, init: B, mut f: F) -> B {
+        let mut acc = init;
+        let mut current = self.next;
+        while let Some(node) = current {
+            current = unsafe { node.next.as_mut() };
+            acc = f(acc, &mut node.value);
+        }
+        acc
+    }
+
+    #[cfg(feature = "nightly")]
+    fn try_fold<B, F, R>(&mut self, init: B, mut f: F) -> R
+    where
+        F: FnMut(B, Self::Item) -> R,
+        R: std::ops::Try<Output = B>,
+    {
+        let mut acc = init;
+        while let Some(node) = self.next.take() {
+            self.next = unsafe { node.next.as_mut() };
+            acc = f(acc, &mut node.value)?;
+        }
+        R::from_output(acc)
+    }
+}
+
+impl<T> FromValues<T> for List<T> {
+    fn from_values<I: IntoIterator<Item = T>>(values: I) -> Self {
+        let mut list = List::new();
+        for value in values {
+            list.push(value);
+        }
+        list
     }
 }
 
@@ -63,9 +340,125 @@ impl<T> Default for List<T> {
     }
 }
 
+// The front of std's list is the front of the queue
+impl<T> From<std::collections::LinkedList<T>> for List<T> {
+    fn from(values: std::collections::LinkedList<T>) -> Self {
+        let mut list = List::new();
+        for value in values {
+            list.push(value);
+        }
+        list
+    }
+}
+
+impl<T> From<List<T>> for std::collections::LinkedList<T> {
+    fn from(mut list: List<T>) -> Self {
+        let mut values = std::collections::LinkedList::new();
+        while let Some(value) = list.pop() {
+            values.push_back(value);
+        }
+        values
+    }
+}
+
+impl<T> Queue<T> for List<T> {
+    fn enqueue(&mut self, value: T) {
+        self.push(value);
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop()
+    }
+}
+
+impl<T> HeapSize for List<T> {
+    fn heap_size(&self) -> usize {
+        let mut count = 0;
+        let mut current = self.first;
+        while !current.is_null() {
+            count += 1;
+            current = unsafe { (*current).next };
+        }
+        count * size_of::<Node<T>>()
+    }
+}
+
+// The values are dropped in the order they would be popped, from the front to
+// the back
+impl<T> List<T> {
+    pub fn drop_back_to_front(mut self) {
+        // the links only go towards the back, so they are reversed first
+        let mut prev = null_mut();
+        let mut current = self.first;
+        self.last = current;
+        while !current.is_null() {
+            unsafe {
+                let next = (*current).next;
+                (*current).next = prev;
+                prev = current;
+                current = next;
+            }
+        }
+        self.first = prev;
+        while let Some(value) = self.pop() {
+            drop(value);
+        }
+    }
+}
+
+#[cfg(not(feature = "nightly"))]
 impl<T> Drop for List<T> {
     fn drop(&mut self) {
-        while self.pop().is_some() {}
+        guard::drop_values(self, Self::pop);
+    }
+}
+
+// The values are only dropped, as in sixth, and the phantom data tells the
+// compiler that they are
+#[cfg(feature = "nightly")]
+unsafe impl<#[may_dangle] T> Drop for List<T> {
+    fn drop(&mut self) {
+        guard::drop_values(self, Self::pop);
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for List<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+        // formats like bincode need the length first
+        let mut len = 0;
+        let mut current = self.first;
+        while !current.is_null() {
+            len += 1;
+            current = unsafe { (*current).next };
+        }
+        let mut seq = serializer.serialize_seq(Some(len))?;
+        let mut current = self.first;
+        while !current.is_null() {
+            let node = unsafe { &*current };
+            seq.serialize_element(&node.value)?;
+            current = node.next;
+        }
+        seq.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for List<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(PushVisitor::new(List::push))
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, T: arbitrary::Arbitrary<'a>> arbitrary::Arbitrary<'a> for List<T> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut list = List::new();
+        for value in u.arbitrary_iter()? {
+            list.push(value?);
+        }
+        Ok(list)
     }
 }
 
@@ -109,4 +502,146 @@ mod test {
         assert_eq!(list.pop(), Some(7));
         assert_eq!(list.pop(), None);
     }
+
+    #[test]
+    fn iter() {
+        let mut list = List::new();
+        for v in 1..=5 {
+            list.push(v);
+        }
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.sum::<i32>(), 12);
+        assert_eq!(list.iter().fold(0, |acc, v| acc * 10 + v), 12345);
+        assert!(list.iter().all(|&v| v > 0));
+
+        for v in &mut list {
+            *v *= 2;
+        }
+        let mut iter = list.iter_mut();
+        assert_eq!(iter.position(|v| *v == 4), Some(1));
+        iter.for_each(|v| *v += 1);
+        assert_eq!(
+            (&list).into_iter().copied().collect::<Vec<_>>(),
+            [2, 4, 7, 9, 11]
+        );
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.find(|&v| v > 5), Some(7));
+        assert_eq!(iter.fold(0, |acc, v| acc * 100 + v), 911);
+    }
+
+    #[test]
+    fn recycle_from() {
+        let mut old = List::new();
+        for i in 0..5 {
+            old.push(i);
+        }
+        let mut list = List::recycle_from(old, 10..13);
+        assert_eq!(list.pop(), Some(10));
+        list.push(13);
+        let mut list = List::recycle_from(list, 20..30);
+        for i in 20..30 {
+            assert_eq!(list.pop(), Some(i));
+        }
+        assert_eq!(list.pop(), None);
+
+        list.push(1);
+        let mut list = List::recycle_from(list, [2]);
+        assert_eq!(list.pop(), Some(2));
+        assert_eq!(list.pop(), None);
+
+        list.push(1);
+        let mut list = List::recycle_from(list, 0..0);
+        assert_eq!(list.pop(), None);
+        list.push(2);
+        assert_eq!(list.pop(), Some(2));
+
+        // nothing to recycle
+        let mut list = List::recycle_from(list, 0..3);
+        assert_eq!(list.pop(), Some(0));
+        assert_eq!(list.pop(), Some(1));
+    }
+
+    #[test]
+    fn std_conversions() {
+        let std_list: std::collections::LinkedList<_> = (0..5).collect();
+        let mut list = List::from(std_list);
+        assert_eq!(list.pop(), Some(0));
+        let std_list = std::collections::LinkedList::from(list);
+        assert!(std_list.into_iter().eq(1..5));
+    }
+
+    #[test]
+    fn const_new() {
+        static QUEUE: std::sync::Mutex<List<i32>> = std::sync::Mutex::new(List::new());
+        QUEUE.lock().unwrap().push(1);
+        QUEUE.lock().unwrap().push(2);
+        assert_eq!(QUEUE.lock().unwrap().pop(), Some(1));
+        assert_eq!(QUEUE.lock().unwrap().pop(), Some(2));
+    }
+}
+
+// Run with cargo kani, which also checks that no node is used after it is
+// freed, or freed twice
+#[cfg(kani)]
+mod proofs {
+    use std::ptr::null_mut;
+
+    use super::List;
+
+    const MAX_LEN: usize = 4;
+
+    fn check_links<T>(list: &List<T>) -> usize {
+        let mut len = 0;
+        let mut last = null_mut();
+        let mut cur = list.first;
+        while !cur.is_null() {
+            last = cur;
+            cur = unsafe { (*cur).next };
+            len += 1;
+        }
+        assert!(list.last == last);
+        len
+    }
+
+    #[kani::proof]
+    #[kani::unwind(6)]
+    fn push_and_pop() {
+        // the values are pushed in order, so the model only needs the bounds
+        let (mut popped, mut pushed) = (0u8, 0u8);
+        let mut list = List::new();
+        for _ in 0..MAX_LEN + 1 {
+            if kani::any() && usize::from(pushed - popped) < MAX_LEN {
+                list.push(pushed);
+                pushed += 1;
+            } else if popped < pushed {
+                assert!(list.pop() == Some(popped));
+                popped += 1;
+            } else {
+                assert!(list.pop().is_none());
+            }
+            assert!(check_links(&list) == usize::from(pushed - popped));
+        }
+    }
+
+    #[kani::proof]
+    #[kani::unwind(6)]
+    fn recycle() {
+        let old_len: usize = kani::any();
+        let new_len: usize = kani::any();
+        kani::assume(old_len <= MAX_LEN && new_len <= MAX_LEN);
+        let mut old = List::new();
+        for value in 0..old_len as u8 {
+            old.push(value);
+        }
+        let mut list = List::recycle_from(old, 10..10 + new_len as u8);
+        assert!(check_links(&list) == new_len);
+        for value in 10..10 + new_len as u8 {
+            assert!(list.pop() == Some(value));
+        }
+        assert!(list.pop().is_none());
+    }
 }
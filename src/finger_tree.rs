@@ -0,0 +1,675 @@
+use std::rc::Rc;
+
+// A monoid on the elements of the tree, cached in the inner nodes so that
+// the tree can be searched and split according to it
+pub trait Measure<T> {
+    type Value: Clone;
+    fn zero() -> Self::Value;
+    fn combine(left: &Self::Value, right: &Self::Value) -> Self::Value;
+    fn measure(value: &T) -> Self::Value;
+}
+
+// Turns the tree into an indexed sequence
+pub struct Size;
+
+impl<T> Measure<T> for Size {
+    type Value = usize;
+    fn zero() -> usize {
+        0
+    }
+    fn combine(left: &usize, right: &usize) -> usize {
+        left + right
+    }
+    fn measure(_: &T) -> usize {
+        1
+    }
+}
+
+// Turns the tree into a priority queue
+pub struct Max;
+
+impl<T: Ord + Clone> Measure<T> for Max {
+    type Value = Option<T>;
+    fn zero() -> Option<T> {
+        None
+    }
+    fn combine(left: &Option<T>, right: &Option<T>) -> Option<T> {
+        left.clone().max(right.clone())
+    }
+    fn measure(value: &T) -> Option<T> {
+        Some(value.clone())
+    }
+}
+
+// The nesting depth is not encoded in the types: a tree holds leaves, its
+// middle tree holds branches of leaves, the middle tree of that holds
+// branches of branches, and so on
+enum Node<T, M: Measure<T>> {
+    Leaf(T),
+    // 2 or 3 children
+    Branch(M::Value, Vec<Rc<Node<T, M>>>),
+}
+
+// 1 to 4 nodes, except transiently
+type Digit<T, M> = Vec<Rc<Node<T, M>>>;
+
+// The nodes before, the node at, and the nodes after a split point
+type DigitSplit<T, M> = (Digit<T, M>, Rc<Node<T, M>>, Digit<T, M>);
+
+enum Tree<T, M: Measure<T>> {
+    Empty,
+    Single(Rc<Node<T, M>>),
+    Deep(Rc<Deep<T, M>>),
+}
+
+struct Deep<T, M: Measure<T>> {
+    measure: M::Value,
+    prefix: Digit<T, M>,
+    middle: Tree<T, M>,
+    suffix: Digit<T, M>,
+}
+
+impl<T, M: Measure<T>> Node<T, M> {
+    fn measure(&self) -> M::Value {
+        match self {
+            Node::Leaf(value) => M::measure(value),
+            Node::Branch(measure, _) => measure.clone(),
+        }
+    }
+
+    fn branch(children: Vec<Rc<Node<T, M>>>) -> Rc<Self> {
+        Rc::new(Node::Branch(measure_all(&children), children))
+    }
+
+    fn children(&self) -> Digit<T, M> {
+        match self {
+            Node::Leaf(_) => unreachable!(),
+            Node::Branch(_, children) => children.clone(),
+        }
+    }
+
+    fn first(&self) -> &T {
+        match self {
+            Node::Leaf(value) => value,
+            Node::Branch(_, children) => children[0].first(),
+        }
+    }
+
+    fn last(&self) -> &T {
+        match self {
+            Node::Leaf(value) => value,
+            Node::Branch(_, children) => children[children.len() - 1].last(),
+        }
+    }
+}
+
+fn measure_all<T, M: Measure<T>>(nodes: &[Rc<Node<T, M>>]) -> M::Value {
+    nodes
+        .iter()
+        .fold(M::zero(), |acc, node| M::combine(&acc, &node.measure()))
+}
+
+// Groups 2 or more nodes in branches of 2 or 3
+fn branches<T, M: Measure<T>>(mut nodes: Vec<Rc<Node<T, M>>>) -> Vec<Rc<Node<T, M>>> {
+    let mut branches = Vec::with_capacity(nodes.len() / 2);
+    let mut rest = nodes.len();
+    let mut drain = nodes.drain(..);
+    while rest > 0 {
+        let size = match rest {
+            2 | 4 => 2,
+            _ => 3,
+        };
+        branches.push(Node::branch(drain.by_ref().take(size).collect()));
+        rest -= size;
+    }
+    branches
+}
+
+impl<T, M: Measure<T>> Clone for Tree<T, M> {
+    fn clone(&self) -> Self {
+        match self {
+            Tree::Empty => Tree::Empty,
+            Tree::Single(node) => Tree::Single(node.clone()),
+            Tree::Deep(deep) => Tree::Deep(deep.clone()),
+        }
+    }
+}
+
+impl<T, M: Measure<T>> Tree<T, M> {
+    fn measure(&self) -> M::Value {
+        match self {
+            Tree::Empty => M::zero(),
+            Tree::Single(node) => node.measure(),
+            Tree::Deep(deep) => deep.measure.clone(),
+        }
+    }
+
+    fn deep(prefix: Digit<T, M>, middle: Tree<T, M>, suffix: Digit<T, M>) -> Self {
+        let measure = M::combine(
+            &M::combine(&measure_all(&prefix), &middle.measure()),
+            &measure_all(&suffix),
+        );
+        Tree::Deep(Rc::new(Deep {
+            measure,
+            prefix,
+            middle,
+            suffix,
+        }))
+    }
+
+    fn from_nodes(nodes: Vec<Rc<Node<T, M>>>) -> Self {
+        nodes
+            .into_iter()
+            .fold(Tree::Empty, |tree, node| tree.push_back(node))
+    }
+
+    fn push_front(&self, node: Rc<Node<T, M>>) -> Self {
+        match self {
+            Tree::Empty => Tree::Single(node),
+            Tree::Single(other) => Tree::deep(vec![node], Tree::Empty, vec![other.clone()]),
+            Tree::Deep(deep) => {
+                if deep.prefix.len() == 4 {
+                    let branch = Node::branch(deep.prefix[1..].to_vec());
+                    Tree::deep(
+                        vec![node, deep.prefix[0].clone()],
+                        deep.middle.push_front(branch),
+                        deep.suffix.clone(),
+                    )
+                } else {
+                    let mut prefix = Vec::with_capacity(deep.prefix.len() + 1);
+                    prefix.push(node);
+                    prefix.extend(deep.prefix.iter().cloned());
+                    Tree::deep(prefix, deep.middle.clone(), deep.suffix.clone())
+                }
+            }
+        }
+    }
+
+    fn push_back(&self, node: Rc<Node<T, M>>) -> Self {
+        match self {
+            Tree::Empty => Tree::Single(node),
+            Tree::Single(other) => Tree::deep(vec![other.clone()], Tree::Empty, vec![node]),
+            Tree::Deep(deep) => {
+                if deep.suffix.len() == 4 {
+                    let branch = Node::branch(deep.suffix[..3].to_vec());
+                    Tree::deep(
+                        deep.prefix.clone(),
+                        deep.middle.push_back(branch),
+                        vec![deep.suffix[3].clone(), node],
+                    )
+                } else {
+                    let mut suffix = deep.suffix.clone();
+                    suffix.push(node);
+                    Tree::deep(deep.prefix.clone(), deep.middle.clone(), suffix)
+                }
+            }
+        }
+    }
+
+    fn view_front(&self) -> Option<(Rc<Node<T, M>>, Self)> {
+        match self {
+            Tree::Empty => None,
+            Tree::Single(node) => Some((node.clone(), Tree::Empty)),
+            Tree::Deep(deep) => Some((
+                deep.prefix[0].clone(),
+                Tree::deep_left(deep.prefix[1..].to_vec(), &deep.middle, deep.suffix.clone()),
+            )),
+        }
+    }
+
+    fn view_back(&self) -> Option<(Self, Rc<Node<T, M>>)> {
+        match self {
+            Tree::Empty => None,
+            Tree::Single(node) => Some((Tree::Empty, node.clone())),
+            Tree::Deep(deep) => {
+                let (last, suffix) = deep.suffix.split_last().unwrap();
+                Some((
+                    Tree::deep_right(deep.prefix.clone(), &deep.middle, suffix.to_vec()),
+                    last.clone(),
+                ))
+            }
+        }
+    }
+
+    // Like deep, but the prefix may be empty
+    fn deep_left(prefix: Digit<T, M>, middle: &Self, suffix: Digit<T, M>) -> Self {
+        if !prefix.is_empty() {
+            return Tree::deep(prefix, middle.clone(), suffix);
+        }
+        match middle.view_front() {
+            None => Tree::from_nodes(suffix),
+            Some((branch, middle)) => Tree::deep(branch.children(), middle, suffix),
+        }
+    }
+
+    // Like deep, but the suffix may be empty
+    fn deep_right(prefix: Digit<T, M>, middle: &Self, suffix: Digit<T, M>) -> Self {
+        if !suffix.is_empty() {
+            return Tree::deep(prefix, middle.clone(), suffix);
+        }
+        match middle.view_back() {
+            None => Tree::from_nodes(prefix),
+            Some((middle, branch)) => Tree::deep(prefix, middle, branch.children()),
+        }
+    }
+
+    // Concatenates left, the loose nodes, and right
+    fn concat(left: &Self, nodes: Vec<Rc<Node<T, M>>>, right: &Self) -> Self {
+        match (left, right) {
+            (Tree::Empty, _) => nodes
+                .into_iter()
+                .rev()
+                .fold(right.clone(), |tree, node| tree.push_front(node)),
+            (_, Tree::Empty) => nodes
+                .into_iter()
+                .fold(left.clone(), |tree, node| tree.push_back(node)),
+            (Tree::Single(node), _) => {
+                Tree::concat(&Tree::Empty, nodes, right).push_front(node.clone())
+            }
+            (_, Tree::Single(node)) => {
+                Tree::concat(left, nodes, &Tree::Empty).push_back(node.clone())
+            }
+            (Tree::Deep(left), Tree::Deep(right)) => {
+                let mut inner = left.suffix.clone();
+                inner.extend(nodes);
+                inner.extend(right.prefix.iter().cloned());
+                Tree::deep(
+                    left.prefix.clone(),
+                    Tree::concat(&left.middle, branches(inner), &right.middle),
+                    right.suffix.clone(),
+                )
+            }
+        }
+    }
+
+    // Splits a non-empty tree around the node where the predicate on the
+    // accumulated measure becomes true, given the measure before the tree
+    fn split<P>(&self, predicate: &P, before: &M::Value) -> (Self, Rc<Node<T, M>>, Self)
+    where
+        P: Fn(&M::Value) -> bool,
+    {
+        match self {
+            Tree::Empty => unreachable!(),
+            Tree::Single(node) => (Tree::Empty, node.clone(), Tree::Empty),
+            Tree::Deep(deep) => {
+                let after_prefix = M::combine(before, &measure_all(&deep.prefix));
+                if predicate(&after_prefix) {
+                    let (left, node, right) = split_digit(&deep.prefix, predicate, before);
+                    return (
+                        Tree::from_nodes(left),
+                        node,
+                        Tree::deep_left(right, &deep.middle, deep.suffix.clone()),
+                    );
+                }
+                let after_middle = M::combine(&after_prefix, &deep.middle.measure());
+                if predicate(&after_middle) {
+                    let (middle_left, branch, middle_right) =
+                        deep.middle.split(predicate, &after_prefix);
+                    let before_branch = M::combine(&after_prefix, &middle_left.measure());
+                    let (left, node, right) =
+                        split_digit(&branch.children(), predicate, &before_branch);
+                    return (
+                        Tree::deep_right(deep.prefix.clone(), &middle_left, left),
+                        node,
+                        Tree::deep_left(right, &middle_right, deep.suffix.clone()),
+                    );
+                }
+                let (left, node, right) = split_digit(&deep.suffix, predicate, &after_middle);
+                (
+                    Tree::deep_right(deep.prefix.clone(), &deep.middle, left),
+                    node,
+                    Tree::from_nodes(right),
+                )
+            }
+        }
+    }
+
+    // Like split, but only finds the element
+    fn find<P>(&self, predicate: &P, before: &M::Value) -> Option<&T>
+    where
+        P: Fn(&M::Value) -> bool,
+    {
+        match self {
+            Tree::Empty => None,
+            Tree::Single(node) => find_in_nodes(std::slice::from_ref(node), predicate, before),
+            Tree::Deep(deep) => {
+                let after_prefix = M::combine(before, &measure_all(&deep.prefix));
+                if predicate(&after_prefix) {
+                    return find_in_nodes(&deep.prefix, predicate, before);
+                }
+                let after_middle = M::combine(&after_prefix, &deep.middle.measure());
+                if predicate(&after_middle) {
+                    return deep.middle.find(predicate, &after_prefix);
+                }
+                find_in_nodes(&deep.suffix, predicate, &after_middle)
+            }
+        }
+    }
+}
+
+fn split_digit<T, M, P>(
+    digit: &[Rc<Node<T, M>>],
+    predicate: &P,
+    before: &M::Value,
+) -> DigitSplit<T, M>
+where
+    M: Measure<T>,
+    P: Fn(&M::Value) -> bool,
+{
+    let mut acc = before.clone();
+    for (i, node) in digit.iter().enumerate() {
+        acc = M::combine(&acc, &node.measure());
+        if predicate(&acc) || i == digit.len() - 1 {
+            return (digit[..i].to_vec(), node.clone(), digit[i + 1..].to_vec());
+        }
+    }
+    unreachable!()
+}
+
+fn find_in_nodes<'a, T, M, P>(
+    nodes: &'a [Rc<Node<T, M>>],
+    predicate: &P,
+    before: &M::Value,
+) -> Option<&'a T>
+where
+    M: Measure<T>,
+    P: Fn(&M::Value) -> bool,
+{
+    let mut acc = before.clone();
+    for node in nodes {
+        let after = M::combine(&acc, &node.measure());
+        if predicate(&after) {
+            return match &**node {
+                Node::Leaf(value) => Some(value),
+                Node::Branch(_, children) => find_in_nodes(children, predicate, &acc),
+            };
+        }
+        acc = after;
+    }
+    None
+}
+
+pub struct FingerTree<T, M: Measure<T> = Size> {
+    tree: Tree<T, M>,
+}
+
+impl<T, M: Measure<T>> FingerTree<T, M> {
+    pub fn new() -> Self {
+        FingerTree { tree: Tree::Empty }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        matches!(self.tree, Tree::Empty)
+    }
+
+    pub fn measure(&self) -> M::Value {
+        self.tree.measure()
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        match &self.tree {
+            Tree::Empty => None,
+            Tree::Single(node) => Some(node.first()),
+            Tree::Deep(deep) => Some(deep.prefix[0].first()),
+        }
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        match &self.tree {
+            Tree::Empty => None,
+            Tree::Single(node) => Some(node.last()),
+            Tree::Deep(deep) => Some(deep.suffix[deep.suffix.len() - 1].last()),
+        }
+    }
+
+    pub fn push_front(&self, value: T) -> Self {
+        FingerTree {
+            tree: self.tree.push_front(Rc::new(Node::Leaf(value))),
+        }
+    }
+
+    pub fn push_back(&self, value: T) -> Self {
+        FingerTree {
+            tree: self.tree.push_back(Rc::new(Node::Leaf(value))),
+        }
+    }
+
+    pub fn pop_front(&self) -> Self {
+        match self.tree.view_front() {
+            None => self.clone(),
+            Some((_, tree)) => FingerTree { tree },
+        }
+    }
+
+    pub fn pop_back(&self) -> Self {
+        match self.tree.view_back() {
+            None => self.clone(),
+            Some((tree, _)) => FingerTree { tree },
+        }
+    }
+
+    pub fn concat(&self, other: &Self) -> Self {
+        FingerTree {
+            tree: Tree::concat(&self.tree, Vec::new(), &other.tree),
+        }
+    }
+
+    // The predicate must be monotonic: false on the measure of the first
+    // elements, then true once enough elements are included. The left part
+    // contains the elements before the predicate becomes true.
+    pub fn split<P>(&self, predicate: P) -> (Self, Self)
+    where
+        P: Fn(&M::Value) -> bool,
+    {
+        if self.is_empty() || !predicate(&self.measure()) {
+            return (self.clone(), FingerTree::new());
+        }
+        let (left, node, right) = self.tree.split(&predicate, &M::zero());
+        (
+            FingerTree { tree: left },
+            FingerTree {
+                tree: right.push_front(node),
+            },
+        )
+    }
+
+    // The first element for which the predicate on the measure of the
+    // elements up to it becomes true
+    pub fn find<P>(&self, predicate: P) -> Option<&T>
+    where
+        P: Fn(&M::Value) -> bool,
+    {
+        self.tree.find(&predicate, &M::zero())
+    }
+
+    pub fn iter(&self) -> Iter<'_, T, M> {
+        Iter {
+            stack: vec![Item::Tree(&self.tree)],
+        }
+    }
+}
+
+impl<T> FingerTree<T, Size> {
+    pub fn len(&self) -> usize {
+        self.measure()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.find(|&size| size > index)
+    }
+
+    pub fn split_at(&self, index: usize) -> (Self, Self) {
+        self.split(|&size| size > index)
+    }
+}
+
+impl<T, M: Measure<T>> Default for FingerTree<T, M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, M: Measure<T>> Clone for FingerTree<T, M> {
+    fn clone(&self) -> Self {
+        FingerTree {
+            tree: self.tree.clone(),
+        }
+    }
+}
+
+impl<T, M: Measure<T>> FromIterator<T> for FingerTree<T, M> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        FingerTree {
+            tree: iter.into_iter().fold(Tree::Empty, |tree, value| {
+                tree.push_back(Rc::new(Node::Leaf(value)))
+            }),
+        }
+    }
+}
+
+enum Item<'a, T, M: Measure<T>> {
+    Tree(&'a Tree<T, M>),
+    Node(&'a Node<T, M>),
+}
+
+pub struct Iter<'a, T, M: Measure<T>> {
+    stack: Vec<Item<'a, T, M>>,
+}
+
+impl<'a, T, M: Measure<T>> Iterator for Iter<'a, T, M> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(item) = self.stack.pop() {
+            match item {
+                Item::Tree(Tree::Empty) => {}
+                Item::Tree(Tree::Single(node)) => self.stack.push(Item::Node(node)),
+                Item::Tree(Tree::Deep(deep)) => {
+                    let suffix = deep.suffix.iter().rev().map(|node| Item::Node(node));
+                    self.stack.extend(suffix);
+                    self.stack.push(Item::Tree(&deep.middle));
+                    let prefix = deep.prefix.iter().rev().map(|node| Item::Node(node));
+                    self.stack.extend(prefix);
+                }
+                Item::Node(Node::Leaf(value)) => return Some(value),
+                Item::Node(Node::Branch(_, children)) => {
+                    self.stack
+                        .extend(children.iter().rev().map(|node| Item::Node(node)));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{FingerTree, Max};
+
+    fn values<M: super::Measure<i32>>(tree: &FingerTree<i32, M>) -> Vec<i32> {
+        tree.iter().copied().collect()
+    }
+
+    #[test]
+    fn basics() {
+        let tree: FingerTree<i32> = FingerTree::new();
+        assert!(tree.is_empty());
+        assert_eq!(tree.len(), 0);
+        assert_eq!(tree.front(), None);
+        assert_eq!(tree.back(), None);
+        assert!(tree.pop_front().is_empty());
+
+        let tree = tree.push_back(2).push_back(3).push_front(1);
+        assert_eq!(tree.len(), 3);
+        assert_eq!(tree.front(), Some(&1));
+        assert_eq!(tree.back(), Some(&3));
+        assert_eq!(values(&tree), vec![1, 2, 3]);
+
+        let tree = tree.pop_front();
+        assert_eq!(tree.front(), Some(&2));
+        let tree = tree.pop_back();
+        assert_eq!(tree.back(), Some(&2));
+        let tree = tree.pop_back();
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn ends() {
+        let mut tree: FingerTree<i32> = FingerTree::new();
+        for i in 0..1000 {
+            tree = tree.push_back(i).push_front(-i - 1);
+        }
+        assert_eq!(tree.len(), 2000);
+        assert_eq!(values(&tree), (-1000..1000).collect::<Vec<_>>());
+        for i in 0..1000 {
+            assert_eq!(tree.front(), Some(&(i - 1000)));
+            assert_eq!(tree.back(), Some(&(999 - i)));
+            tree = tree.pop_front().pop_back();
+            assert_eq!(tree.len(), 1998 - 2 * i as usize);
+        }
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn persistence() {
+        let base: FingerTree<i32> = (0..10).collect();
+        let pushed = base.push_back(10);
+        let popped = base.pop_front();
+        assert_eq!(values(&base), (0..10).collect::<Vec<_>>());
+        assert_eq!(values(&pushed), (0..11).collect::<Vec<_>>());
+        assert_eq!(values(&popped), (1..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn indexing() {
+        let tree: FingerTree<i32> = (0..1000).collect();
+        for i in 0..1000 {
+            assert_eq!(tree.get(i), Some(&(i as i32)));
+        }
+        assert_eq!(tree.get(1000), None);
+    }
+
+    #[test]
+    fn split_and_concat() {
+        for len in [0, 1, 2, 5, 9, 30, 100, 1000] {
+            let tree: FingerTree<i32> = (0..len).collect();
+            for at in [0, 1, len / 3, len / 2, len.max(1) - 1, len, len + 1] {
+                let (left, right) = tree.split_at(at as usize);
+                let at = at.min(len);
+                assert_eq!(left.len(), at as usize);
+                assert_eq!(right.len(), (len - at) as usize);
+                assert_eq!(values(&left), (0..at).collect::<Vec<_>>());
+                assert_eq!(values(&right), (at..len).collect::<Vec<_>>());
+                let joined = left.concat(&right);
+                assert_eq!(joined.len(), len as usize);
+                assert_eq!(values(&joined), (0..len).collect::<Vec<_>>());
+            }
+        }
+    }
+
+    #[test]
+    fn concat_many() {
+        let mut tree: FingerTree<i32> = FingerTree::new();
+        for i in 0..200 {
+            let part: FingerTree<i32> = (i * 10..i * 10 + 10).collect();
+            tree = tree.concat(&part);
+        }
+        assert_eq!(tree.len(), 2000);
+        assert_eq!(values(&tree), (0..2000).collect::<Vec<_>>());
+        assert_eq!(tree.get(1234), Some(&1234));
+    }
+
+    #[test]
+    fn priority_queue() {
+        let queue: FingerTree<i32, Max> = [3, 9, 1, 9, 4, 7].into_iter().collect();
+        assert_eq!(queue.measure(), Some(9));
+
+        // take out the first maximum
+        let max = queue.measure();
+        let (left, right) = queue.split(|m| *m >= max);
+        assert_eq!(right.front(), Some(&9));
+        let queue = left.concat(&right.pop_front());
+        assert_eq!(values(&queue), vec![3, 1, 9, 4, 7]);
+        assert_eq!(queue.measure(), Some(9));
+        assert_eq!(queue.find(|m| *m >= Some(5)), Some(&9));
+    }
+}
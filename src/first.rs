@@ -1,3 +1,9 @@
+#[cfg(feature = "futures")]
+use crate::async_iter::IntoStream;
+#[cfg(feature = "stats")]
+use crate::stats::Stats;
+use crate::traits::{FromValues, HeapSize, Stack};
+
 type Link<T> = Option<Box<Node<T>>>;
 
 struct Node<T> {
@@ -7,11 +13,22 @@ struct Node<T> {
 
 pub struct List<T> {
     root: Link<T>,
+    #[cfg(feature = "stats")]
+    stats: Stats,
 }
 
 impl<T> List<T> {
-    pub fn new() -> Self {
-        List { root: None }
+    pub const fn new() -> Self {
+        List {
+            root: None,
+            #[cfg(feature = "stats")]
+            stats: Stats::new(),
+        }
+    }
+
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> Stats {
+        self.stats
     }
 
     pub fn peek(&self) -> Option<&T> {
@@ -27,11 +44,15 @@ impl<T> List<T> {
             value,
             next: self.root.take(),
         }));
+        #[cfg(feature = "stats")]
+        self.stats.allocated();
     }
 
     pub fn pop_front(&mut self) -> Option<T> {
         self.root.take().map(|node| {
             self.root = node.next;
+            #[cfg(feature = "stats")]
+            self.stats.freed();
             node.value
         })
     }
@@ -43,6 +64,22 @@ impl<T> Default for List<T> {
     }
 }
 
+impl<T> Stack<T> for List<T> {
+    fn push(&mut self, value: T) {
+        self.push_front(value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T> HeapSize for List<T> {
+    fn heap_size(&self) -> usize {
+        self.iter().count() * size_of::<Node<T>>()
+    }
+}
+
 impl<T> Drop for List<T> {
     fn drop(&mut self) {
         let mut cur = self.root.take();
@@ -59,6 +96,35 @@ impl<T> Iterator for IntoIter<T> {
     fn next(&mut self) -> Option<Self::Item> {
         self.0.pop_front()
     }
+
+    // The nodes are taken one after the other without going through next, and
+    // the list keeps those not reached yet in case f panics
+    fn fold<B, F: FnMut(B, Self::Item) -> B>(mut self, init: B, mut f: F) -> B {
+        let mut acc = init;
+        while let Some(node) = self.0.root.take() {
+            self.0.root = node.next;
+            #[cfg(feature = "stats")]
+            self.0.stats.freed();
+            acc = f(acc, node.value);
+        }
+        acc
+    }
+
+    #[cfg(feature = "nightly")]
+    fn try_fold<B, F, R>(&mut self, init: B, mut f: F) -> R
+    where
+        F: FnMut(B, Self::Item) -> R,
+        R: std::ops::Try<Output = B>,
+    {
+        let mut acc = init;
+        while let Some(node) = self.0.root.take() {
+            self.0.root = node.next;
+            #[cfg(feature = "stats")]
+            self.0.stats.freed();
+            acc = f(acc, node.value)?;
+        }
+        R::from_output(acc)
+    }
 }
 
 impl<T> IntoIterator for List<T> {
@@ -69,6 +135,28 @@ impl<T> IntoIterator for List<T> {
     }
 }
 
+// The first value ends up at the top, the nodes being linked from there down
+impl<T> FromValues<T> for List<T> {
+    fn from_values<I: IntoIterator<Item = T>>(values: I) -> Self {
+        let mut list = List::new();
+        let mut tail = &mut list.root;
+        for value in values {
+            let node = tail.insert(Box::new(Node { value, next: None }));
+            tail = &mut node.next;
+            #[cfg(feature = "stats")]
+            list.stats.allocated();
+        }
+        list
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T> List<T> {
+    pub fn into_stream(self) -> IntoStream<IntoIter<T>> {
+        IntoStream::new(self.into_iter())
+    }
+}
+
 pub struct IterRef<'a, T> {
     current: Option<&'a Node<T>>,
 }
@@ -84,6 +172,32 @@ impl<'a, T> Iterator for IterRef<'a, T> {
             }
         }
     }
+
+    // Adapters such as sum drive the iteration themselves, so the nodes can be
+    // walked without going through next
+    fn fold<B, F: FnMut(B, Self::Item) -> B>(self, init: B, mut f: F) -> B {
+        let mut acc = init;
+        let mut current = self.current;
+        while let Some(node) = current {
+            acc = f(acc, &node.value);
+            current = node.next.as_deref();
+        }
+        acc
+    }
+
+    #[cfg(feature = "nightly")]
+    fn try_fold<B, F, R>(&mut self, init: B, mut f: F) -> R
+    where
+        F: FnMut(B, Self::Item) -> R,
+        R: std::ops::Try<Output = B>,
+    {
+        let mut acc = init;
+        while let Some(node) = self.current {
+            self.current = node.next.as_deref();
+            acc = f(acc, &node.value)?;
+        }
+        R::from_output(acc)
+    }
 }
 
 impl<T> List<T> {
@@ -109,6 +223,30 @@ impl<'a, T> Iterator for IterMut<'a, T> {
             }
         }
     }
+
+    fn fold<B, F: FnMut(B, Self::Item) -> B>(self, init: B, mut f: F) -> B {
+        let mut acc = init;
+        let mut current = self.current;
+        while let Some(node) = current {
+            acc = f(acc, &mut node.value);
+            current = node.next.as_deref_mut();
+        }
+        acc
+    }
+
+    #[cfg(feature = "nightly")]
+    fn try_fold<B, F, R>(&mut self, init: B, mut f: F) -> R
+    where
+        F: FnMut(B, Self::Item) -> R,
+        R: std::ops::Try<Output = B>,
+    {
+        let mut acc = init;
+        while let Some(node) = self.current.take() {
+            self.current = node.next.as_deref_mut();
+            acc = f(acc, &mut node.value)?;
+        }
+        R::from_output(acc)
+    }
 }
 
 impl<T> List<T> {
@@ -119,6 +257,44 @@ impl<T> List<T> {
     }
 }
 
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for List<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // formats like bincode need the length first
+        use serde::ser::SerializeSeq;
+        let mut seq = serializer.serialize_seq(Some(self.iter().count()))?;
+        for value in self.iter() {
+            seq.serialize_element(value)?;
+        }
+        seq.end()
+    }
+}
+
+// The top of the stack comes first, so the stack has to be built from the
+// last value
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for List<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let values = Vec::<T>::deserialize(deserializer)?;
+        let mut list = List::new();
+        for value in values.into_iter().rev() {
+            list.push_front(value);
+        }
+        Ok(list)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, T: arbitrary::Arbitrary<'a>> arbitrary::Arbitrary<'a> for List<T> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut list = List::new();
+        for value in u.arbitrary_iter()? {
+            list.push_front(value?);
+        }
+        Ok(list)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::List;
@@ -183,4 +359,36 @@ mod test {
         let values: Vec<_> = list.into_iter().collect();
         assert_eq!(values, vec![10, 8, 6, 4, 2]);
     }
+
+    #[test]
+    fn fold() {
+        let mut list = List::new();
+        for v in 1..=5 {
+            list.push_front(v);
+        }
+        assert_eq!(list.iter().sum::<i32>(), 15);
+        assert_eq!(list.iter().fold(0, |acc, v| acc * 10 + v), 54321);
+        let mut iter = list.iter();
+        assert_eq!(iter.find(|&&v| v == 3), Some(&3));
+        assert_eq!(iter.copied().collect::<Vec<_>>(), [2, 1]);
+        assert!(list.iter().all(|&v| v > 0));
+
+        list.iter_mut().for_each(|v| *v *= 2);
+        let mut iter = list.iter_mut();
+        assert_eq!(iter.position(|v| *v == 6), Some(2));
+        assert_eq!(iter.fold(0, |acc, v| acc + *v), 6);
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.find(|&v| v == 8), Some(8));
+        assert_eq!(iter.fold(0, |acc, v| acc * 10 + v), 642);
+    }
+
+    #[test]
+    fn const_new() {
+        const EMPTY: List<i32> = List::new();
+        let mut list = EMPTY;
+        list.push_front(1);
+        assert_eq!(list.peek(), Some(&1));
+        assert_eq!(EMPTY.peek(), None);
+    }
 }
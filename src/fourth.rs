@@ -1,5 +1,16 @@
 use std::cell::{Ref, RefCell, RefMut};
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
+
+#[cfg(feature = "futures")]
+use crate::async_iter::IntoStream;
+#[cfg(feature = "dot")]
+use crate::dot::{self, DotNode};
+use crate::pointer;
+#[cfg(feature = "serde")]
+use crate::serde_seq::PushVisitor;
+#[cfg(feature = "stats")]
+use crate::stats::Stats;
+use crate::traits::{Deque, FromValues, HeapSize, Queue, Stack};
 
 type Link<T> = Option<Rc<RefCell<Node<T>>>>;
 
@@ -22,6 +33,8 @@ impl<T> Node<T> {
 pub struct List<T> {
     first: Link<T>,
     last: Link<T>,
+    #[cfg(feature = "stats")]
+    stats: Stats,
 }
 
 impl<T> List<T> {
@@ -29,9 +42,16 @@ impl<T> List<T> {
         List {
             first: None,
             last: None,
+            #[cfg(feature = "stats")]
+            stats: Stats::new(),
         }
     }
 
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
     pub fn push_front(&mut self, value: T) {
         let new_node = Node::new(value);
         match self.first.take() {
@@ -46,6 +66,10 @@ impl<T> List<T> {
             }
         }
         assert_eq!(Rc::strong_count(self.first.as_ref().unwrap()), 2);
+        #[cfg(feature = "stats")]
+        self.stats.allocated();
+        #[cfg(feature = "tracing")]
+        tracing::trace!("push_front");
     }
 
     pub fn pop_front(&mut self) -> Option<T> {
@@ -63,19 +87,23 @@ impl<T> List<T> {
                         self.first = Some(next);
                     }
                 }
+                #[cfg(feature = "stats")]
+                self.stats.freed();
+                #[cfg(feature = "tracing")]
+                tracing::trace!("pop_front");
                 // unwrap the value
                 Some(Rc::try_unwrap(node).ok().unwrap().into_inner().value)
             }
         }
     }
 
-    pub fn peek_front(&self) -> Option<Ref<T>> {
+    pub fn peek_front(&self) -> Option<Ref<'_, T>> {
         self.first
             .as_ref()
             .map(|node| Ref::map(node.borrow(), |node| &node.value))
     }
 
-    pub fn peek_front_mut(&self) -> Option<RefMut<T>> {
+    pub fn peek_front_mut(&self) -> Option<RefMut<'_, T>> {
         self.first
             .as_ref()
             .map(|node| RefMut::map(node.borrow_mut(), |node| &mut node.value))
@@ -95,6 +123,10 @@ impl<T> List<T> {
             }
         }
         assert_eq!(Rc::strong_count(self.first.as_ref().unwrap()), 2);
+        #[cfg(feature = "stats")]
+        self.stats.allocated();
+        #[cfg(feature = "tracing")]
+        tracing::trace!("push_back");
     }
 
     pub fn pop_back(&mut self) -> Option<T> {
@@ -112,37 +144,129 @@ impl<T> List<T> {
                         self.last = Some(prev);
                     }
                 }
+                #[cfg(feature = "stats")]
+                self.stats.freed();
+                #[cfg(feature = "tracing")]
+                tracing::trace!("pop_back");
                 // unwrap the value
                 Some(Rc::try_unwrap(node).ok().unwrap().into_inner().value)
             }
         }
     }
 
-    pub fn peek_back(&self) -> Option<Ref<T>> {
+    pub fn peek_back(&self) -> Option<Ref<'_, T>> {
         self.last
             .as_ref()
             .map(|node| Ref::map(node.borrow(), |node| &node.value))
     }
 
-    pub fn peek_back_mut(&self) -> Option<RefMut<T>> {
+    pub fn peek_back_mut(&self) -> Option<RefMut<'_, T>> {
         self.last
             .as_ref()
             .map(|node| RefMut::map(node.borrow_mut(), |node| &mut node.value))
     }
 }
 
+#[cfg(feature = "dot")]
+impl<T: std::fmt::Debug> List<T> {
+    pub fn write_dot(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        let id = |node: &Rc<RefCell<Node<T>>>| Rc::as_ptr(node) as usize;
+        let mut current = self.first.clone();
+        let nodes = std::iter::from_fn(move || {
+            let rc = current.take()?;
+            let node = rc.borrow();
+            current = node.next.clone();
+            let dot_node = DotNode {
+                id: id(&rc),
+                label: format!("{:?}", node.value),
+                next: node.next.as_ref().map(id),
+                prev: node.prev.as_ref().map(id),
+            };
+            drop(node);
+            Some(dot_node)
+        });
+        dot::write_graph(
+            w,
+            self.first.as_ref().map(id),
+            self.last.as_ref().map(id),
+            nodes,
+        )
+    }
+
+    pub fn dot(&self) -> String {
+        dot::to_string(|w| self.write_dot(w))
+    }
+}
+
 impl<T> Default for List<T> {
     fn default() -> Self {
         List::new()
     }
 }
 
+impl<T> Stack<T> for List<T> {
+    fn push(&mut self, value: T) {
+        self.push_front(value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T> Queue<T> for List<T> {
+    fn enqueue(&mut self, value: T) {
+        self.push_back(value);
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T> Deque<T> for List<T> {
+    fn push_front(&mut self, value: T) {
+        self.push_front(value);
+    }
+
+    fn push_back(&mut self, value: T) {
+        self.push_back(value);
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        self.pop_back()
+    }
+}
+
+impl<T> HeapSize for List<T> {
+    fn heap_size(&self) -> usize {
+        let mut count = 0;
+        let mut current = self.first.clone();
+        while let Some(node) = current {
+            count += 1;
+            current = node.borrow().next.clone();
+        }
+        count * pointer::allocation_size::<RefCell<Node<T>>>()
+    }
+}
+
+// The values are dropped from the front to the back
 impl<T> Drop for List<T> {
     fn drop(&mut self) {
         while self.pop_front().is_some() {}
     }
 }
 
+impl<T> List<T> {
+    pub fn drop_back_to_front(mut self) {
+        while self.pop_back().is_some() {}
+    }
+}
+
 pub struct IntoIter<T> {
     list: List<T>,
 }
@@ -162,6 +286,39 @@ impl<T> IntoIterator for List<T> {
     }
 }
 
+impl<T> FromValues<T> for List<T> {
+    fn from_values<I: IntoIterator<Item = T>>(values: I) -> Self {
+        let mut list = List::new();
+        for value in values {
+            list.push_back(value);
+        }
+        list
+    }
+}
+
+impl<T> From<std::collections::LinkedList<T>> for List<T> {
+    fn from(values: std::collections::LinkedList<T>) -> Self {
+        let mut list = List::new();
+        for value in values {
+            list.push_back(value);
+        }
+        list
+    }
+}
+
+impl<T> From<List<T>> for std::collections::LinkedList<T> {
+    fn from(list: List<T>) -> Self {
+        list.into_iter().collect()
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T> List<T> {
+    pub fn into_stream(self) -> IntoStream<IntoIter<T>> {
+        IntoStream::new(self.into_iter())
+    }
+}
+
 impl<T> DoubleEndedIterator for IntoIter<T> {
     fn next_back(&mut self) -> Option<Self::Item> {
         self.list.pop_back()
@@ -185,6 +342,84 @@ impl<'a, T> Iterator for Iter<'a, T> {
 }
 */
 
+// Iterates without borrowing the list, so that it can be pushed to and popped
+// from between two steps. Only weak references are kept, to the node last
+// visited and to the next one, so popping them still works; when the next
+// one is gone, the iteration goes on from whatever now follows the last one.
+// If both were popped, the iterator has lost its place, and ends. The values
+// are cloned, since nothing can be borrowed from the nodes between steps.
+pub struct WeakIter<T> {
+    current: Weak<RefCell<Node<T>>>,
+    next: Weak<RefCell<Node<T>>>,
+}
+
+impl<T> List<T> {
+    pub fn weak_iter(&self) -> WeakIter<T> {
+        WeakIter {
+            current: Weak::new(),
+            next: self.first.as_ref().map(Rc::downgrade).unwrap_or_default(),
+        }
+    }
+}
+
+impl<T: Clone> Iterator for WeakIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = match self.next.upgrade() {
+            Some(node) => node,
+            None => self.current.upgrade()?.borrow().next.clone()?,
+        };
+        let node_ref = node.borrow();
+        self.next = node_ref
+            .next
+            .as_ref()
+            .map(Rc::downgrade)
+            .unwrap_or_default();
+        self.current = Rc::downgrade(&node);
+        Some(node_ref.value.clone())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for List<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+        // formats like bincode need the length first
+        let mut len = 0;
+        let mut current = self.first.clone();
+        while let Some(rc) = current {
+            len += 1;
+            current = rc.borrow().next.clone();
+        }
+        let mut seq = serializer.serialize_seq(Some(len))?;
+        let mut current = self.first.clone();
+        while let Some(rc) = current {
+            let node = rc.borrow();
+            seq.serialize_element(&node.value)?;
+            current = node.next.clone();
+        }
+        seq.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for List<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(PushVisitor::new(List::push_back))
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, T: arbitrary::Arbitrary<'a>> arbitrary::Arbitrary<'a> for List<T> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut list = List::new();
+        for value in u.arbitrary_iter()? {
+            list.push_back(value?);
+        }
+        Ok(list)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::List;
@@ -276,4 +511,66 @@ mod test {
         assert_eq!(iter.next_back(), None);
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn weak_iter() {
+        let mut list = List::new();
+        for i in 0..6 {
+            list.push_back(i);
+        }
+        let mut iter = list.weak_iter();
+        assert_eq!(iter.next(), Some(0));
+        // the node the iterator is on can be popped
+        assert_eq!(list.pop_front(), Some(0));
+        assert_eq!(iter.next(), Some(1));
+        // and so can the next one
+        assert_eq!(list.pop_back(), Some(5));
+        assert_eq!(iter.next(), Some(2));
+        list.push_back(6);
+        assert_eq!(list.pop_back(), Some(6));
+        assert_eq!(list.pop_back(), Some(4));
+        assert_eq!(list.pop_back(), Some(3));
+        assert_eq!(iter.next(), None);
+        // values pushed at the back later are still visited
+        list.push_back(7);
+        list.push_front(-1);
+        assert_eq!(iter.collect::<Vec<_>>(), [7]);
+
+        // popping both the current and the next node loses the place
+        let mut iter = list.weak_iter();
+        assert_eq!(iter.next(), Some(-1));
+        list.pop_front();
+        list.pop_front();
+        assert_eq!(iter.next(), None);
+
+        // an iterator over an empty list stays empty
+        let mut empty = List::new();
+        let mut iter = empty.weak_iter();
+        empty.push_back(1);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn std_conversions() {
+        let std_list: std::collections::LinkedList<_> = (0..5).collect();
+        let mut list = List::from(std_list);
+        assert_eq!(list.pop_front(), Some(0));
+        assert_eq!(list.pop_back(), Some(4));
+        let std_list = std::collections::LinkedList::from(list);
+        assert!(std_list.into_iter().eq(1..4));
+    }
+
+    #[cfg(feature = "dot")]
+    #[test]
+    fn dot() {
+        let mut list = List::new();
+        assert!(!list.dot().contains("->"));
+        list.push_back("a");
+        list.push_back("b|c");
+        let dot = list.dot();
+        assert!(dot.starts_with("digraph list {"));
+        assert!(dot.contains("\\\"b\\|c\\\""));
+        assert_eq!(dot.matches("[label=next]").count(), 1);
+        assert_eq!(dot.matches("[label=prev, style=dashed]").count(), 1);
+    }
 }
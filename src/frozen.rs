@@ -0,0 +1,127 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::sixth::LinkedList;
+
+// A list that will not change any more does not need its links: the values
+// are moved once into a single shared allocation, and each clone only bumps
+// the count. It is Send and Sync whenever the values are.
+pub struct FrozenList<T>(Arc<[T]>);
+
+impl<T> FrozenList<T> {
+    pub fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<T: Clone> FrozenList<T> {
+    pub fn to_list(&self) -> LinkedList<T> {
+        self.0.iter().cloned().collect()
+    }
+}
+
+impl<T> LinkedList<T> {
+    pub fn freeze(self) -> FrozenList<T> {
+        FrozenList(self.into_iter().collect())
+    }
+}
+
+impl<T> From<LinkedList<T>> for FrozenList<T> {
+    fn from(list: LinkedList<T>) -> Self {
+        list.freeze()
+    }
+}
+
+impl<T> Clone for FrozenList<T> {
+    fn clone(&self) -> Self {
+        FrozenList(Arc::clone(&self.0))
+    }
+}
+
+impl<T> Deref for FrozenList<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<'a, T> IntoIterator for &'a FrozenList<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<T: PartialEq> PartialEq for FrozenList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: Eq> Eq for FrozenList<T> {}
+
+impl<T: std::hash::Hash> std::hash::Hash for FrozenList<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for FrozenList<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.0.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::thread;
+
+    use super::FrozenList;
+    use crate::sixth::LinkedList;
+
+    #[test]
+    fn basics() {
+        let list: LinkedList<_> = (0..5).collect();
+        let frozen = list.freeze();
+        assert_eq!(frozen.len(), 5);
+        assert_eq!(frozen.first(), Some(&0));
+        assert_eq!(frozen.last(), Some(&4));
+        assert_eq!(frozen.get(2), Some(&2));
+        assert!(frozen.iter().copied().eq(0..5));
+        assert!(frozen.iter().rev().copied().eq((0..5).rev()));
+        assert_eq!(format!("{frozen:?}"), "[0, 1, 2, 3, 4]");
+
+        let mut list = frozen.to_list();
+        list.push_back(5);
+        assert!(list.iter().copied().eq(0..6));
+        assert_eq!(frozen.len(), 5);
+
+        let empty = FrozenList::from(LinkedList::<i32>::new());
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn shared_between_threads() {
+        let list: LinkedList<String> = (0..100).map(|i| i.to_string()).collect();
+        let frozen = list.freeze();
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let frozen = frozen.clone();
+                thread::spawn(move || frozen.iter().map(String::len).sum::<usize>())
+            })
+            .collect();
+        for reader in readers {
+            assert_eq!(reader.join().unwrap(), 190);
+        }
+        let clone = frozen.clone();
+        assert!(clone.ptr_eq(&frozen));
+        assert_eq!(clone, frozen);
+    }
+}
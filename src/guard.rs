@@ -0,0 +1,20 @@
+// Pops the values out of a list and drops them, for the lists that free their
+// nodes by hand. If dropping a value panics, the guard keeps popping while
+// unwinding, so the rest of the nodes are still freed.
+pub(crate) fn drop_values<L, T>(list: &mut L, pop: fn(&mut L) -> Option<T>) {
+    struct Guard<'a, L, T> {
+        list: &'a mut L,
+        pop: fn(&mut L) -> Option<T>,
+    }
+
+    impl<L, T> Drop for Guard<'_, L, T> {
+        fn drop(&mut self) {
+            while (self.pop)(self.list).is_some() {}
+        }
+    }
+
+    let guard = Guard { list, pop };
+    while let Some(value) = (guard.pop)(guard.list) {
+        drop(value);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::sixth::{self, LinkedList, NodeHandle};
+
+// The states go from the oldest to the newest, the current one being somewhere
+// in the middle: the states before it can be restored by undo, and those after
+// it by redo
+pub struct History<T> {
+    states: LinkedList<T>,
+    current: NodeHandle<T>,
+    redo_len: usize,
+    capacity: usize,
+}
+
+impl<T> History<T> {
+    pub fn new(initial: T, capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity should be > 0");
+        let mut states = LinkedList::new();
+        let current = states.push_back_handle(initial);
+        History {
+            states,
+            current,
+            redo_len: 0,
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn current(&self) -> &T {
+        unsafe { self.states.get_handle(self.current) }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.undo_len() > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.redo_len > 0
+    }
+
+    pub fn undo_len(&self) -> usize {
+        self.states.len() - self.redo_len - 1
+    }
+
+    pub fn redo_len(&self) -> usize {
+        self.redo_len
+    }
+
+    // Forgets the states that could be redone, and evicts the oldest state
+    // when over capacity
+    pub fn push(&mut self, state: T) {
+        for _ in 0..self.redo_len {
+            self.states.pop_back();
+        }
+        self.redo_len = 0;
+        self.current = self.states.push_back_handle(state);
+        if self.states.len() > self.capacity {
+            self.states.pop_front();
+        }
+    }
+
+    pub fn undo(&mut self) -> Option<&T> {
+        let prev = unsafe { self.states.prev_handle(self.current)? };
+        self.current = prev;
+        self.redo_len += 1;
+        Some(self.current())
+    }
+
+    pub fn redo(&mut self) -> Option<&T> {
+        let next = unsafe { self.states.next_handle(self.current)? };
+        self.current = next;
+        self.redo_len -= 1;
+        Some(self.current())
+    }
+
+    // All the states, from the oldest to the newest
+    pub fn iter(&self) -> sixth::Iter<'_, T> {
+        self.states.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::History;
+
+    #[test]
+    fn undo_redo() {
+        let mut history = History::new("", 10);
+        assert!(!history.can_undo());
+        assert_eq!(history.undo(), None);
+        history.push("a");
+        history.push("ab");
+        history.push("abc");
+        assert_eq!(history.current(), &"abc");
+        assert_eq!(history.undo_len(), 3);
+
+        assert_eq!(history.undo(), Some(&"ab"));
+        assert_eq!(history.undo(), Some(&"a"));
+        assert!(history.can_redo());
+        assert_eq!(history.redo(), Some(&"ab"));
+        assert_eq!(history.redo(), Some(&"abc"));
+        assert_eq!(history.redo(), None);
+        assert_eq!(history.current(), &"abc");
+    }
+
+    #[test]
+    fn push_truncates_redo() {
+        let mut history = History::new(0, 10);
+        for i in 1..=5 {
+            history.push(i);
+        }
+        history.undo();
+        history.undo();
+        assert_eq!(history.redo_len(), 2);
+        history.push(30);
+        assert!(!history.can_redo());
+        assert_eq!(history.redo(), None);
+        assert_eq!(
+            history.iter().copied().collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 30]
+        );
+        assert_eq!(history.undo(), Some(&3));
+    }
+
+    #[test]
+    fn capacity() {
+        let mut history = History::new(0, 3);
+        for i in 1..=10 {
+            history.push(i);
+        }
+        assert_eq!(history.undo_len(), 2);
+        assert_eq!(history.iter().copied().collect::<Vec<_>>(), vec![8, 9, 10]);
+        assert_eq!(history.undo(), Some(&9));
+        assert_eq!(history.undo(), Some(&8));
+        assert_eq!(history.undo(), None);
+
+        let mut single = History::new('a', 1);
+        single.push('b');
+        assert_eq!(single.current(), &'b');
+        assert_eq!(single.undo(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_capacity() {
+        History::new(0, 0);
+    }
+}
@@ -0,0 +1,239 @@
+use crate::sixth::{CursorMut, LinkedList, NodeHandle};
+
+// Keeps a handle to every stride-th node of the list (0, stride, 2 * stride,
+// ...), so that reaching a position only walks from the closest one before it.
+// The index is built by build_index, and kept up to date by the pushes and
+// pops at the back; any other change drops it, and positions are then reached
+// by walking from the closest end, like in the plain list.
+pub struct IndexedList<T> {
+    list: LinkedList<T>,
+    stride: usize,
+    jumps: Vec<NodeHandle<T>>,
+    indexed: bool,
+}
+
+impl<T> IndexedList<T> {
+    pub fn new(stride: usize) -> Self {
+        Self::from_list(LinkedList::new(), stride)
+    }
+
+    pub fn from_list(list: LinkedList<T>, stride: usize) -> Self {
+        assert!(stride > 0, "stride should be > 0");
+        IndexedList {
+            list,
+            stride,
+            jumps: Vec::new(),
+            indexed: false,
+        }
+    }
+
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    pub fn is_indexed(&self) -> bool {
+        self.indexed
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn list(&self) -> &LinkedList<T> {
+        &self.list
+    }
+
+    pub fn list_mut(&mut self) -> &mut LinkedList<T> {
+        self.drop_index();
+        &mut self.list
+    }
+
+    pub fn into_list(self) -> LinkedList<T> {
+        self.list
+    }
+
+    pub fn build_index(&mut self) {
+        self.jumps.clear();
+        let mut current = self.list.front_handle();
+        let mut i: usize = 0;
+        while let Some(handle) = current {
+            if i.is_multiple_of(self.stride) {
+                self.jumps.push(handle);
+            }
+            current = unsafe { self.list.next_handle(handle) };
+            i += 1;
+        }
+        self.indexed = true;
+    }
+
+    fn drop_index(&mut self) {
+        self.jumps.clear();
+        self.indexed = false;
+    }
+
+    pub fn push_back(&mut self, value: T) {
+        let handle = self.list.push_back_handle(value);
+        if self.indexed && (self.list.len() - 1).is_multiple_of(self.stride) {
+            self.jumps.push(handle);
+        }
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        let value = self.list.pop_back();
+        self.jumps.truncate(self.list.len().div_ceil(self.stride));
+        value
+    }
+
+    fn handle_at(&self, at: usize) -> Option<NodeHandle<T>> {
+        let len = self.list.len();
+        if at >= len {
+            return None;
+        }
+        unsafe {
+            if self.indexed {
+                let mut handle = self.jumps[at / self.stride];
+                for _ in 0..at % self.stride {
+                    handle = self.list.next_handle(handle)?;
+                }
+                Some(handle)
+            } else if at < len / 2 {
+                let mut handle = self.list.front_handle()?;
+                for _ in 0..at {
+                    handle = self.list.next_handle(handle)?;
+                }
+                Some(handle)
+            } else {
+                let mut handle = self.list.back_handle()?;
+                for _ in at + 1..len {
+                    handle = self.list.prev_handle(handle)?;
+                }
+                Some(handle)
+            }
+        }
+    }
+
+    pub fn get(&self, at: usize) -> Option<&T> {
+        let handle = self.handle_at(at)?;
+        unsafe { Some(self.list.get_handle(handle)) }
+    }
+
+    pub fn get_mut(&mut self, at: usize) -> Option<&mut T> {
+        let handle = self.handle_at(at)?;
+        unsafe { Some(self.list.get_handle_mut(handle)) }
+    }
+
+    // A cursor on the value at the position, or on the ghost if there is
+    // none; the cursor may change the list, so the index is dropped
+    pub fn seek_to(&mut self, at: usize) -> CursorMut<'_, T> {
+        let handle = self.handle_at(at);
+        self.drop_index();
+        match handle {
+            Some(handle) => unsafe { self.list.cursor_at_indexed_handle(handle, at) },
+            None => self.list.cursor_mut(),
+        }
+    }
+
+    // Keeps the values before the position, and returns the others
+    pub fn split_off(&mut self, at: usize) -> LinkedList<T> {
+        let len = self.list.len();
+        assert!(
+            at <= len,
+            "split index (is {at}) should be <= len (is {len})"
+        );
+        let tail = if at == 0 {
+            self.list.cursor_mut().split_after()
+        } else {
+            let handle = self.handle_at(at - 1).unwrap();
+            unsafe {
+                self.list
+                    .cursor_at_indexed_handle(handle, at - 1)
+                    .split_after()
+            }
+        };
+        self.jumps.truncate(at.div_ceil(self.stride));
+        tail
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::IndexedList;
+    use crate::sixth::LinkedList;
+
+    fn check(list: &IndexedList<usize>, len: usize) {
+        assert_eq!(list.len(), len);
+        for i in 0..len {
+            assert_eq!(list.get(i), Some(&i));
+        }
+        assert_eq!(list.get(len), None);
+    }
+
+    #[test]
+    fn get() {
+        let mut list = IndexedList::from_list((0..100).collect(), 8);
+        assert!(!list.is_indexed());
+        check(&list, 100);
+        list.build_index();
+        assert!(list.is_indexed());
+        check(&list, 100);
+        *list.get_mut(42).unwrap() = 0;
+        assert_eq!(list.get(42), Some(&0));
+    }
+
+    #[test]
+    fn back_keeps_index() {
+        let mut list = IndexedList::new(4);
+        list.build_index();
+        for i in 0..30 {
+            list.push_back(i);
+            check(&list, i + 1);
+        }
+        for len in (0..30).rev() {
+            assert_eq!(list.pop_back(), Some(len));
+            check(&list, len);
+        }
+        assert!(list.is_indexed());
+        assert_eq!(list.pop_back(), None);
+    }
+
+    #[test]
+    fn other_changes_drop_index() {
+        let mut list = IndexedList::from_list((1..20).collect(), 3);
+        list.build_index();
+        list.list_mut().push_front(0);
+        assert!(!list.is_indexed());
+        check(&list, 20);
+
+        list.build_index();
+        let mut cursor = list.seek_to(10);
+        assert_eq!(cursor.current(), Some(&mut 10));
+        assert_eq!(cursor.index(), Some(10));
+        let tail = cursor.split_after();
+        assert!(!list.is_indexed());
+        check(&list, 11);
+        assert!(tail.into_iter().eq(11..20));
+        assert_eq!(list.seek_to(11).current(), None);
+    }
+
+    #[test]
+    fn split_off() {
+        for stride in [1, 3, 16] {
+            for at in 0..=20 {
+                let mut list = IndexedList::from_list((0..20).collect(), stride);
+                list.build_index();
+                let tail = list.split_off(at);
+                assert!(list.is_indexed());
+                check(&list, at);
+                assert!(tail.into_iter().eq(at..20));
+                list.push_back(at);
+                check(&list, at + 1);
+            }
+        }
+        let mut list: IndexedList<usize> = IndexedList::from_list(LinkedList::new(), 2);
+        assert!(list.split_off(0).is_empty());
+    }
+}
@@ -1,5 +1,131 @@
+// Without the features of the unsafe lists, and the counting allocator of
+// test-utils, the crate has no unsafe code
+#![cfg_attr(
+    not(any(
+        feature = "unsafe-queue",
+        feature = "unsafe-deque",
+        feature = "concurrent",
+        feature = "ring",
+        feature = "static",
+        feature = "static-rc",
+        feature = "test-utils"
+    )),
+    forbid(unsafe_code)
+)]
+// With no-std, the crate is built on core alone, for targets without an
+// allocator; of the lists, only static_list can be built that way
+#![cfg_attr(all(feature = "no-std", not(test)), no_std)]
+#![cfg_attr(feature = "nightly", feature(dropck_eyepatch, try_trait_v2))]
+#![cfg_attr(feature = "allocator-api", feature(allocator_api))]
+
+#[cfg(feature = "unsafe-deque")]
+mod allocator;
+#[cfg(feature = "futures")]
+pub mod async_iter;
+#[cfg(feature = "persistent")]
+pub mod bankers_queue;
+#[cfg(feature = "unsafe-queue")]
+pub mod bounded;
+#[cfg(feature = "unsafe-deque")]
+pub mod branded;
+#[cfg(feature = "unsafe-deque")]
+pub mod byte_list;
+#[cfg(feature = "persistent")]
+pub mod cactus;
+#[cfg(feature = "unsafe-deque")]
+pub mod dlist;
+#[cfg(all(feature = "dot", any(feature = "rc-deque", feature = "unsafe-deque")))]
+mod dot;
+#[cfg(feature = "concurrent")]
+pub mod epoch;
+#[cfg(feature = "unsafe-queue")]
 pub mod fifth;
+#[cfg(feature = "persistent")]
+pub mod finger_tree;
+#[cfg(feature = "first")]
 pub mod first;
+#[cfg(feature = "rc-deque")]
 pub mod fourth;
+#[cfg(feature = "unsafe-deque")]
+pub mod frozen;
+#[cfg(any(
+    feature = "unsafe-queue",
+    feature = "unsafe-deque",
+    feature = "ring",
+    feature = "static-rc"
+))]
+mod guard;
+#[cfg(feature = "unsafe-deque")]
+pub mod history;
+#[cfg(feature = "unsafe-deque")]
+pub mod indexed;
+#[cfg(feature = "unsafe-deque")]
+pub mod linked_hash_map;
+#[cfg(feature = "unsafe-deque")]
+pub mod linked_hash_set;
+#[cfg(feature = "unsafe-deque")]
+pub mod lru;
+mod macros;
+#[cfg(any(feature = "unsafe-queue", feature = "unsafe-deque"))]
+mod no_panic;
+pub mod ops;
+#[cfg(feature = "sorted")]
+pub mod pairing_heap;
+#[cfg(feature = "persistent")]
+pub mod persistent_deque;
+#[cfg(not(feature = "no-std"))]
+pub mod pointer;
+#[cfg(all(
+    feature = "pool",
+    any(feature = "unsafe-queue", feature = "unsafe-deque")
+))]
+pub mod pool;
+pub mod prelude;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "ring")]
+pub mod ring;
+#[cfg(feature = "unsafe-deque")]
+pub mod rope;
+#[cfg(all(
+    feature = "serde",
+    any(
+        feature = "rc-deque",
+        feature = "unsafe-queue",
+        feature = "unsafe-deque"
+    )
+))]
+mod serde_seq;
+#[cfg(feature = "unsafe-deque")]
 pub mod sixth;
+#[cfg(feature = "persistent")]
+pub mod skew_list;
+#[cfg(feature = "unsafe-deque")]
+pub mod small_list;
+#[cfg(feature = "sorted")]
+pub mod sorted;
+#[cfg(feature = "static")]
+pub mod static_list;
+#[cfg(feature = "static-rc")]
+pub mod static_rc;
+#[cfg(feature = "stats")]
+pub mod stats;
+#[cfg(feature = "concurrent")]
+pub mod steal;
+#[cfg(feature = "persistent")]
+pub mod stream;
+#[cfg(feature = "concurrent")]
+pub mod sync_deque;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+#[cfg(feature = "persistent")]
 pub mod third;
+#[cfg(feature = "unsafe-deque")]
+pub mod timer_wheel;
+pub mod traits;
+#[cfg(feature = "first")]
+pub mod two_stack_queue;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use prelude::*;
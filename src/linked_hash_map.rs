@@ -0,0 +1,335 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use crate::sixth::{self, LinkedList, NodeHandle};
+
+// The list keeps the entries in insertion order, and the map points every key
+// to its node in the list
+pub struct LinkedHashMap<K, V> {
+    map: HashMap<K, NodeHandle<(K, V)>>,
+    list: LinkedList<(K, V)>,
+}
+
+impl<K, V> LinkedHashMap<K, V> {
+    pub fn new() -> Self {
+        LinkedHashMap {
+            map: HashMap::new(),
+            list: LinkedList::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn front(&self) -> Option<(&K, &V)> {
+        self.list.front().map(|(key, value)| (key, value))
+    }
+
+    pub fn back(&self) -> Option<(&K, &V)> {
+        self.list.back().map(|(key, value)| (key, value))
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter(self.list.iter())
+    }
+
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> LinkedHashMap<K, V> {
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = *self.map.get(key)?;
+        unsafe { Some(&self.list.get_handle(handle).1) }
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = *self.map.get(key)?;
+        unsafe { Some(&mut self.list.get_handle_mut(handle).1) }
+    }
+
+    // Replacing the value of an existing key keeps its position
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(&handle) = self.map.get(&key) {
+            let entry = unsafe { self.list.get_handle_mut(handle) };
+            return Some(std::mem::replace(&mut entry.1, value));
+        }
+        let handle = self.list.push_back_handle((key.clone(), value));
+        self.map.insert(key, handle);
+        None
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = self.map.remove(key)?;
+        unsafe { Some(self.list.remove_handle(handle)) }
+    }
+
+    // Removes the oldest entry
+    pub fn pop_front(&mut self) -> Option<(K, V)> {
+        let handle = self.list.front_handle()?;
+        Some(self.remove_node(handle))
+    }
+
+    // Removes the newest entry
+    pub fn pop_back(&mut self) -> Option<(K, V)> {
+        let handle = self.list.back_handle()?;
+        Some(self.remove_node(handle))
+    }
+
+    // The key in the list is a clone of the one in the map, and nothing makes
+    // a clone compare equal to the original: when the key does not lead to
+    // the node, its entry is found by the handle, so that the map never keeps
+    // the handle of a freed node
+    fn remove_node(&mut self, handle: NodeHandle<(K, V)>) -> (K, V) {
+        let key = unsafe { &self.list.get_handle(handle).0 };
+        if self.map.get(key) == Some(&handle) {
+            self.map.remove(key);
+        } else {
+            self.map.retain(|_, other| *other != handle);
+        }
+        unsafe { self.list.remove_handle(handle) }
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.list.clear();
+    }
+}
+
+impl<K, V> Default for LinkedHashMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Extend<(K, V)> for LinkedHashMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> FromIterator<(K, V)> for LinkedHashMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = LinkedHashMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Clone for LinkedHashMap<K, V> {
+    fn clone(&self) -> Self {
+        self.iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for LinkedHashMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.list == other.list
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for LinkedHashMap<K, V> {}
+
+impl<K: Debug, V: Debug> Debug for LinkedHashMap<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+pub struct Iter<'a, K, V>(sixth::Iter<'a, (K, V)>);
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(key, value)| (key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(key, value)| (key, value))
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a LinkedHashMap<K, V> {
+    type IntoIter = Iter<'a, K, V>;
+    type Item = (&'a K, &'a V);
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct IntoIter<K, V>(sixth::IntoIter<(K, V)>);
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
+    }
+}
+
+impl<K, V> IntoIterator for LinkedHashMap<K, V> {
+    type IntoIter = IntoIter<K, V>;
+    type Item = (K, V);
+    fn into_iter(self) -> Self::IntoIter {
+        // the handles die with the map
+        IntoIter(self.list.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LinkedHashMap;
+
+    #[test]
+    fn basics() {
+        let mut map = LinkedHashMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.get("a"), None);
+        assert_eq!(map.front(), None);
+
+        assert_eq!(map.insert(String::from("b"), 2), None);
+        assert_eq!(map.insert(String::from("a"), 1), None);
+        assert_eq!(map.insert(String::from("c"), 3), None);
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get("a"), Some(&1));
+        assert!(map.contains_key("c"));
+        assert!(!map.contains_key("d"));
+
+        // replacing keeps the insertion position
+        assert_eq!(map.insert(String::from("b"), 20), Some(2));
+        *map.get_mut("c").unwrap() += 10;
+        assert_eq!(
+            map.iter()
+                .map(|(k, v)| (k.as_str(), *v))
+                .collect::<Vec<_>>(),
+            vec![("b", 20), ("a", 1), ("c", 13)]
+        );
+        assert_eq!(map.front(), Some((&String::from("b"), &20)));
+        assert_eq!(map.back(), Some((&String::from("c"), &13)));
+
+        assert_eq!(map.remove("a"), Some(1));
+        assert_eq!(map.remove("a"), None);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.keys().collect::<Vec<_>>(), vec!["b", "c"]);
+        assert_eq!(map.values().rev().collect::<Vec<_>>(), vec![&13, &20]);
+    }
+
+    #[test]
+    fn pop_ends() {
+        let mut map: LinkedHashMap<_, _> = (0..5).map(|i| (i, i * 10)).collect();
+        assert_eq!(map.pop_front(), Some((0, 0)));
+        assert_eq!(map.pop_back(), Some((4, 40)));
+        assert!(!map.contains_key(&0));
+        assert!(!map.contains_key(&4));
+
+        // a removed key goes to the back when inserted again
+        map.insert(0, 0);
+        assert_eq!(
+            map.into_iter().collect::<Vec<_>>(),
+            vec![(1, 10), (2, 20), (3, 30), (0, 0)]
+        );
+    }
+
+    // A key whose clone is never equal to it
+    #[derive(Debug, PartialEq, Eq, Hash)]
+    struct Fickle(usize);
+
+    impl Clone for Fickle {
+        fn clone(&self) -> Self {
+            Fickle(self.0 + 1000)
+        }
+    }
+
+    #[test]
+    fn pop_ends_with_unequal_clones() {
+        let mut map = LinkedHashMap::new();
+        map.insert(Fickle(1), 'a');
+        map.insert(Fickle(2), 'b');
+        map.insert(Fickle(3), 'c');
+        assert_eq!(map.pop_front(), Some((Fickle(1001), 'a')));
+        assert_eq!(map.pop_back(), Some((Fickle(1003), 'c')));
+        assert_eq!(map.get(&Fickle(1)), None);
+        assert_eq!(map.get(&Fickle(3)), None);
+        assert_eq!(map.map.len(), 1);
+        assert_eq!(map.remove_entry(&Fickle(2)), Some((Fickle(1002), 'b')));
+        assert!(map.map.is_empty());
+    }
+
+    #[test]
+    fn clear() {
+        let mut map: LinkedHashMap<_, _> = (0..5).map(|i| (i, i)).collect();
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.get(&1), None);
+        assert_eq!(map.pop_front(), None);
+        map.insert(1, 1);
+        assert_eq!(map.get(&1), Some(&1));
+    }
+
+    #[test]
+    fn traits() {
+        let map: LinkedHashMap<_, _> = [("x", 1), ("y", 2)].into_iter().collect();
+        let clone = map.clone();
+        assert_eq!(map, clone);
+        assert_eq!(format!("{:?}", map), r#"{"x": 1, "y": 2}"#);
+
+        // same entries in a different order
+        let other: LinkedHashMap<_, _> = [("y", 2), ("x", 1)].into_iter().collect();
+        assert_ne!(map, other);
+    }
+}
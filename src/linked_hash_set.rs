@@ -0,0 +1,324 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use crate::linked_hash_map::{self, LinkedHashMap};
+
+pub struct LinkedHashSet<T> {
+    map: LinkedHashMap<T, ()>,
+}
+
+impl<T> LinkedHashSet<T> {
+    pub fn new() -> Self {
+        LinkedHashSet {
+            map: LinkedHashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.map.front().map(|(value, _)| value)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.map.back().map(|(value, _)| value)
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self.map.iter())
+    }
+}
+
+impl<T: Hash + Eq + Clone> LinkedHashSet<T> {
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(value)
+    }
+
+    // Inserting a value that is already present keeps its position
+    pub fn insert(&mut self, value: T) -> bool {
+        if self.map.contains_key(&value) {
+            false
+        } else {
+            self.map.insert(value, ());
+            true
+        }
+    }
+
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.map.pop_front().map(|(value, _)| value)
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.map.pop_back().map(|(value, _)| value)
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    // Values of self in order, then those only in other in order
+    pub fn union<'a>(&'a self, other: &'a LinkedHashSet<T>) -> Union<'a, T> {
+        Union {
+            first: self.iter(),
+            second: other.iter(),
+            set: self,
+        }
+    }
+
+    // Values of self that are also in other, in the order of self
+    pub fn intersection<'a>(&'a self, other: &'a LinkedHashSet<T>) -> Intersection<'a, T> {
+        Intersection {
+            iter: self.iter(),
+            other,
+        }
+    }
+
+    // Values of self that are not in other, in the order of self
+    pub fn difference<'a>(&'a self, other: &'a LinkedHashSet<T>) -> Difference<'a, T> {
+        Difference {
+            iter: self.iter(),
+            other,
+        }
+    }
+}
+
+impl<T> Default for LinkedHashSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hash + Eq + Clone> Extend<T> for LinkedHashSet<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<T: Hash + Eq + Clone> FromIterator<T> for LinkedHashSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = LinkedHashSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: Hash + Eq + Clone> Clone for LinkedHashSet<T> {
+    fn clone(&self) -> Self {
+        LinkedHashSet {
+            map: self.map.clone(),
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for LinkedHashSet<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<T: Eq> Eq for LinkedHashSet<T> {}
+
+impl<T: Debug> Debug for LinkedHashSet<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+pub struct Iter<'a, T>(linked_hash_map::Iter<'a, T, ()>);
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(value, _)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(value, _)| value)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LinkedHashSet<T> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct IntoIter<T>(linked_hash_map::IntoIter<T, ()>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(value, _)| value)
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(value, _)| value)
+    }
+}
+
+impl<T> IntoIterator for LinkedHashSet<T> {
+    type IntoIter = IntoIter<T>;
+    type Item = T;
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self.map.into_iter())
+    }
+}
+
+pub struct Union<'a, T> {
+    first: Iter<'a, T>,
+    second: Iter<'a, T>,
+    set: &'a LinkedHashSet<T>,
+}
+
+impl<'a, T: Hash + Eq + Clone> Iterator for Union<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.first
+            .next()
+            .or_else(|| self.second.find(|value| !self.set.contains(*value)))
+    }
+}
+
+pub struct Intersection<'a, T> {
+    iter: Iter<'a, T>,
+    other: &'a LinkedHashSet<T>,
+}
+
+impl<'a, T: Hash + Eq + Clone> Iterator for Intersection<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.find(|value| self.other.contains(*value))
+    }
+}
+
+pub struct Difference<'a, T> {
+    iter: Iter<'a, T>,
+    other: &'a LinkedHashSet<T>,
+}
+
+impl<'a, T: Hash + Eq + Clone> Iterator for Difference<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.find(|value| !self.other.contains(*value))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LinkedHashSet;
+
+    #[test]
+    fn basics() {
+        let mut set = LinkedHashSet::new();
+        assert!(set.is_empty());
+        assert!(set.insert(3));
+        assert!(set.insert(1));
+        assert!(set.insert(2));
+        assert!(!set.insert(3));
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(&1));
+        assert!(!set.contains(&4));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![&3, &1, &2]);
+        assert_eq!(set.front(), Some(&3));
+        assert_eq!(set.back(), Some(&2));
+
+        assert!(set.remove(&1));
+        assert!(!set.remove(&1));
+        assert_eq!(set.pop_front(), Some(3));
+        assert_eq!(set.pop_back(), Some(2));
+        assert_eq!(set.pop_back(), None);
+        assert!(set.is_empty());
+    }
+
+    // A value whose clone is never equal to it
+    #[derive(Debug, PartialEq, Eq, Hash)]
+    struct Fickle(usize);
+
+    impl Clone for Fickle {
+        fn clone(&self) -> Self {
+            Fickle(self.0 + 1000)
+        }
+    }
+
+    #[test]
+    fn pop_ends_with_unequal_clones() {
+        let mut set = LinkedHashSet::new();
+        set.insert(Fickle(1));
+        set.insert(Fickle(2));
+        set.insert(Fickle(3));
+        assert_eq!(set.pop_front(), Some(Fickle(1001)));
+        assert_eq!(set.pop_back(), Some(Fickle(1003)));
+        assert!(!set.contains(&Fickle(1)));
+        assert!(!set.remove(&Fickle(3)));
+        assert!(set.remove(&Fickle(2)));
+        assert!(set.is_empty());
+        assert!(set.insert(Fickle(1)));
+    }
+
+    #[test]
+    fn dedup_keeping_order() {
+        let words = ["b", "a", "b", "c", "a", "d"];
+        let set: LinkedHashSet<_> = words.into_iter().collect();
+        assert_eq!(
+            set.into_iter().collect::<Vec<_>>(),
+            vec!["b", "a", "c", "d"]
+        );
+    }
+
+    #[test]
+    fn set_operations() {
+        let a: LinkedHashSet<_> = [5, 1, 4, 2].into_iter().collect();
+        let b: LinkedHashSet<_> = [3, 4, 6, 5].into_iter().collect();
+        assert_eq!(
+            a.union(&b).copied().collect::<Vec<_>>(),
+            vec![5, 1, 4, 2, 3, 6]
+        );
+        assert_eq!(a.intersection(&b).copied().collect::<Vec<_>>(), vec![5, 4]);
+        assert_eq!(b.intersection(&a).copied().collect::<Vec<_>>(), vec![4, 5]);
+        assert_eq!(a.difference(&b).copied().collect::<Vec<_>>(), vec![1, 2]);
+
+        let empty = LinkedHashSet::new();
+        assert_eq!(a.intersection(&empty).count(), 0);
+        assert_eq!(empty.union(&a).count(), 4);
+    }
+
+    #[test]
+    fn traits() {
+        let set: LinkedHashSet<_> = ["x", "y"].into_iter().collect();
+        assert_eq!(set, set.clone());
+        assert_eq!(format!("{:?}", set), r#"{"x", "y"}"#);
+        let other: LinkedHashSet<_> = ["y", "x"].into_iter().collect();
+        assert_ne!(set, other);
+    }
+}
@@ -0,0 +1,240 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::sixth::{LinkedList, NodeHandle};
+
+// The list goes from the most recently used entry to the least recently used
+// one, and the map points every key to its node in the list
+pub struct LruCache<K, V> {
+    map: HashMap<K, NodeHandle<(K, V)>>,
+    list: LinkedList<(K, V)>,
+    capacity: usize,
+}
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        LruCache {
+            map: HashMap::with_capacity(capacity),
+            list: LinkedList::new(),
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = *self.map.get(key)?;
+        unsafe {
+            self.list.move_handle_to_front(handle);
+            Some(&self.list.get_handle(handle).1)
+        }
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = *self.map.get(key)?;
+        unsafe {
+            self.list.move_handle_to_front(handle);
+            Some(&mut self.list.get_handle_mut(handle).1)
+        }
+    }
+
+    // Like get, but without marking the entry as recently used
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = *self.map.get(key)?;
+        unsafe { Some(&self.list.get_handle(handle).1) }
+    }
+
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(&handle) = self.map.get(&key) {
+            unsafe {
+                self.list.move_handle_to_front(handle);
+                let entry = self.list.get_handle_mut(handle);
+                return Some(std::mem::replace(&mut entry.1, value));
+            }
+        }
+        if self.capacity == 0 {
+            return None;
+        }
+        if self.list.len() == self.capacity {
+            self.pop_lru();
+        }
+        let handle = self.list.push_front_handle((key.clone(), value));
+        self.map.insert(key, handle);
+        None
+    }
+
+    pub fn pop<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = self.map.remove(key)?;
+        unsafe { Some(self.list.remove_handle(handle).1) }
+    }
+
+    // The key in the list is a clone of the one in the map, which may not be
+    // equal to it, so the entry is found by the handle when the key misses:
+    // the map must not keep the handle of the freed node
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        let handle = self.list.back_handle()?;
+        let key = unsafe { &self.list.get_handle(handle).0 };
+        if self.map.get(key) == Some(&handle) {
+            self.map.remove(key);
+        } else {
+            self.map.retain(|_, other| *other != handle);
+        }
+        unsafe { Some(self.list.remove_handle(handle)) }
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.list.clear();
+    }
+
+    // From the most recently used entry to the least recently used one
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, &V)> {
+        self.list.iter().map(|(key, value)| (key, value))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LruCache;
+
+    #[test]
+    fn basics() {
+        let mut cache = LruCache::new(2);
+        assert!(cache.is_empty());
+        assert_eq!(cache.get(&1), None);
+
+        assert_eq!(cache.put(1, "one"), None);
+        assert_eq!(cache.put(2, "two"), None);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&1), Some(&"one"));
+
+        // 2 is now the least recently used entry
+        assert_eq!(cache.put(3, "three"), None);
+        assert_eq!(cache.len(), 2);
+        assert!(!cache.contains_key(&2));
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&3), Some(&"three"));
+        assert_eq!(cache.get(&1), Some(&"one"));
+
+        // updating refreshes the entry
+        assert_eq!(cache.put(3, "THREE"), Some("three"));
+        assert_eq!(cache.put(4, "four"), None);
+        assert!(!cache.contains_key(&1));
+        assert_eq!(cache.peek(&3), Some(&"THREE"));
+        assert_eq!(
+            cache.iter().collect::<Vec<_>>(),
+            vec![(&4, &"four"), (&3, &"THREE")]
+        );
+    }
+
+    #[test]
+    fn peek_does_not_refresh() {
+        let mut cache = LruCache::new(2);
+        cache.put("a", 1);
+        cache.put("b", 2);
+        assert_eq!(cache.peek("a"), Some(&1));
+        cache.put("c", 3);
+        assert!(!cache.contains_key("a"));
+        assert!(cache.contains_key("b"));
+    }
+
+    #[test]
+    fn get_mut_and_pop() {
+        let mut cache = LruCache::new(3);
+        cache.put(String::from("a"), 1);
+        cache.put(String::from("b"), 2);
+        cache.put(String::from("c"), 3);
+        *cache.get_mut("a").unwrap() += 10;
+        assert_eq!(cache.pop("b"), Some(2));
+        assert_eq!(cache.pop("b"), None);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.pop_lru(), Some((String::from("c"), 3)));
+        assert_eq!(cache.pop_lru(), Some((String::from("a"), 11)));
+        assert_eq!(cache.pop_lru(), None);
+        assert!(cache.is_empty());
+    }
+
+    // A key whose clone is never equal to it
+    #[derive(Debug, PartialEq, Eq, Hash)]
+    struct Fickle(usize);
+
+    impl Clone for Fickle {
+        fn clone(&self) -> Self {
+            Fickle(self.0 + 1000)
+        }
+    }
+
+    #[test]
+    fn evict_with_unequal_clones() {
+        let mut cache = LruCache::new(1);
+        cache.put(Fickle(1), 'a');
+        cache.put(Fickle(2), 'b');
+        assert_eq!(cache.get(&Fickle(1)), None);
+        assert_eq!(cache.get_mut(&Fickle(1)), None);
+        assert_eq!(cache.peek(&Fickle(1)), None);
+        assert_eq!(cache.map.len(), 1);
+        assert_eq!(cache.pop_lru(), Some((Fickle(1002), 'b')));
+        assert!(cache.map.is_empty());
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let mut cache = LruCache::new(0);
+        assert_eq!(cache.put(1, 1), None);
+        assert_eq!(cache.get(&1), None);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn clear() {
+        let mut cache = LruCache::new(4);
+        for i in 0..10 {
+            cache.put(i, i * i);
+        }
+        assert_eq!(cache.len(), 4);
+        assert_eq!(
+            cache.iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+            vec![9, 8, 7, 6]
+        );
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.get(&9), None);
+        cache.put(1, 1);
+        assert_eq!(cache.get(&1), Some(&1));
+    }
+}
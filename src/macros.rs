@@ -0,0 +1,128 @@
+// Each macro builds its list so that iterating it yields the values in the
+// order they are written
+
+// first::List, the first value being the top of the stack
+#[cfg(feature = "first")]
+#[macro_export]
+macro_rules! list {
+    () => {
+        $crate::first::List::new()
+    };
+    ($value:expr; $n:expr) => {{
+        let mut list = $crate::first::List::new();
+        let value = $value;
+        let n: usize = $n;
+        for _ in 0..n {
+            list.push_front(::core::clone::Clone::clone(&value));
+        }
+        list
+    }};
+    ($($value:expr),+ $(,)?) => {{
+        let mut list = $crate::first::List::new();
+        for value in ::core::iter::IntoIterator::into_iter([$($value),+]).rev() {
+            list.push_front(value);
+        }
+        list
+    }};
+}
+
+// third::List, the first value being the head
+#[cfg(feature = "persistent")]
+#[macro_export]
+macro_rules! plist {
+    () => {
+        $crate::third::List::new()
+    };
+    ($value:expr; $n:expr) => {{
+        let mut list = $crate::third::List::new();
+        let value = $value;
+        let n: usize = $n;
+        for _ in 0..n {
+            list = list.prepend(::core::clone::Clone::clone(&value));
+        }
+        list
+    }};
+    ($($value:expr),+ $(,)?) => {{
+        let mut list = $crate::third::List::new();
+        for value in ::core::iter::IntoIterator::into_iter([$($value),+]).rev() {
+            list = list.prepend(value);
+        }
+        list
+    }};
+}
+
+// sixth::LinkedList, the values being pushed at the back
+#[cfg(feature = "unsafe-deque")]
+#[macro_export]
+macro_rules! deque {
+    () => {
+        $crate::sixth::LinkedList::new()
+    };
+    ($value:expr; $n:expr) => {{
+        let mut list = $crate::sixth::LinkedList::new();
+        let value = $value;
+        let n: usize = $n;
+        for _ in 0..n {
+            list.push_back(::core::clone::Clone::clone(&value));
+        }
+        list
+    }};
+    ($($value:expr),+ $(,)?) => {{
+        let mut list = $crate::sixth::LinkedList::new();
+        $(list.push_back($value);)+
+        list
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "first")]
+    use crate::first;
+    #[cfg(feature = "unsafe-deque")]
+    use crate::sixth;
+    #[cfg(feature = "persistent")]
+    use crate::third;
+
+    #[cfg(feature = "first")]
+    #[test]
+    fn list() {
+        let mut list = list![1, 2, 3];
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![2, 3]);
+
+        let list = list![String::from("a"); 3];
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec!["a", "a", "a"]);
+
+        let list: first::List<i32> = list![];
+        assert_eq!(list.peek(), None);
+        let list = list!['x',];
+        assert_eq!(list.peek(), Some(&'x'));
+    }
+
+    #[cfg(feature = "persistent")]
+    #[test]
+    fn plist() {
+        let list = plist![1, 2, 3];
+        assert_eq!(list.head(), Some(&1));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        let list = plist![0; 4];
+        assert_eq!(list.iter().count(), 4);
+        let list: third::List<i32> = plist![5; 0];
+        assert_eq!(list.head(), None);
+    }
+
+    #[cfg(feature = "unsafe-deque")]
+    #[test]
+    fn deque() {
+        let list = deque![1, 2, 3];
+        assert_eq!(list.front(), Some(&1));
+        assert_eq!(list.back(), Some(&3));
+        assert_eq!(list.len(), 3);
+
+        let list = deque![vec![1]; 2];
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&vec![1], &vec![1]]);
+        let list: sixth::LinkedList<()> = deque![];
+        assert!(list.is_empty());
+    }
+}
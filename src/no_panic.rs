@@ -0,0 +1,63 @@
+// With the no-panic feature, an optimized build fails to link if the compiler
+// cannot prove that f never unwinds: the guard is only dropped while
+// unwinding, and dropping it calls a function that does not exist, so the
+// call has to be optimized away. Unoptimized builds cannot prove anything, so
+// they skip the check.
+#[cfg(all(feature = "no-panic", not(debug_assertions)))]
+#[inline(always)]
+pub(crate) fn check<R>(f: impl FnOnce() -> R) -> R {
+    struct Guard;
+
+    extern "C" {
+        #[link_name = "\n\nerror: a function of fifth or sixth checked by the no-panic feature may panic\n\n"]
+        fn may_panic() -> !;
+    }
+
+    impl Drop for Guard {
+        #[inline(always)]
+        fn drop(&mut self) {
+            unsafe { may_panic() }
+        }
+    }
+
+    let guard = Guard;
+    let result = f();
+    std::mem::forget(guard);
+    result
+}
+
+#[cfg(not(all(feature = "no-panic", not(debug_assertions))))]
+#[inline(always)]
+pub(crate) fn check<R>(f: impl FnOnce() -> R) -> R {
+    f()
+}
+
+// Box::new may panic when out of memory, so with the feature the nodes are
+// allocated through an extern "C" function, which aborts instead of
+// unwinding. They are freed as boxes either way. The pool allocates its own.
+#[cfg(all(feature = "no-panic", not(feature = "pool")))]
+pub(crate) fn boxed<T>(value: T) -> std::ptr::NonNull<T> {
+    use std::alloc::{alloc, handle_alloc_error, Layout};
+
+    extern "C" fn alloc_or_abort(size: usize, align: usize) -> *mut u8 {
+        let layout = unsafe { Layout::from_size_align_unchecked(size, align) };
+        let ptr = unsafe { alloc(layout) };
+        if ptr.is_null() {
+            handle_alloc_error(layout);
+        }
+        ptr
+    }
+
+    let layout = Layout::new::<T>();
+    assert!(layout.size() > 0, "nodes are never zero-sized");
+    let ptr = alloc_or_abort(layout.size(), layout.align()).cast::<T>();
+    unsafe {
+        ptr.write(value);
+        std::ptr::NonNull::new_unchecked(ptr)
+    }
+}
+
+#[cfg(not(any(feature = "no-panic", feature = "pool")))]
+pub(crate) fn boxed<T>(value: T) -> std::ptr::NonNull<T> {
+    std::ptr::NonNull::from(Box::leak(Box::new(value)))
+}
@@ -0,0 +1,111 @@
+use crate::traits::{Deque, Queue, Stack};
+
+// An operation on a list, so that sequences of them can be generated by
+// fuzzers and replayed on any implementation of the traits. Stacks and
+// queues only have one end to push to and one to pop from, so they ignore
+// which end is given.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Op<T> {
+    PushFront(T),
+    PushBack(T),
+    PopFront,
+    PopBack,
+}
+
+impl<T> Op<T> {
+    // Each function returns what popping gave
+    pub fn apply_to_stack(self, stack: &mut impl Stack<T>) -> Option<T> {
+        match self {
+            Op::PushFront(value) | Op::PushBack(value) => {
+                stack.push(value);
+                None
+            }
+            Op::PopFront | Op::PopBack => stack.pop(),
+        }
+    }
+
+    pub fn apply_to_queue(self, queue: &mut impl Queue<T>) -> Option<T> {
+        match self {
+            Op::PushFront(value) | Op::PushBack(value) => {
+                queue.enqueue(value);
+                None
+            }
+            Op::PopFront | Op::PopBack => queue.dequeue(),
+        }
+    }
+
+    pub fn apply_to_deque(self, deque: &mut impl Deque<T>) -> Option<T> {
+        match self {
+            Op::PushFront(value) => {
+                deque.push_front(value);
+                None
+            }
+            Op::PushBack(value) => {
+                deque.push_back(value);
+                None
+            }
+            Op::PopFront => deque.pop_front(),
+            Op::PopBack => deque.pop_back(),
+        }
+    }
+}
+
+#[cfg(all(
+    test,
+    feature = "first",
+    feature = "rc-deque",
+    feature = "unsafe-queue",
+    feature = "unsafe-deque"
+))]
+mod test {
+    use super::Op;
+    use crate::{fifth, first, fourth, sixth};
+
+    fn ops() -> Vec<Op<i32>> {
+        vec![
+            Op::PushBack(1),
+            Op::PushFront(2),
+            Op::PushBack(3),
+            Op::PopBack,
+            Op::PushFront(4),
+            Op::PopFront,
+            Op::PopFront,
+            Op::PopBack,
+            Op::PopBack,
+        ]
+    }
+
+    #[test]
+    fn stack() {
+        let mut list = first::List::new();
+        let popped: Vec<_> = ops()
+            .into_iter()
+            .filter_map(|op| op.apply_to_stack(&mut list))
+            .collect();
+        assert_eq!(popped, vec![3, 4, 2, 1]);
+    }
+
+    #[test]
+    fn queue() {
+        let mut list = fifth::List::new();
+        let popped: Vec<_> = ops()
+            .into_iter()
+            .filter_map(|op| op.apply_to_queue(&mut list))
+            .collect();
+        assert_eq!(popped, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn deque() {
+        let mut list = fourth::List::new();
+        let mut deque = sixth::LinkedList::new();
+        let mut popped = Vec::new();
+        for op in ops() {
+            let value = op.clone().apply_to_deque(&mut list);
+            assert_eq!(op.apply_to_deque(&mut deque), value);
+            popped.extend(value);
+        }
+        assert_eq!(popped, vec![3, 4, 2, 1]);
+    }
+}
@@ -0,0 +1,224 @@
+type Link<T> = Option<Box<Node<T>>>;
+
+struct Node<T> {
+    value: T,
+    child: Link<T>,
+    sibling: Link<T>,
+}
+
+pub struct PairingHeap<T> {
+    root: Link<T>,
+    len: usize,
+}
+
+impl<T> PairingHeap<T> {
+    pub fn new() -> Self {
+        PairingHeap { root: None, len: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn peek_min(&self) -> Option<&T> {
+        self.root.as_ref().map(|node| &node.value)
+    }
+}
+
+impl<T: Ord> PairingHeap<T> {
+    pub fn push(&mut self, value: T) {
+        let node = Box::new(Node {
+            value,
+            child: None,
+            sibling: None,
+        });
+        self.root = Some(match self.root.take() {
+            None => node,
+            Some(root) => meld(root, node),
+        });
+        self.len += 1;
+    }
+
+    pub fn pop_min(&mut self) -> Option<T> {
+        self.root.take().map(|mut root| {
+            self.root = merge_pairs(root.child.take());
+            self.len -= 1;
+            root.value
+        })
+    }
+
+    pub fn merge(&mut self, mut other: PairingHeap<T>) {
+        self.root = match (self.root.take(), other.root.take()) {
+            (Some(a), Some(b)) => Some(meld(a, b)),
+            (a, b) => a.or(b),
+        };
+        self.len += other.len;
+        other.len = 0;
+    }
+}
+
+// Makes the root with the larger value the leftmost child of the other one
+fn meld<T: Ord>(mut a: Box<Node<T>>, mut b: Box<Node<T>>) -> Box<Node<T>> {
+    if b.value < a.value {
+        std::mem::swap(&mut a, &mut b);
+    }
+    b.sibling = a.child.take();
+    a.child = Some(b);
+    a
+}
+
+// The two-pass merge that gives the amortized O(log n) bound: meld the
+// children pairwise from left to right, then meld the pairs from right to left
+fn merge_pairs<T: Ord>(mut children: Link<T>) -> Link<T> {
+    let mut pairs: Link<T> = None;
+    while let Some(mut a) = children {
+        children = a.sibling.take();
+        let mut pair = match children {
+            None => a,
+            Some(mut b) => {
+                children = b.sibling.take();
+                meld(a, b)
+            }
+        };
+        pair.sibling = pairs;
+        pairs = Some(pair);
+    }
+
+    let mut result: Link<T> = None;
+    while let Some(mut pair) = pairs {
+        pairs = pair.sibling.take();
+        result = Some(match result {
+            None => pair,
+            Some(result) => meld(result, pair),
+        });
+    }
+    result
+}
+
+impl<T> Default for PairingHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for PairingHeap<T> {
+    fn drop(&mut self) {
+        let mut stack: Vec<_> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.child.take());
+            stack.extend(node.sibling.take());
+        }
+    }
+}
+
+impl<T: Ord> Extend<T> for PairingHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: Ord> FromIterator<T> for PairingHeap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut heap = PairingHeap::new();
+        heap.extend(iter);
+        heap
+    }
+}
+
+pub struct IntoIter<T>(PairingHeap<T>);
+
+impl<T: Ord> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_min()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T: Ord> IntoIterator for PairingHeap<T> {
+    type IntoIter = IntoIter<T>;
+    type Item = T;
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PairingHeap;
+
+    #[test]
+    fn basics() {
+        let mut heap = PairingHeap::new();
+        assert!(heap.is_empty());
+        assert_eq!(heap.peek_min(), None);
+        assert_eq!(heap.pop_min(), None);
+
+        heap.push(5);
+        heap.push(1);
+        heap.push(8);
+        heap.push(3);
+        heap.push(3);
+        assert_eq!(heap.len(), 5);
+        assert_eq!(heap.peek_min(), Some(&1));
+        assert_eq!(heap.pop_min(), Some(1));
+        assert_eq!(heap.pop_min(), Some(3));
+
+        heap.push(0);
+        heap.push(4);
+        assert_eq!(heap.pop_min(), Some(0));
+        assert_eq!(heap.pop_min(), Some(3));
+        assert_eq!(heap.pop_min(), Some(4));
+        assert_eq!(heap.pop_min(), Some(5));
+        assert_eq!(heap.pop_min(), Some(8));
+        assert_eq!(heap.pop_min(), None);
+        assert_eq!(heap.len(), 0);
+    }
+
+    #[test]
+    fn merge() {
+        let mut heap: PairingHeap<_> = [7, 2, 9].into_iter().collect();
+        heap.merge([4, 1, 8, 6].into_iter().collect());
+        assert_eq!(heap.len(), 7);
+        heap.merge(PairingHeap::new());
+        assert_eq!(heap.len(), 7);
+
+        let mut empty = PairingHeap::new();
+        empty.merge(heap);
+        let values: Vec<_> = empty.into_iter().collect();
+        assert_eq!(values, vec![1, 2, 4, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn sorts() {
+        // a simple LCG is enough to shuffle the input
+        let mut x: u64 = 42;
+        let mut values: Vec<u32> = (0..10_000)
+            .map(|_| {
+                x = x.wrapping_mul(6364136223846793005).wrapping_add(1);
+                (x >> 33) as u32
+            })
+            .collect();
+        let heap: PairingHeap<_> = values.iter().copied().collect();
+        values.sort();
+        assert_eq!(heap.into_iter().collect::<Vec<_>>(), values);
+    }
+
+    #[test]
+    fn long() {
+        // a long chain of children must not overflow the stack on drop
+        let mut heap: PairingHeap<_> = (0..100_000).collect();
+        assert_eq!(heap.pop_min(), Some(0));
+        let heap: PairingHeap<_> = (0..100_000).rev().collect();
+        drop(heap);
+    }
+}
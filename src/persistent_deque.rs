@@ -0,0 +1,225 @@
+use crate::third::List;
+
+// Front elements are kept in order on the front list, back elements in
+// reverse order on the rear list. Neither list may grow more than C times
+// longer than the other; when that happens, the elements are redistributed
+// evenly, which costs O(n) but only after O(n) operations since the last
+// time. Like the banker's queue without laziness, that amortized bound only
+// holds when each version of the deque is used once.
+const C: usize = 3;
+
+pub struct Deque<T> {
+    front: List<T>,
+    front_len: usize,
+    rear: List<T>,
+    rear_len: usize,
+}
+
+impl<T> Deque<T> {
+    pub fn new() -> Self {
+        Deque {
+            front: List::new(),
+            front_len: 0,
+            rear: List::new(),
+            rear_len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.front_len + self.rear_len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // With 2 elements or more, both lists are non-empty; with 1, it can be in
+    // either list
+    pub fn front(&self) -> Option<&T> {
+        self.front.head().or_else(|| self.rear.head())
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.rear.head().or_else(|| self.front.head())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let rear: Vec<_> = self.rear.iter().collect();
+        self.front.iter().chain(rear.into_iter().rev())
+    }
+}
+
+impl<T: Clone> Deque<T> {
+    pub fn push_front(&self, value: T) -> Self {
+        Deque {
+            front: self.front.prepend(value),
+            front_len: self.front_len + 1,
+            rear: self.rear.clone(),
+            rear_len: self.rear_len,
+        }
+        .check()
+    }
+
+    pub fn push_back(&self, value: T) -> Self {
+        Deque {
+            front: self.front.clone(),
+            front_len: self.front_len,
+            rear: self.rear.prepend(value),
+            rear_len: self.rear_len + 1,
+        }
+        .check()
+    }
+
+    pub fn pop_front(&self) -> Self {
+        if self.front_len == 0 {
+            return Deque::new();
+        }
+        Deque {
+            front: self.front.tail(),
+            front_len: self.front_len - 1,
+            rear: self.rear.clone(),
+            rear_len: self.rear_len,
+        }
+        .check()
+    }
+
+    pub fn pop_back(&self) -> Self {
+        if self.rear_len == 0 {
+            return Deque::new();
+        }
+        Deque {
+            front: self.front.clone(),
+            front_len: self.front_len,
+            rear: self.rear.tail(),
+            rear_len: self.rear_len - 1,
+        }
+        .check()
+    }
+
+    fn check(self) -> Self {
+        if self.front_len <= C * self.rear_len + 1 && self.rear_len <= C * self.front_len + 1 {
+            return self;
+        }
+        let values: Vec<&T> = self.iter().collect();
+        let (front_values, rear_values) = values.split_at(values.len() / 2);
+        let mut front = List::new();
+        for value in front_values.iter().rev() {
+            front = front.prepend((*value).clone());
+        }
+        let mut rear = List::new();
+        for value in rear_values {
+            rear = rear.prepend((*value).clone());
+        }
+        Deque {
+            front,
+            front_len: front_values.len(),
+            rear,
+            rear_len: rear_values.len(),
+        }
+    }
+}
+
+impl<T> Default for Deque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Deque<T> {
+    fn clone(&self) -> Self {
+        Deque {
+            front: self.front.clone(),
+            front_len: self.front_len,
+            rear: self.rear.clone(),
+            rear_len: self.rear_len,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Deque;
+    use std::collections::VecDeque;
+
+    #[test]
+    fn basics() {
+        let deque = Deque::new();
+        assert!(deque.is_empty());
+        assert_eq!(deque.front(), None);
+        assert_eq!(deque.back(), None);
+        assert!(deque.pop_front().is_empty());
+        assert!(deque.pop_back().is_empty());
+
+        let deque = deque.push_back(2).push_front(1).push_back(3);
+        assert_eq!(deque.len(), 3);
+        assert_eq!(deque.front(), Some(&1));
+        assert_eq!(deque.back(), Some(&3));
+        assert_eq!(deque.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        let deque = deque.pop_back().pop_back();
+        assert_eq!(deque.front(), Some(&1));
+        assert_eq!(deque.back(), Some(&1));
+        assert!(deque.pop_front().is_empty());
+    }
+
+    #[test]
+    fn one_side() {
+        // all pushes on one end, all pops on the other
+        let mut deque = Deque::new();
+        for i in 0..1000 {
+            deque = deque.push_front(i);
+        }
+        for i in 0..1000 {
+            assert_eq!(deque.back(), Some(&i));
+            deque = deque.pop_back();
+        }
+        assert!(deque.is_empty());
+    }
+
+    #[test]
+    fn persistence() {
+        let base = Deque::new().push_back(1).push_back(2).push_back(3);
+        let a = base.pop_front().push_back(4);
+        let b = base.pop_back().push_front(0);
+        assert_eq!(base.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(a.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert_eq!(b.iter().copied().collect::<Vec<_>>(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn against_vec_deque() {
+        let mut x: u64 = 11;
+        let mut random = |bound: u64| {
+            x = x
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (x >> 33) % bound
+        };
+        let mut deque = Deque::new();
+        let mut expected = VecDeque::new();
+        for i in 0..10_000 {
+            match random(5) {
+                0 => {
+                    deque = deque.push_front(i);
+                    expected.push_front(i);
+                }
+                1 | 2 => {
+                    deque = deque.push_back(i);
+                    expected.push_back(i);
+                }
+                3 => {
+                    deque = deque.pop_front();
+                    expected.pop_front();
+                }
+                _ => {
+                    deque = deque.pop_back();
+                    expected.pop_back();
+                }
+            }
+            assert_eq!(deque.len(), expected.len());
+            assert_eq!(deque.front(), expected.front());
+            assert_eq!(deque.back(), expected.back());
+        }
+        assert!(deque.iter().eq(expected.iter()));
+    }
+}
@@ -0,0 +1,63 @@
+use std::ops::Deref;
+use std::rc::Rc;
+use std::sync::Arc;
+
+// Lets a type choose its shared pointer through a parameter, instead of being
+// written once for Rc and once again for Arc
+pub trait PointerFamily {
+    type Pointer<T>: Deref<Target = T> + Clone;
+
+    fn new<T>(value: T) -> Self::Pointer<T>;
+
+    // Gives the value back when this is the last pointer to it
+    fn try_unwrap<T>(pointer: Self::Pointer<T>) -> Result<T, Self::Pointer<T>>;
+
+    fn strong_count<T>(pointer: &Self::Pointer<T>) -> usize;
+}
+
+// The size of the allocation behind an Rc or an Arc, where the strong and
+// weak counts come before the value
+#[cfg(any(feature = "persistent", feature = "rc-deque"))]
+pub(crate) fn allocation_size<T>() -> usize {
+    use std::alloc::Layout;
+    let (layout, _) = Layout::new::<[usize; 2]>()
+        .extend(Layout::new::<T>())
+        .unwrap();
+    layout.pad_to_align().size()
+}
+
+pub struct RcFamily;
+
+impl PointerFamily for RcFamily {
+    type Pointer<T> = Rc<T>;
+
+    fn new<T>(value: T) -> Rc<T> {
+        Rc::new(value)
+    }
+
+    fn try_unwrap<T>(pointer: Rc<T>) -> Result<T, Rc<T>> {
+        Rc::try_unwrap(pointer)
+    }
+
+    fn strong_count<T>(pointer: &Rc<T>) -> usize {
+        Rc::strong_count(pointer)
+    }
+}
+
+pub struct ArcFamily;
+
+impl PointerFamily for ArcFamily {
+    type Pointer<T> = Arc<T>;
+
+    fn new<T>(value: T) -> Arc<T> {
+        Arc::new(value)
+    }
+
+    fn try_unwrap<T>(pointer: Arc<T>) -> Result<T, Arc<T>> {
+        Arc::try_unwrap(pointer)
+    }
+
+    fn strong_count<T>(pointer: &Arc<T>) -> usize {
+        Arc::strong_count(pointer)
+    }
+}
@@ -0,0 +1,129 @@
+use std::alloc::{self, Layout};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ptr::NonNull;
+
+// The most blocks kept for each layout, so that a thread that once had many
+// nodes does not hold on to all of their memory
+const MAX_BLOCKS: usize = 4096;
+
+// The blocks freed by the lists of a thread, by layout, ready to be reused by
+// the next nodes of the same layout, whatever list or type they are for. The
+// blocks come from the global allocator, so a node allocated on a thread and
+// freed on another simply goes to the pool of the latter.
+struct Pool(HashMap<Layout, Vec<NonNull<u8>>>);
+
+impl Pool {
+    fn drain(&mut self) -> usize {
+        let mut count = 0;
+        for (layout, blocks) in self.0.drain() {
+            count += blocks.len();
+            for block in blocks {
+                unsafe { alloc::dealloc(block.as_ptr(), layout) };
+            }
+        }
+        count
+    }
+}
+
+impl Drop for Pool {
+    fn drop(&mut self) {
+        self.drain();
+    }
+}
+
+thread_local! {
+    static POOL: RefCell<Pool> = RefCell::new(Pool(HashMap::new()));
+}
+
+// Moves the value to a block from the pool, or to a new one
+pub(crate) fn alloc<T>(value: T) -> NonNull<T> {
+    let layout = Layout::new::<T>();
+    assert!(layout.size() > 0, "nodes are never zero-sized");
+    // the pool may be gone while the thread is exiting
+    let block = POOL
+        .try_with(|pool| pool.borrow_mut().0.get_mut(&layout)?.pop())
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| {
+            let block = unsafe { alloc::alloc(layout) };
+            NonNull::new(block).unwrap_or_else(|| alloc::handle_alloc_error(layout))
+        });
+    let ptr = block.cast::<T>();
+    unsafe { ptr.as_ptr().write(value) };
+    ptr
+}
+
+// The pointer must come from alloc, or from a Box, and not be used after
+pub(crate) unsafe fn free<T>(ptr: NonNull<T>) -> T {
+    let value = ptr.as_ptr().read();
+    let layout = Layout::new::<T>();
+    let block = ptr.cast::<u8>();
+    let kept = POOL
+        .try_with(|pool| {
+            let mut pool = pool.borrow_mut();
+            let blocks = pool.0.entry(layout).or_default();
+            if blocks.len() < MAX_BLOCKS {
+                blocks.push(block);
+                true
+            } else {
+                false
+            }
+        })
+        .unwrap_or(false);
+    if !kept {
+        alloc::dealloc(block.as_ptr(), layout);
+    }
+    value
+}
+
+// The number of blocks in the pool of the current thread
+pub fn pooled() -> usize {
+    POOL.with(|pool| pool.borrow().0.values().map(Vec::len).sum())
+}
+
+// Gives the blocks in the pool of the current thread back to the allocator,
+// and returns how many there were
+pub fn drain_pool() -> usize {
+    POOL.with(|pool| pool.borrow_mut().drain())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{alloc, drain_pool, free, pooled, MAX_BLOCKS};
+
+    #[test]
+    fn reuse() {
+        drain_pool();
+        let a = alloc(1u64);
+        let b = alloc(2u64);
+        assert_eq!(pooled(), 0);
+        assert_eq!(unsafe { free(a) }, 1);
+        assert_eq!(pooled(), 1);
+        // a block of another layout is not reused
+        let c = alloc(3u32);
+        assert_eq!(pooled(), 1);
+        let d = alloc(4u64);
+        assert_eq!(d, a);
+        assert_eq!(pooled(), 0);
+        unsafe {
+            free(b);
+            free(c);
+            free(d);
+        }
+        assert_eq!(pooled(), 3);
+        assert_eq!(drain_pool(), 3);
+        assert_eq!(pooled(), 0);
+    }
+
+    #[test]
+    fn bounded() {
+        drain_pool();
+        let blocks: Vec<_> = (0..MAX_BLOCKS + 10).map(|i| alloc([i; 3])).collect();
+        for block in blocks {
+            unsafe { free(block) };
+        }
+        assert_eq!(pooled(), MAX_BLOCKS);
+        assert_eq!(drain_pool(), MAX_BLOCKS);
+    }
+}
@@ -0,0 +1,69 @@
+// The lists are all called List in their own modules; this gives each one a
+// name saying what it is, so that they can be imported together. The traits
+// are only brought in scope for their methods, since their names are taken.
+
+#[cfg(feature = "unsafe-queue")]
+pub use crate::fifth::List as UnsafeQueue;
+#[cfg(feature = "first")]
+pub use crate::first::List as Stack;
+#[cfg(feature = "rc-deque")]
+pub use crate::fourth::List as RcDeque;
+#[cfg(feature = "unsafe-deque")]
+pub use crate::sixth::LinkedList as Deque;
+#[cfg(feature = "sorted")]
+pub use crate::sorted::List as SortedList;
+#[cfg(feature = "persistent")]
+pub use crate::third::SyncList as SyncPersistentList;
+
+// An alias rather than a re-export, so that the Rc sharing is picked without
+// naming it, as it is for SyncPersistentList
+#[cfg(feature = "persistent")]
+pub type PersistentList<T> = crate::third::List<T>;
+
+pub use crate::traits::{Deque as _, HeapSize as _, Queue as _, SortedList as _, Stack as _};
+pub use crate::traits::{FromList, FromValues, IntoList};
+
+#[cfg(all(
+    test,
+    feature = "first",
+    feature = "persistent",
+    feature = "rc-deque",
+    feature = "unsafe-queue",
+    feature = "unsafe-deque",
+    feature = "sorted"
+))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn names() {
+        let mut stack = Stack::new();
+        let mut queue = UnsafeQueue::new();
+        let mut deque = Deque::new();
+        let mut rc_deque = RcDeque::new();
+        for i in 0..3 {
+            stack.push_front(i);
+            queue.push(i);
+            deque.push_back(i);
+            rc_deque.push_back(i);
+        }
+        assert_eq!(stack.pop_front(), Some(2));
+        assert_eq!(queue.pop(), Some(0));
+        assert_eq!(deque.pop_back(), Some(2));
+        assert_eq!(rc_deque.pop_front(), Some(0));
+
+        let list = PersistentList::new().prepend(1);
+        assert_eq!(list.head(), Some(&1));
+    }
+
+    #[test]
+    fn trait_methods() {
+        let mut stack = Stack::new();
+        stack.push(1);
+        let mut queue = UnsafeQueue::new();
+        queue.enqueue(1);
+        let mut sorted = SortedList::new();
+        sorted.insert(1);
+        assert_eq!(stack.pop(), queue.dequeue());
+    }
+}
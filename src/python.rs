@@ -0,0 +1,285 @@
+use pyo3::exceptions::PyIndexError;
+use pyo3::prelude::*;
+
+use crate::sixth::LinkedList;
+use crate::third::SyncList;
+
+// sixth, as a mutable deque. Python keeps a reference to each value pushed, so
+// the values are shared with Python rather than copied.
+#[pyclass(name = "Deque", module = "rust_too_many_lists", sequence)]
+#[derive(Default)]
+pub struct PyDeque {
+    list: LinkedList<PyObject>,
+}
+
+#[pymethods]
+impl PyDeque {
+    #[new]
+    #[pyo3(signature = (values = None))]
+    fn new(values: Option<&Bound<'_, PyAny>>) -> PyResult<Self> {
+        let mut list = LinkedList::new();
+        if let Some(values) = values {
+            for value in values.try_iter()? {
+                list.push_back(value?.unbind());
+            }
+        }
+        Ok(PyDeque { list })
+    }
+
+    fn push_front(&mut self, value: PyObject) {
+        self.list.push_front(value);
+    }
+
+    fn push_back(&mut self, value: PyObject) {
+        self.list.push_back(value);
+    }
+
+    fn pop_front(&mut self) -> PyResult<PyObject> {
+        self.list
+            .pop_front()
+            .ok_or_else(|| PyIndexError::new_err("pop from an empty Deque"))
+    }
+
+    fn pop_back(&mut self) -> PyResult<PyObject> {
+        self.list
+            .pop_back()
+            .ok_or_else(|| PyIndexError::new_err("pop from an empty Deque"))
+    }
+
+    fn __len__(&self) -> usize {
+        self.list.len()
+    }
+
+    // The iterator walks a snapshot, so that the deque can be changed while
+    // it is iterated without invalidating anything
+    fn __iter__(&self, py: Python<'_>) -> DequeIter {
+        let values: Vec<_> = self.list.iter().map(|value| value.clone_ref(py)).collect();
+        DequeIter {
+            values: values.into_iter(),
+        }
+    }
+
+    fn __eq__(&self, other: &Self, py: Python<'_>) -> PyResult<bool> {
+        values_eq(
+            py,
+            self.list.iter(),
+            self.list.len(),
+            other.list.iter(),
+            other.list.len(),
+        )
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        repr(py, "Deque", self.list.iter())
+    }
+}
+
+#[pyclass(module = "rust_too_many_lists")]
+pub struct DequeIter {
+    values: std::vec::IntoIter<PyObject>,
+}
+
+#[pymethods]
+impl DequeIter {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> Option<PyObject> {
+        self.values.next()
+    }
+}
+
+// third, shared between threads since Python may hand it to any of them. Each
+// prepend and tail gives a new list sharing the nodes of this one.
+#[pyclass(name = "PersistentList", module = "rust_too_many_lists", frozen)]
+#[derive(Clone)]
+pub struct PyPersistentList {
+    list: SyncList<PyObject>,
+}
+
+#[pymethods]
+impl PyPersistentList {
+    #[new]
+    fn new() -> Self {
+        PyPersistentList {
+            list: SyncList::default(),
+        }
+    }
+
+    fn prepend(&self, value: PyObject) -> Self {
+        PyPersistentList {
+            list: self.list.prepend(value),
+        }
+    }
+
+    fn head(&self, py: Python<'_>) -> Option<PyObject> {
+        self.list.head().map(|value| value.clone_ref(py))
+    }
+
+    fn tail(&self) -> Self {
+        PyPersistentList {
+            list: self.list.tail(),
+        }
+    }
+
+    fn __len__(&self) -> usize {
+        self.list.iter().count()
+    }
+
+    fn __bool__(&self) -> bool {
+        self.list.head().is_some()
+    }
+
+    // Iterating only takes the tail at each step, without copying anything
+    fn __iter__(&self) -> PersistentListIter {
+        PersistentListIter {
+            list: self.list.clone(),
+        }
+    }
+
+    fn __eq__(&self, other: &Self, py: Python<'_>) -> PyResult<bool> {
+        let len = self.__len__();
+        values_eq(
+            py,
+            self.list.iter(),
+            len,
+            other.list.iter(),
+            other.__len__(),
+        )
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        repr(py, "PersistentList", self.list.iter())
+    }
+}
+
+#[pyclass(module = "rust_too_many_lists")]
+pub struct PersistentListIter {
+    list: SyncList<PyObject>,
+}
+
+#[pymethods]
+impl PersistentListIter {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> Option<PyObject> {
+        let value = self.list.head()?.clone_ref(py);
+        self.list = self.list.tail();
+        Some(value)
+    }
+}
+
+// Compares the values with Python's ==, like two Python lists would
+fn values_eq<'a>(
+    py: Python<'_>,
+    a: impl Iterator<Item = &'a PyObject>,
+    a_len: usize,
+    mut b: impl Iterator<Item = &'a PyObject>,
+    b_len: usize,
+) -> PyResult<bool> {
+    if a_len != b_len {
+        return Ok(false);
+    }
+    for x in a {
+        let y = b.next().unwrap();
+        if !x.bind(py).eq(y)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn repr<'a>(
+    py: Python<'_>,
+    name: &str,
+    values: impl Iterator<Item = &'a PyObject>,
+) -> PyResult<String> {
+    let values = values
+        .map(|value| Ok(value.bind(py).repr()?.to_string()))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(format!("{name}([{}])", values.join(", ")))
+}
+
+#[pymodule]
+fn rust_too_many_lists(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyDeque>()?;
+    m.add_class::<PyPersistentList>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use pyo3::ffi::c_str;
+    use pyo3::prelude::*;
+    use pyo3::types::PyDict;
+
+    use super::rust_too_many_lists;
+
+    fn run(code: &std::ffi::CStr) {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "rust_too_many_lists").unwrap();
+            rust_too_many_lists(&module).unwrap();
+            let globals = PyDict::new(py);
+            globals.set_item("lists", module).unwrap();
+            if let Err(err) = py.run(code, Some(&globals), None) {
+                err.print(py);
+                panic!("the Python code failed");
+            }
+        });
+    }
+
+    #[test]
+    fn deque() {
+        run(c_str!(
+            r#"
+d = lists.Deque([2, 3])
+d.push_front(1)
+d.push_back("four")
+assert len(d) == 4
+assert list(d) == [1, 2, 3, "four"]
+assert repr(d) == "Deque([1, 2, 3, 'four'])"
+assert d == lists.Deque([1, 2, 3, "four"])
+assert d != lists.Deque([1, 2, 3])
+assert d != [1, 2, 3, "four"]
+# changing the deque does not affect an iterator already started
+it = iter(d)
+assert d.pop_front() == 1
+assert d.pop_back() == "four"
+assert list(it) == [1, 2, 3, "four"]
+assert list(d) == [2, 3]
+d.pop_back()
+d.pop_back()
+try:
+    d.pop_front()
+    assert False
+except IndexError:
+    pass
+"#
+        ));
+    }
+
+    #[test]
+    fn persistent_list() {
+        run(c_str!(
+            r#"
+empty = lists.PersistentList()
+assert not empty and len(empty) == 0 and empty.head() is None
+a = empty.prepend(3).prepend(2)
+b = a.prepend(1)
+c = a.prepend(0)
+assert list(b) == [1, 2, 3]
+assert list(c) == [0, 2, 3]
+assert list(a) == [2, 3]
+assert b.tail() == a == c.tail()
+assert b != c
+assert b.head() == 1 and len(b) == 3
+assert repr(b) == "PersistentList([1, 2, 3])"
+assert [x for x in b.tail().tail().tail()] == []
+"#
+        ));
+    }
+}
@@ -0,0 +1,322 @@
+use std::marker::PhantomData;
+use std::ptr::null_mut;
+
+use crate::guard;
+
+type Link<T> = *mut Node<T>;
+
+struct Node<T> {
+    value: T,
+    next: Link<T>,
+}
+
+// The nodes form a cycle; we keep a pointer to the node before the head so
+// that the head can be removed without walking around the ring
+pub struct Ring<T> {
+    tail: Link<T>,
+    len: usize,
+    _boo: PhantomData<T>,
+}
+
+impl<T> Ring<T> {
+    pub fn new() -> Self {
+        Ring {
+            tail: null_mut(),
+            len: 0,
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn head(&self) -> Option<&T> {
+        unsafe { self.head_ptr().as_ref().map(|node| &node.value) }
+    }
+
+    pub fn head_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.head_ptr().as_mut().map(|node| &mut node.value) }
+    }
+
+    // Moves the head to the next node
+    pub fn rotate(&mut self) {
+        if !self.tail.is_null() {
+            unsafe {
+                self.tail = (*self.tail).next;
+            }
+        }
+    }
+
+    pub fn insert_after_head(&mut self, value: T) {
+        let head = self.head_ptr();
+        if head.is_null() {
+            self.tail = Self::singleton(value);
+        } else {
+            unsafe {
+                let node = Box::into_raw(Box::new(Node {
+                    value,
+                    next: (*head).next,
+                }));
+                (*head).next = node;
+                if head == self.tail {
+                    self.tail = node;
+                }
+            }
+        }
+        self.len += 1;
+    }
+
+    // Inserts the value just before the head, so that it is reached last when
+    // rotating around the ring
+    pub fn push_back(&mut self, value: T) {
+        if self.tail.is_null() {
+            self.tail = Self::singleton(value);
+        } else {
+            unsafe {
+                let node = Box::into_raw(Box::new(Node {
+                    value,
+                    next: (*self.tail).next,
+                }));
+                (*self.tail).next = node;
+                self.tail = node;
+            }
+        }
+        self.len += 1;
+    }
+
+    // Removes the head; the next node becomes the head
+    pub fn remove_head(&mut self) -> Option<T> {
+        if self.tail.is_null() {
+            return None;
+        }
+        unsafe {
+            let head = Box::from_raw((*self.tail).next);
+            if self.len == 1 {
+                self.tail = null_mut();
+            } else {
+                (*self.tail).next = head.next;
+            }
+            self.len -= 1;
+            Some(head.value)
+        }
+    }
+
+    // One lap around the ring, starting at the head
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head_ptr(),
+            remaining: self.len,
+            _boo: PhantomData,
+        }
+    }
+
+    // Goes around the ring forever, unless it is empty
+    pub fn cycle(&self) -> Cycle<'_, T> {
+        Cycle {
+            next: self.head_ptr(),
+            _boo: PhantomData,
+        }
+    }
+
+    fn head_ptr(&self) -> Link<T> {
+        if self.tail.is_null() {
+            null_mut()
+        } else {
+            unsafe { (*self.tail).next }
+        }
+    }
+
+    fn singleton(value: T) -> Link<T> {
+        let node = Box::into_raw(Box::new(Node {
+            value,
+            next: null_mut(),
+        }));
+        unsafe {
+            (*node).next = node;
+        }
+        node
+    }
+}
+
+impl<T> Default for Ring<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(not(feature = "nightly"))]
+impl<T> Drop for Ring<T> {
+    fn drop(&mut self) {
+        guard::drop_values(self, Self::remove_head);
+    }
+}
+
+// Like sixth, dropping the ring only drops the values
+#[cfg(feature = "nightly")]
+unsafe impl<#[may_dangle] T> Drop for Ring<T> {
+    fn drop(&mut self) {
+        guard::drop_values(self, Self::remove_head);
+    }
+}
+
+impl<T> Extend<T> for Ring<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push_back(value);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for Ring<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut ring = Ring::new();
+        ring.extend(iter);
+        ring
+    }
+}
+
+pub struct Iter<'a, T> {
+    next: Link<T>,
+    remaining: usize,
+    _boo: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        unsafe {
+            let node = &*self.next;
+            self.next = node.next;
+            Some(&node.value)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Ring<T> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct Cycle<'a, T> {
+    next: Link<T>,
+    _boo: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for Cycle<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        unsafe {
+            let node = self.next.as_ref()?;
+            self.next = node.next;
+            Some(&node.value)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Ring;
+
+    #[test]
+    fn basics() {
+        let mut ring = Ring::new();
+        assert!(ring.is_empty());
+        assert_eq!(ring.head(), None);
+        assert_eq!(ring.remove_head(), None);
+        ring.rotate();
+
+        ring.insert_after_head(1);
+        assert_eq!(ring.head(), Some(&1));
+        ring.rotate();
+        assert_eq!(ring.head(), Some(&1));
+
+        ring.insert_after_head(3);
+        ring.insert_after_head(2);
+        assert_eq!(ring.len(), 3);
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        ring.rotate();
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), vec![2, 3, 1]);
+        *ring.head_mut().unwrap() = 20;
+        assert_eq!(ring.remove_head(), Some(20));
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), vec![3, 1]);
+        assert_eq!(ring.remove_head(), Some(3));
+        assert_eq!(ring.remove_head(), Some(1));
+        assert_eq!(ring.remove_head(), None);
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn push_back() {
+        let mut ring: Ring<_> = (1..=3).collect();
+        ring.rotate();
+        ring.push_back(4);
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), vec![2, 3, 1, 4]);
+        ring.rotate();
+        ring.rotate();
+        ring.rotate();
+        ring.insert_after_head(5);
+        ring.push_back(6);
+        assert_eq!(
+            ring.iter().copied().collect::<Vec<_>>(),
+            vec![4, 5, 2, 3, 1, 6]
+        );
+    }
+
+    #[test]
+    fn cycle() {
+        let ring: Ring<_> = (1..=3).collect();
+        assert_eq!(
+            ring.cycle().take(7).copied().collect::<Vec<_>>(),
+            vec![1, 2, 3, 1, 2, 3, 1]
+        );
+        let empty: Ring<i32> = Ring::new();
+        assert_eq!(empty.cycle().next(), None);
+    }
+
+    #[test]
+    fn josephus() {
+        // every third person leaves the circle
+        let mut ring: Ring<_> = (1..=7).collect();
+        let mut order = Vec::new();
+        while !ring.is_empty() {
+            ring.rotate();
+            ring.rotate();
+            order.push(ring.remove_head().unwrap());
+        }
+        assert_eq!(order, vec![3, 6, 2, 7, 5, 1, 4]);
+    }
+
+    #[test]
+    fn round_robin() {
+        // tasks needing some amount of work, served one unit at a time
+        let mut ring: Ring<_> = [("a", 2), ("b", 1), ("c", 3)].into_iter().collect();
+        let mut schedule = Vec::new();
+        while let Some((name, work)) = ring.head_mut() {
+            schedule.push(*name);
+            *work -= 1;
+            if *work == 0 {
+                ring.remove_head();
+            } else {
+                ring.rotate();
+            }
+        }
+        assert_eq!(schedule, vec!["a", "b", "c", "a", "c", "c"]);
+    }
+}
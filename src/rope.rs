@@ -0,0 +1,359 @@
+use std::fmt::Display;
+use std::ops::Range;
+
+use crate::sixth::{CursorMut, LinkedList};
+
+const CHUNK_CAPACITY: usize = 64;
+
+// A chunk is never empty, and never holds more than CHUNK_CAPACITY bytes
+// unless a single character is longer than that
+struct Chunk {
+    text: String,
+    chars: usize,
+}
+
+pub struct Rope {
+    chunks: LinkedList<Chunk>,
+    len_chars: usize,
+    len_bytes: usize,
+}
+
+impl Rope {
+    pub fn new() -> Self {
+        Rope {
+            chunks: LinkedList::new(),
+            len_chars: 0,
+            len_bytes: 0,
+        }
+    }
+
+    pub fn len_chars(&self) -> usize {
+        self.len_chars
+    }
+
+    pub fn len_bytes(&self) -> usize {
+        self.len_bytes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len_chars == 0
+    }
+
+    pub fn chunks(&self) -> impl DoubleEndedIterator<Item = &str> {
+        self.chunks.iter().map(|chunk| chunk.text.as_str())
+    }
+
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.chunks().flat_map(str::chars)
+    }
+
+    pub fn char_at(&self, char_idx: usize) -> Option<char> {
+        if char_idx >= self.len_chars {
+            return None;
+        }
+        if char_idx < self.len_chars / 2 {
+            let mut start = 0;
+            for chunk in self.chunks.iter() {
+                if char_idx < start + chunk.chars {
+                    return chunk.text.chars().nth(char_idx - start);
+                }
+                start += chunk.chars;
+            }
+        } else {
+            let mut end = self.len_chars;
+            for chunk in self.chunks.iter().rev() {
+                let start = end - chunk.chars;
+                if char_idx >= start {
+                    return chunk.text.chars().nth(char_idx - start);
+                }
+                end = start;
+            }
+        }
+        unreachable!()
+    }
+
+    pub fn insert(&mut self, char_idx: usize, text: &str) {
+        assert!(
+            char_idx <= self.len_chars,
+            "insertion index (is {char_idx}) should be <= len (is {})",
+            self.len_chars
+        );
+        if text.is_empty() {
+            return;
+        }
+        let chars = text.chars().count();
+        if self.chunks.is_empty() {
+            self.chunks = split_into_chunks(text);
+        } else {
+            let (mut cursor, offset) = self.seek(char_idx, true);
+            let chunk = cursor.current().unwrap();
+            let byte_offset = byte_offset(&chunk.text, offset);
+            if chunk.text.len() + text.len() <= CHUNK_CAPACITY {
+                chunk.text.insert_str(byte_offset, text);
+                chunk.chars += chars;
+            } else {
+                // redistribute the chunk and the inserted text in new chunks
+                let mut combined = String::with_capacity(chunk.text.len() + text.len());
+                combined.push_str(&chunk.text[..byte_offset]);
+                combined.push_str(text);
+                combined.push_str(&chunk.text[byte_offset..]);
+                let mut chunks = split_into_chunks(&combined);
+                *chunk = chunks.pop_front().unwrap();
+                cursor.splice_after(chunks);
+            }
+        }
+        self.len_chars += chars;
+        self.len_bytes += text.len();
+    }
+
+    pub fn remove(&mut self, char_range: Range<usize>) {
+        let Range { start, end } = char_range;
+        assert!(
+            start <= end,
+            "range start (is {start}) should be <= end (is {end})"
+        );
+        assert!(
+            end <= self.len_chars,
+            "range end (is {end}) should be <= len (is {})",
+            self.len_chars
+        );
+        if start == end {
+            return;
+        }
+        let mut removed_bytes = 0;
+        let (mut cursor, mut offset) = self.seek(start, false);
+        let mut remaining = end - start;
+        while remaining > 0 {
+            let chunk = cursor.current().unwrap();
+            let count = remaining.min(chunk.chars - offset);
+            let from = byte_offset(&chunk.text, offset);
+            let to = from + byte_offset(&chunk.text[from..], count);
+            chunk.text.replace_range(from..to, "");
+            chunk.chars -= count;
+            removed_bytes += to - from;
+            remaining -= count;
+            if chunk.text.is_empty() {
+                cursor.remove_current();
+            } else {
+                cursor.move_next();
+            }
+            offset = 0;
+        }
+        self.len_chars -= end - start;
+        self.len_bytes -= removed_bytes;
+    }
+
+    // Finds the chunk holding the character at char_idx, and the offset of the
+    // character in the chunk; when inclusive, an index at the boundary
+    // between two chunks designates the end of the first one
+    fn seek(&mut self, char_idx: usize, inclusive: bool) -> (CursorMut<'_, Chunk>, usize) {
+        let len = self.len_chars;
+        let mut cursor = self.chunks.cursor_mut();
+        if char_idx < len / 2 {
+            let mut start = 0;
+            loop {
+                cursor.move_next();
+                let chars = cursor.current().unwrap().chars;
+                if char_idx < start + chars || (inclusive && char_idx == start + chars) {
+                    return (cursor, char_idx - start);
+                }
+                start += chars;
+            }
+        } else {
+            let mut end = len;
+            loop {
+                cursor.move_prev();
+                let start = end - cursor.current().unwrap().chars;
+                if char_idx > start || (char_idx == start && (!inclusive || start == 0)) {
+                    return (cursor, char_idx - start);
+                }
+                end = start;
+            }
+        }
+    }
+}
+
+fn byte_offset(text: &str, char_offset: usize) -> usize {
+    text.char_indices()
+        .nth(char_offset)
+        .map_or(text.len(), |(offset, _)| offset)
+}
+
+fn split_into_chunks(text: &str) -> LinkedList<Chunk> {
+    let mut chunks = LinkedList::new();
+    let mut chunk = Chunk {
+        text: String::new(),
+        chars: 0,
+    };
+    for c in text.chars() {
+        if !chunk.text.is_empty() && chunk.text.len() + c.len_utf8() > CHUNK_CAPACITY {
+            let full = std::mem::replace(
+                &mut chunk,
+                Chunk {
+                    text: String::new(),
+                    chars: 0,
+                },
+            );
+            chunks.push_back(full);
+        }
+        chunk.text.push(c);
+        chunk.chars += 1;
+    }
+    if !chunk.text.is_empty() {
+        chunks.push_back(chunk);
+    }
+    chunks
+}
+
+impl Default for Rope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&str> for Rope {
+    fn from(text: &str) -> Self {
+        let mut rope = Rope::new();
+        rope.insert(0, text);
+        rope
+    }
+}
+
+impl Display for Rope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for chunk in self.chunks() {
+            f.write_str(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Rope, CHUNK_CAPACITY};
+
+    fn check(rope: &Rope, expected: &str) {
+        assert_eq!(rope.to_string(), expected);
+        assert_eq!(rope.len_bytes(), expected.len());
+        assert_eq!(rope.len_chars(), expected.chars().count());
+        for chunk in rope.chunks() {
+            assert!(!chunk.is_empty());
+            assert!(chunk.len() <= CHUNK_CAPACITY);
+        }
+    }
+
+    #[test]
+    fn basics() {
+        let mut rope = Rope::new();
+        assert!(rope.is_empty());
+        assert_eq!(rope.char_at(0), None);
+        rope.insert(0, "world");
+        rope.insert(0, "hello ");
+        rope.insert(11, "!");
+        check(&rope, "hello world!");
+        assert_eq!(rope.char_at(4), Some('o'));
+        assert_eq!(rope.char_at(11), Some('!'));
+        assert_eq!(rope.char_at(12), None);
+
+        rope.remove(5..11);
+        check(&rope, "hello!");
+        rope.remove(0..6);
+        check(&rope, "");
+        assert!(rope.is_empty());
+    }
+
+    #[test]
+    fn multiple_chunks() {
+        let text: String = (0..1000)
+            .map(|i| char::from(b'a' + (i % 26) as u8))
+            .collect();
+        let mut rope = Rope::from(text.as_str());
+        check(&rope, &text);
+        assert!(rope.chunks().count() > 1);
+        for (i, c) in text.chars().enumerate() {
+            assert_eq!(rope.char_at(i), Some(c));
+        }
+
+        // across chunk boundaries
+        let mut expected = text.clone();
+        rope.remove(50..900);
+        expected.replace_range(50..900, "");
+        check(&rope, &expected);
+        rope.insert(60, &text);
+        expected.insert_str(60, &text);
+        check(&rope, &expected);
+    }
+
+    #[test]
+    fn unicode() {
+        let mut rope = Rope::from("h\u{e9}llo w\u{f6}rld");
+        rope.insert(5, " \u{1f980}");
+        check(&rope, "h\u{e9}llo \u{1f980} w\u{f6}rld");
+        assert_eq!(rope.char_at(1), Some('\u{e9}'));
+        assert_eq!(rope.char_at(6), Some('\u{1f980}'));
+        rope.remove(1..7);
+        check(&rope, "h w\u{f6}rld");
+
+        let wide = "\u{1f980}".repeat(100);
+        let mut rope = Rope::from(wide.as_str());
+        rope.insert(50, "x");
+        assert_eq!(rope.char_at(50), Some('x'));
+        assert_eq!(rope.len_chars(), 101);
+        check(&rope, &format!("{}x{}", &wide[..200], &wide[200..]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_out_of_bounds() {
+        let mut rope = Rope::from("abc");
+        rope.insert(4, "d");
+    }
+
+    #[test]
+    #[should_panic]
+    fn remove_out_of_bounds() {
+        let mut rope = Rope::from("abc");
+        rope.remove(1..4);
+    }
+
+    #[test]
+    fn editing_session() {
+        // a long series of small edits, checked against a plain String
+        let mut x: u64 = 7;
+        let mut random = |bound: usize| {
+            x = x
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (x >> 33) as usize % bound
+        };
+        let mut rope = Rope::new();
+        let mut expected = String::new();
+        for i in 0..20_000 {
+            let len = expected.len();
+            if len > 0 && random(3) == 0 {
+                let start = random(len);
+                let end = (start + random(16)).min(len);
+                rope.remove(start..end);
+                expected.replace_range(start..end, "");
+            } else {
+                let at = random(len + 1);
+                let text = ["a", "bc", "def", "ghij\n"][i % 4];
+                rope.insert(at, text);
+                expected.insert_str(at, text);
+            }
+        }
+        check(&rope, &expected);
+    }
+
+    #[test]
+    fn large_appends() {
+        let mut rope = Rope::new();
+        for _ in 0..100_000 {
+            rope.insert(rope.len_chars(), "line\n");
+        }
+        assert_eq!(rope.len_bytes(), 500_000);
+        rope.remove(0..250_000);
+        assert_eq!(rope.len_bytes(), 250_000);
+        assert_eq!(rope.char_at(4), Some('\n'));
+    }
+}
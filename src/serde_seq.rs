@@ -0,0 +1,35 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{Deserialize, SeqAccess, Visitor};
+
+// Builds a list from a sequence by pushing each element in order
+pub(crate) struct PushVisitor<L, T> {
+    push: fn(&mut L, T),
+    _boo: PhantomData<fn() -> (L, T)>,
+}
+
+impl<L, T> PushVisitor<L, T> {
+    pub(crate) fn new(push: fn(&mut L, T)) -> Self {
+        PushVisitor {
+            push,
+            _boo: PhantomData,
+        }
+    }
+}
+
+impl<'de, L: Default, T: Deserialize<'de>> Visitor<'de> for PushVisitor<L, T> {
+    type Value = L;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<L, A::Error> {
+        let mut list = L::default();
+        while let Some(value) = seq.next_element()? {
+            (self.push)(&mut list, value);
+        }
+        Ok(list)
+    }
+}
@@ -1,33 +1,174 @@
+use std::cmp::Ordering;
 use std::fmt::Debug;
 use std::hash::Hash;
+use std::iter::FusedIterator;
 use std::marker::PhantomData;
-use std::ptr::NonNull;
+use std::mem::{self, ManuallyDrop};
+use std::ptr::{self, NonNull};
 
-type Link<T> = Option<NonNull<Node<T>>>;
+#[cfg(feature = "rand")]
+use rand::seq::SliceRandom;
+#[cfg(feature = "rand")]
+use rand::Rng;
+#[cfg(feature = "rayon")]
+use rayon::iter::{FromParallelIterator, IntoParallelIterator, ParallelExtend, ParallelIterator};
 
-struct Node<T> {
+pub use crate::allocator::{Allocator, Global};
+#[cfg(feature = "futures")]
+use crate::async_iter::IntoStream;
+#[cfg(feature = "dot")]
+use crate::dot::{self, DotNode};
+use crate::guard;
+use crate::no_panic;
+#[cfg(all(feature = "pool", not(feature = "allocator-api")))]
+use crate::pool;
+#[cfg(feature = "serde")]
+use crate::serde_seq::PushVisitor;
+#[cfg(feature = "stats")]
+use crate::stats::Stats;
+use crate::traits::{Deque, FromValues, HeapSize, Queue, Stack};
+
+pub type Link<T> = Option<NonNull<Node<T>>>;
+
+// Only public so that the links of a list can be handed out; what is inside
+// stays private
+pub struct Node<T> {
     value: T,
     next: Link<T>,
     prev: Link<T>,
 }
 
-pub struct LinkedList<T> {
+// With the pool feature, the nodes are taken from and given back to the pool
+// of the thread instead of the allocator. Unless the allocator API is there,
+// the allocator can only be the global one, and is not used.
+impl<T> Node<T> {
+    #[cfg(not(any(feature = "pool", feature = "allocator-api")))]
+    fn alloc<A: Allocator>(value: T, _alloc: &A) -> NonNull<Node<T>> {
+        no_panic::boxed(Node {
+            value,
+            next: None,
+            prev: None,
+        })
+    }
+
+    #[cfg(all(feature = "pool", not(feature = "allocator-api")))]
+    fn alloc<A: Allocator>(value: T, _alloc: &A) -> NonNull<Node<T>> {
+        pool::alloc(Node {
+            value,
+            next: None,
+            prev: None,
+        })
+    }
+
+    #[cfg(feature = "allocator-api")]
+    fn alloc<A: Allocator>(value: T, alloc: &A) -> NonNull<Node<T>> {
+        let node = Box::new_in(
+            Node {
+                value,
+                next: None,
+                prev: None,
+            },
+            alloc,
+        );
+        let (node, _) = Box::into_raw_with_allocator(node);
+        unsafe { NonNull::new_unchecked(node) }
+    }
+
+    #[cfg(not(any(feature = "pool", feature = "allocator-api")))]
+    unsafe fn free<A: Allocator>(node: NonNull<Node<T>>, _alloc: &A) -> Node<T> {
+        *Box::from_raw(node.as_ptr())
+    }
+
+    #[cfg(all(feature = "pool", not(feature = "allocator-api")))]
+    unsafe fn free<A: Allocator>(node: NonNull<Node<T>>, _alloc: &A) -> Node<T> {
+        pool::free(node)
+    }
+
+    #[cfg(feature = "allocator-api")]
+    unsafe fn free<A: Allocator>(node: NonNull<Node<T>>, alloc: &A) -> Node<T> {
+        *Box::from_raw_in(node.as_ptr(), alloc)
+    }
+}
+
+pub struct LinkedList<T, A: Allocator = Global> {
     first: Link<T>,
     last: Link<T>,
     len: usize,
+    alloc: A,
     _phantom: PhantomData<T>,
+    #[cfg(feature = "stats")]
+    stats: Stats,
 }
 
 impl<T> LinkedList<T> {
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    // The nodes are leaked until they are given back to from_raw_parts
+    pub fn into_raw_parts(self) -> (Link<T>, Link<T>, usize) {
+        let (first, last, len, _) = self.into_raw_parts_with_alloc();
+        (first, last, len)
+    }
+
+    /// # Safety
+    ///
+    /// The parts must come from [`LinkedList::into_raw_parts`], and must not
+    /// be used again afterwards.
+    pub unsafe fn from_raw_parts(first: Link<T>, last: Link<T>, len: usize) -> Self {
+        Self::from_raw_parts_in(first, last, len, Global)
+    }
+}
+
+impl<T, A: Allocator> LinkedList<T, A> {
+    pub const fn new_in(alloc: A) -> Self {
         LinkedList {
             first: None,
             last: None,
             len: 0,
+            alloc,
+            _phantom: PhantomData,
+            #[cfg(feature = "stats")]
+            stats: Stats::new(),
+        }
+    }
+
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    pub fn into_raw_parts_with_alloc(self) -> (Link<T>, Link<T>, usize, A) {
+        let this = ManuallyDrop::new(self);
+        let alloc = unsafe { ptr::read(&this.alloc) };
+        (this.first, this.last, this.len, alloc)
+    }
+
+    /// # Safety
+    ///
+    /// The parts must come from [`LinkedList::into_raw_parts_with_alloc`],
+    /// and must not be used again afterwards.
+    pub unsafe fn from_raw_parts_in(first: Link<T>, last: Link<T>, len: usize, alloc: A) -> Self {
+        LinkedList {
+            first,
+            last,
+            len,
+            alloc,
             _phantom: PhantomData,
+            // the counters start over, as if the nodes had been moved in
+            #[cfg(feature = "stats")]
+            stats: Stats {
+                nodes: len,
+                peak: len,
+                ..Stats::new()
+            },
         }
     }
 
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
     pub fn is_empty(&self) -> bool {
         self.len == 0
     }
@@ -37,924 +178,3924 @@ impl<T> LinkedList<T> {
     }
 
     pub fn push_front(&mut self, value: T) {
-        unsafe {
-            let node = Some(NonNull::new_unchecked(Box::into_raw(Box::new(Node {
-                value,
-                next: self.first,
-                prev: None,
-            }))));
-            match self.first.as_mut() {
-                None => self.last = node,
-                Some(first) => first.as_mut().prev = node,
-            }
-            self.first = node;
-            self.len += 1;
-        }
+        no_panic::check(|| {
+            self.push_front_handle(value);
+        })
     }
 
     pub fn push_back(&mut self, value: T) {
-        unsafe {
-            let node = Some(NonNull::new_unchecked(Box::into_raw(Box::new(Node {
-                value,
-                next: None,
-                prev: self.last,
-            }))));
-            match self.last.as_mut() {
-                None => self.first = node,
-                Some(last) => last.as_mut().next = node,
-            }
-            self.last = node;
-            self.len += 1;
-        }
+        no_panic::check(|| {
+            self.push_back_handle(value);
+        })
     }
 
     pub fn pop_front(&mut self) -> Option<T> {
-        unsafe {
-            self.first.map(|node| {
-                let node = Box::from_raw(node.as_ptr());
-                self.first = node.next;
-                match self.first.as_mut() {
-                    None => self.last = None,
-                    Some(first) => first.as_mut().prev = None,
-                }
-                self.len -= 1;
-                node.value
-            })
-        }
+        no_panic::check(|| unsafe { self.first.map(|node| self.free_first(node)) })
     }
 
     pub fn pop_back(&mut self) -> Option<T> {
-        unsafe {
-            self.last.map(|node| {
-                let node = Box::from_raw(node.as_ptr());
-                self.last = node.prev;
-                match self.last.as_mut() {
-                    None => self.first = None,
-                    Some(last) => last.as_mut().next = None,
-                }
-                self.len -= 1;
-                node.value
-            })
+        no_panic::check(|| unsafe { self.last.map(|node| self.free_last(node)) })
+    }
+
+    // Frees the node, which must be the first, and gives back its value
+    unsafe fn free_first(&mut self, node: NonNull<Node<T>>) -> T {
+        let node = Node::free(node, &self.alloc);
+        self.first = node.next;
+        match self.first.as_mut() {
+            None => self.last = None,
+            Some(first) => first.as_mut().prev = None,
+        }
+        self.len -= 1;
+        #[cfg(feature = "stats")]
+        self.stats.freed();
+        #[cfg(feature = "tracing")]
+        tracing::trace!(len = self.len, "pop_front");
+        node.value
+    }
+
+    // Frees the node, which must be the last, and gives back its value
+    unsafe fn free_last(&mut self, node: NonNull<Node<T>>) -> T {
+        let node = Node::free(node, &self.alloc);
+        self.last = node.prev;
+        match self.last.as_mut() {
+            None => self.first = None,
+            Some(last) => last.as_mut().next = None,
         }
+        self.len -= 1;
+        #[cfg(feature = "stats")]
+        self.stats.freed();
+        #[cfg(feature = "tracing")]
+        tracing::trace!(len = self.len, "pop_back");
+        node.value
     }
 
     pub fn front(&self) -> Option<&T> {
-        unsafe { self.first.map(|node| &node.as_ref().value) }
+        no_panic::check(|| unsafe { self.first.map(|node| &node.as_ref().value) })
     }
 
     pub fn back(&self) -> Option<&T> {
-        unsafe { self.last.map(|node| &node.as_ref().value) }
+        no_panic::check(|| unsafe { self.last.map(|node| &node.as_ref().value) })
     }
 
     pub fn front_mut(&mut self) -> Option<&mut T> {
-        unsafe { self.first.as_mut().map(|node| &mut node.as_mut().value) }
+        no_panic::check(|| unsafe { self.first.as_mut().map(|node| &mut node.as_mut().value) })
     }
 
     pub fn back_mut(&mut self) -> Option<&mut T> {
-        unsafe { self.last.as_mut().map(|node| &mut node.as_mut().value) }
+        no_panic::check(|| unsafe { self.last.as_mut().map(|node| &mut node.as_mut().value) })
     }
-}
 
-impl<T> Default for LinkedList<T> {
-    fn default() -> Self {
-        Self::new()
+    // Moves the values of other to the back, leaving it empty
+    pub fn append(&mut self, other: &mut Self)
+    where
+        A: Clone,
+    {
+        let other = other.take_nodes();
+        self.cursor_mut().splice_before(other);
     }
-}
 
-impl<T> Drop for LinkedList<T> {
-    fn drop(&mut self) {
-        while self.pop_front().is_some() {}
+    // Moves the values of other to the front, leaving it empty
+    pub fn prepend(&mut self, other: &mut Self)
+    where
+        A: Clone,
+    {
+        let other = other.take_nodes();
+        self.cursor_mut().splice_after(other);
     }
-}
 
-impl<T> Extend<T> for LinkedList<T> {
-    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        for item in iter {
-            self.push_back(item);
-        }
+    pub fn clear(&mut self) {
+        // the same as dropping, so a panicking value still frees the rest
+        guard::drop_values(self, Self::pop_front);
     }
 }
 
-impl<T> FromIterator<T> for LinkedList<T> {
-    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        let mut l = LinkedList::new();
-        l.extend(iter);
-        l
+impl<T, A: Allocator> LinkedList<T, A> {
+    pub fn truncate(&mut self, len: usize) {
+        while self.len > len {
+            self.pop_back();
+        }
     }
-}
 
-pub struct IntoIter<T>(LinkedList<T>);
+    pub fn resize_with<F: FnMut() -> T>(&mut self, len: usize, mut f: F) {
+        self.truncate(len);
+        while self.len < len {
+            self.push_back(f());
+        }
+    }
 
-impl<T> Iterator for IntoIter<T> {
-    type Item = T;
-    fn next(&mut self) -> Option<Self::Item> {
-        self.0.pop_front()
+    pub fn resize(&mut self, len: usize, value: T)
+    where
+        T: Clone,
+    {
+        if len <= self.len {
+            self.truncate(len);
+            return;
+        }
+        // the last one is the value itself, like Vec::resize does
+        for _ in self.len + 1..len {
+            self.push_back(value.clone());
+        }
+        self.push_back(value);
     }
 }
 
-impl<T> DoubleEndedIterator for IntoIter<T> {
-    fn next_back(&mut self) -> Option<Self::Item> {
-        self.0.pop_back()
+impl<T, A: Allocator> LinkedList<T, A> {
+    // A cursor at the given index, reached from the closest end
+    fn cursor_at(&mut self, at: usize) -> CursorMut<'_, T, A> {
+        let len = self.len;
+        let mut cursor = self.cursor_mut();
+        if at < len / 2 {
+            for _ in 0..=at {
+                cursor.move_next();
+            }
+        } else {
+            for _ in at..len {
+                cursor.move_prev();
+            }
+        }
+        cursor
     }
-}
 
-impl<T> IntoIterator for LinkedList<T> {
-    type IntoIter = IntoIter<T>;
-    type Item = T;
-    fn into_iter(self) -> Self::IntoIter {
-        IntoIter(self)
+    pub fn insert(&mut self, at: usize, value: T) {
+        let len = self.len;
+        assert!(
+            at <= len,
+            "insertion index (is {at}) should be <= len (is {len})"
+        );
+        // at the end, the cursor is at the ghost, and inserts at the back
+        self.cursor_at(at).insert_before(value);
     }
-}
 
-pub struct Iter<'a, T> {
-    front: Link<T>,
-    back: Link<T>,
-    len: usize,
-    _phantom: PhantomData<&'a T>,
-}
+    pub fn remove(&mut self, at: usize) -> T {
+        let len = self.len;
+        assert!(
+            at < len,
+            "removal index (is {at}) should be < len (is {len})"
+        );
+        self.cursor_at(at).remove_current().unwrap()
+    }
 
-impl<'a, T> Iterator for Iter<'a, T> {
-    type Item = &'a T;
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.len == 0 {
-            None
+    // Like cursor_at, walks from whichever end is closer
+    pub fn get(&self, at: usize) -> Option<&T> {
+        let len = self.len;
+        if at < len / 2 {
+            self.iter().nth(at)
+        } else if at < len {
+            self.iter().rev().nth(len - 1 - at)
         } else {
-            self.front.map(|node| unsafe {
-                let ptr = node.as_ptr();
-                self.front = (*ptr).next;
-                self.len -= 1;
-                &(*ptr).value
-            })
+            None
         }
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.len, Some(self.len))
-    }
-}
-
-impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
-    fn next_back(&mut self) -> Option<Self::Item> {
-        if self.len == 0 {
-            None
+    pub fn get_mut(&mut self, at: usize) -> Option<&mut T> {
+        let len = self.len;
+        if at < len / 2 {
+            self.iter_mut().nth(at)
+        } else if at < len {
+            self.iter_mut().rev().nth(len - 1 - at)
         } else {
-            self.back.map(|node| unsafe {
-                let ptr = node.as_ptr();
-                self.back = (*ptr).prev;
-                self.len -= 1;
-                &(*ptr).value
-            })
+            None
         }
     }
-}
 
-impl<'a, T> IntoIterator for &'a LinkedList<T> {
-    type IntoIter = Iter<'a, T>;
-    type Item = &'a T;
-    fn into_iter(self) -> Self::IntoIter {
-        Iter {
-            front: self.first,
-            back: self.last,
-            len: self.len,
-            _phantom: PhantomData,
+    // The nodes trade places rather than their values, which may be large
+    pub fn swap(&mut self, i: usize, j: usize) {
+        let len = self.len;
+        assert!(i < len, "swap index (is {i}) should be < len (is {len})");
+        assert!(j < len, "swap index (is {j}) should be < len (is {len})");
+        if i == j {
+            return;
+        }
+        let (i, j) = (i.min(j), i.max(j));
+        let a = self.cursor_at(i).current.unwrap();
+        let b = self.cursor_at(j).current.unwrap();
+        unsafe {
+            let a_prev = (*a.as_ptr()).prev;
+            let b_prev = (*b.as_ptr()).prev;
+            if b_prev == Some(a) {
+                // next to each other, moving b in front of a is enough
+                self.unlink(b);
+                self.link_after(a_prev, b);
+            } else {
+                self.unlink(a);
+                self.unlink(b);
+                self.link_after(a_prev, b);
+                self.link_after(b_prev, a);
+            }
         }
     }
 }
 
-pub struct IterMut<'a, T> {
-    front: Link<T>,
-    back: Link<T>,
-    len: usize,
-    _phantom: PhantomData<&'a mut T>,
+impl<T, A: Allocator> LinkedList<T, A> {
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        self.retain_mut(|value| f(value));
+    }
+
+    pub fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, mut f: F) {
+        let mut cursor = self.cursor_mut();
+        cursor.move_next();
+        while let Some(value) = cursor.current() {
+            if f(value) {
+                cursor.move_next();
+            } else {
+                cursor.remove_current();
+            }
+        }
+    }
 }
 
-impl<'a, T> Iterator for IterMut<'a, T> {
-    type Item = &'a mut T;
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.len == 0 {
-            None
-        } else {
-            self.front.map(|node| unsafe {
-                let ptr = node.as_ptr();
-                self.front = (*ptr).next;
-                self.len -= 1;
-                &mut (*ptr).value
-            })
+impl<T, A: Allocator> LinkedList<T, A> {
+    // Like Vec::dedup_by, same_bucket is given each value and the one kept
+    // before it, and the value is removed if it returns true
+    pub fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(&mut self, mut same_bucket: F) {
+        let mut cursor = self.cursor_mut();
+        cursor.move_next();
+        cursor.move_next();
+        while let Some(node) = cursor.current {
+            let same = unsafe {
+                let prev = (*node.as_ptr()).prev.unwrap();
+                same_bucket(&mut (*node.as_ptr()).value, &mut (*prev.as_ptr()).value)
+            };
+            if same {
+                cursor.remove_current();
+            } else {
+                cursor.move_next();
+            }
         }
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.len, Some(self.len))
+    pub fn dedup_by_key<K: PartialEq, F: FnMut(&mut T) -> K>(&mut self, mut key: F) {
+        self.dedup_by(|a, b| key(a) == key(b));
     }
-}
 
-impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
-    fn next_back(&mut self) -> Option<Self::Item> {
-        if self.len == 0 {
-            None
-        } else {
-            self.back.map(|node| unsafe {
-                let ptr = node.as_ptr();
-                self.back = (*ptr).prev;
-                self.len -= 1;
-                &mut (*ptr).value
-            })
-        }
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b);
     }
-}
 
-impl<'a, T> IntoIterator for &'a mut LinkedList<T> {
-    type IntoIter = IterMut<'a, T>;
-    type Item = &'a mut T;
-    fn into_iter(self) -> Self::IntoIter {
-        IterMut {
-            front: self.first,
-            back: self.last,
-            len: self.len,
-            _phantom: PhantomData,
+    pub fn reverse(&mut self) {
+        let mut node = self.first;
+        while let Some(current) = node {
+            unsafe {
+                let current = &mut *current.as_ptr();
+                mem::swap(&mut current.next, &mut current.prev);
+                node = current.prev;
+            }
         }
+        mem::swap(&mut self.first, &mut self.last);
     }
-}
 
-impl<T> LinkedList<T> {
-    pub fn iter(&self) -> Iter<'_, T> {
-        self.into_iter()
+    // The first n values go to the back, by closing the list into a ring and
+    // opening it again before the value at n
+    pub fn rotate_left(&mut self, n: usize) {
+        let len = self.len;
+        assert!(
+            n <= len,
+            "rotation amount (is {n}) should be <= len (is {len})"
+        );
+        if n == 0 || n == len {
+            return;
+        }
+        let new_first = self.cursor_at(n).current.unwrap();
+        unsafe {
+            let (first, last) = (self.first.unwrap(), self.last.unwrap());
+            (*last.as_ptr()).next = Some(first);
+            (*first.as_ptr()).prev = Some(last);
+            let new_last = (*new_first.as_ptr()).prev.take().unwrap();
+            (*new_last.as_ptr()).next = None;
+            self.first = Some(new_first);
+            self.last = Some(new_last);
+        }
     }
-    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
-        self.into_iter()
+
+    pub fn rotate_right(&mut self, n: usize) {
+        let len = self.len;
+        assert!(
+            n <= len,
+            "rotation amount (is {n}) should be <= len (is {len})"
+        );
+        self.rotate_left(len - n);
     }
 }
 
-impl<T: PartialEq> PartialEq for LinkedList<T> {
-    fn eq(&self, other: &Self) -> bool {
-        self.iter().eq(other)
+// Removes the values the predicate matches as it goes, and leaves the others,
+// and the ones it did not get to, in the list
+pub struct ExtractIf<'a, T, F, A: Allocator = Global> {
+    // at the next value to check, or at the ghost after the last one
+    cursor: CursorMut<'a, T, A>,
+    pred: F,
+}
+
+impl<T, A: Allocator> LinkedList<T, A> {
+    pub fn extract_if<F: FnMut(&mut T) -> bool>(&mut self, pred: F) -> ExtractIf<'_, T, F, A> {
+        let mut cursor = self.cursor_mut();
+        cursor.move_next();
+        ExtractIf { cursor, pred }
     }
 }
 
-impl<T: Eq> Eq for LinkedList<T> {}
+impl<T, F: FnMut(&mut T) -> bool, A: Allocator> Iterator for ExtractIf<'_, T, F, A> {
+    type Item = T;
 
-impl<T: PartialOrd> PartialOrd for LinkedList<T> {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.iter().partial_cmp(other)
+    fn next(&mut self) -> Option<T> {
+        while let Some(value) = self.cursor.current() {
+            if (self.pred)(value) {
+                return self.cursor.remove_current();
+            }
+            self.cursor.move_next();
+        }
+        None
     }
-}
 
-impl<T: Ord> Ord for LinkedList<T> {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.iter().cmp(other)
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = match self.cursor.index() {
+            Some(index) => self.cursor.list.len - index,
+            None => 0,
+        };
+        (0, Some(remaining))
     }
 }
 
-impl<T: Debug> Debug for LinkedList<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_list().entries(self).finish()
-    }
+// The nodes being merged, in chains linked by next only: those merged so far,
+// the two runs being merged, and the rest. Dropping it links the chains back
+// into the list, so that a comparison that panics leaves every value in it,
+// in some order.
+struct Merge<'a, T, A: Allocator> {
+    list: &'a mut LinkedList<T, A>,
+    merged: Link<T>,
+    merged_last: Link<T>,
+    left: Link<T>,
+    right: Link<T>,
+    rest: Link<T>,
 }
 
-impl<T: Clone> Clone for LinkedList<T> {
-    fn clone(&self) -> Self {
-        let mut l = LinkedList::new();
-        for v in self {
-            l.push_back(v.clone())
+impl<'a, T, A: Allocator> Merge<'a, T, A> {
+    fn new(list: &'a mut LinkedList<T, A>) -> Self {
+        // each pass starts from what the previous one merged
+        Merge {
+            merged: list.first,
+            merged_last: None,
+            left: None,
+            right: None,
+            rest: None,
+            list,
         }
-        l
     }
-}
 
-impl<T: Hash> Hash for LinkedList<T> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.len.hash(state);
-        for v in self {
-            v.hash(state);
+    // Moves a chain to the back of the merged nodes
+    unsafe fn push_merged(&mut self, chain: NonNull<Node<T>>) {
+        match self.merged_last {
+            None => self.merged = Some(chain),
+            Some(last) => (*last.as_ptr()).next = Some(chain),
+        }
+        let mut last = chain;
+        while let Some(next) = (*last.as_ptr()).next {
+            last = next;
         }
+        self.merged_last = Some(last);
     }
-}
 
-pub struct CursorMut<'a, T> {
-    list: &'a mut LinkedList<T>,
-    current: Link<T>,
-    index: Option<usize>,
-}
+    // Detaches the first len nodes of the rest
+    unsafe fn take_run(&mut self, len: usize) -> Link<T> {
+        let first = self.rest?;
+        let mut last = first;
+        for _ in 1..len {
+            match (*last.as_ptr()).next {
+                Some(next) => last = next,
+                None => break,
+            }
+        }
+        self.rest = (*last.as_ptr()).next.take();
+        Some(first)
+    }
 
-impl<T> LinkedList<T> {
-    pub fn cursor_mut(&mut self) -> CursorMut<'_, T> {
-        CursorMut {
-            list: self,
-            current: None,
-            index: None,
+    unsafe fn merge_runs<F: FnMut(&T, &T) -> Ordering>(&mut self, compare: &mut F) {
+        while let (Some(left), Some(right)) = (self.left, self.right) {
+            // on ties, the left value goes first, for the sort to be stable
+            let node = if compare(&(*left.as_ptr()).value, &(*right.as_ptr()).value).is_gt() {
+                self.right = (*right.as_ptr()).next.take();
+                right
+            } else {
+                self.left = (*left.as_ptr()).next.take();
+                left
+            };
+            self.push_merged(node);
+        }
+        if let Some(chain) = self.left.take().or(self.right.take()) {
+            self.push_merged(chain);
         }
     }
 }
 
-impl<'a, T> CursorMut<'a, T> {
-    pub fn index(&self) -> Option<usize> {
-        self.index
+impl<T, A: Allocator> Drop for Merge<'_, T, A> {
+    fn drop(&mut self) {
+        unsafe {
+            for chain in [self.left.take(), self.right.take(), self.rest.take()]
+                .into_iter()
+                .flatten()
+            {
+                self.push_merged(chain);
+            }
+            let mut prev = None;
+            let mut node = self.merged;
+            while let Some(current) = node {
+                (*current.as_ptr()).prev = prev;
+                prev = node;
+                node = (*current.as_ptr()).next;
+            }
+            self.list.first = self.merged;
+            self.list.last = prev;
+        }
     }
+}
 
-    pub fn move_next(&mut self) {
-        if let Some(node) = self.current {
+impl<T, A: Allocator> LinkedList<T, A> {
+    // Bottom-up, merging runs of 1, 2, 4... nodes, by relinking them
+    pub fn sort_by<F: FnMut(&T, &T) -> Ordering>(&mut self, mut compare: F) {
+        let len = self.len;
+        let mut merge = Merge::new(self);
+        let mut width = 1;
+        while width < len {
+            merge.rest = merge.merged.take();
+            merge.merged_last = None;
             unsafe {
-                self.current = node.as_ref().next;
-                if self.current.is_some() {
-                    self.index = Some(self.index.unwrap() + 1);
-                } else {
-                    self.index = None;
+                while merge.rest.is_some() {
+                    merge.left = merge.take_run(width);
+                    merge.right = merge.take_run(width);
+                    merge.merge_runs(&mut compare);
                 }
             }
-        } else if !self.list.is_empty() {
-            self.current = self.list.first;
-            self.index = Some(0);
-        } else {
+            width *= 2;
         }
     }
 
-    pub fn move_prev(&mut self) {
-        if let Some(node) = self.current {
-            unsafe {
-                self.current = node.as_ref().prev;
-                if self.current.is_some() {
-                    self.index = Some(self.index.unwrap() - 1);
-                } else {
-                    self.index = None;
-                }
-            }
-        } else if !self.list.is_empty() {
-            self.current = self.list.last;
-            self.index = Some(self.list.len - 1);
-        } else {
-        }
+    pub fn sort_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, mut f: F) {
+        self.sort_by(|a, b| f(a).cmp(&f(b)));
     }
 
-    pub fn current(&mut self) -> Option<&mut T> {
-        unsafe { self.current.as_mut().map(|node| &mut node.as_mut().value) }
+    // Both lists must be sorted already; on ties, the values of self go first
+    pub fn merge_by<F: FnMut(&T, &T) -> Ordering>(
+        mut self,
+        mut other: LinkedList<T, A>,
+        mut compare: F,
+    ) -> LinkedList<T, A> {
+        // the nodes of other belong to self from now on, even if comparing
+        // panics halfway
+        let right = other.first.take();
+        other.last = None;
+        let moved = mem::take(&mut other.len);
+        self.len += moved;
+        #[cfg(feature = "stats")]
+        self.stats.moved_in(moved);
+        let mut merge = Merge::new(&mut self);
+        merge.left = merge.merged.take();
+        merge.right = right;
+        unsafe { merge.merge_runs(&mut compare) };
+        drop(merge);
+        self
     }
+}
 
-    pub fn peek_next(&mut self) -> Option<&mut T> {
-        unsafe {
-            let next_node = if let Some(node) = self.current {
-                (*node.as_ptr()).next
-            } else {
-                self.list.first
-            };
-            next_node.map(|node| &mut (*node.as_ptr()).value)
-        }
+impl<T: Ord, A: Allocator> LinkedList<T, A> {
+    pub fn sort(&mut self) {
+        self.sort_by(T::cmp);
     }
 
-    pub fn peek_prev(&mut self) -> Option<&mut T> {
-        unsafe {
-            let prev_node = if let Some(node) = self.current {
-                (*node.as_ptr()).prev
-            } else {
-                self.list.last
-            };
-            prev_node.map(|node| &mut (*node.as_ptr()).value)
+    pub fn merge(self, other: LinkedList<T, A>) -> LinkedList<T, A> {
+        self.merge_by(other, T::cmp)
+    }
+}
+
+impl<T, A: Allocator + Default> Default for LinkedList<T, A> {
+    fn default() -> Self {
+        Self::new_in(A::default())
+    }
+}
+
+// The list owns its nodes, so it can move between threads like a Vec<T>
+unsafe impl<T: Send, A: Allocator + Send> Send for LinkedList<T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for LinkedList<T, A> {}
+
+// The values are behind pointers, so moving the list does not move them
+impl<T, A: Allocator> Unpin for LinkedList<T, A> {}
+
+impl<T, A: Allocator> Stack<T> for LinkedList<T, A> {
+    fn push(&mut self, value: T) {
+        self.push_front(value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T, A: Allocator> Queue<T> for LinkedList<T, A> {
+    fn enqueue(&mut self, value: T) {
+        self.push_back(value);
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T, A: Allocator> Deque<T> for LinkedList<T, A> {
+    fn push_front(&mut self, value: T) {
+        self.push_front(value);
+    }
+
+    fn push_back(&mut self, value: T) {
+        self.push_back(value);
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        self.pop_back()
+    }
+}
+
+impl<T, A: Allocator> HeapSize for LinkedList<T, A> {
+    fn heap_size(&self) -> usize {
+        self.len * size_of::<Node<T>>()
+    }
+}
+
+// Dropping the list drops its values from the front to the back, as std's
+// LinkedList does; drop_back_to_front is there for the opposite order
+impl<T, A: Allocator> LinkedList<T, A> {
+    pub fn drop_back_to_front(mut self) {
+        // if a value panics, the rest is dropped from the front by Drop
+        while let Some(value) = self.pop_back() {
+            drop(value);
         }
     }
+}
 
-    pub fn split_before(&mut self) -> LinkedList<T> {
-        if let Some(node) = self.current {
-            let index = self.index.unwrap();
-            let prev = unsafe { (*node.as_ptr()).prev };
+#[cfg(not(feature = "nightly"))]
+impl<T, A: Allocator> Drop for LinkedList<T, A> {
+    fn drop(&mut self) {
+        guard::drop_values(self, Self::pop_front);
+    }
+}
 
-            // Self will contain everything after the cursor
-            let self_len = self.list.len - index;
-            let self_first = Some(node);
-            let self_last = self.list.last;
-            let self_index = Some(0);
+// The values are dropped and nothing else is done with them, so they may hold
+// references that are already dangling, unless their own Drop uses them
+#[cfg(feature = "nightly")]
+unsafe impl<#[may_dangle] T, A: Allocator> Drop for LinkedList<T, A> {
+    fn drop(&mut self) {
+        guard::drop_values(self, Self::pop_front);
+    }
+}
 
-            // Output will contain everything before the cursor
-            let new_len = self.list.len - self_len;
-            let new_first = self.list.first;
-            let new_last = prev;
+impl<T, A: Allocator> Extend<T> for LinkedList<T, A> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push_back(item);
+        }
+    }
+}
 
-            unsafe {
-                if let Some(prev) = prev {
-                    (*prev.as_ptr()).next = None;
-                    (*node.as_ptr()).prev = None;
-                }
-            }
+impl<T> FromIterator<T> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut l = LinkedList::new();
+        l.extend(iter);
+        l
+    }
+}
 
-            self.list.len = self_len;
-            self.list.first = self_first;
-            self.list.last = self_last;
-            self.index = self_index;
+impl<T> FromValues<T> for LinkedList<T> {
+    fn from_values<I: IntoIterator<Item = T>>(values: I) -> Self {
+        values.into_iter().collect()
+    }
+}
 
-            LinkedList {
-                first: new_first,
-                last: new_last,
-                len: new_len,
-                _phantom: PhantomData,
+// The lists are linked one after the other, without touching their values,
+// so this is linear in the number of lists rather than of values
+impl<T, A: Allocator> Extend<LinkedList<T, A>> for LinkedList<T, A> {
+    fn extend<I: IntoIterator<Item = LinkedList<T, A>>>(&mut self, iter: I) {
+        let mut cursor = self.cursor_mut();
+        for list in iter {
+            // at the ghost, splicing before appends
+            cursor.splice_before(list);
+        }
+    }
+}
+
+impl<T> FromIterator<LinkedList<T>> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = LinkedList<T>>>(iter: I) -> Self {
+        let mut l = LinkedList::new();
+        l.extend(iter);
+        l
+    }
+}
+
+impl<T, A: Allocator> LinkedList<T, A> {
+    // The values are written over those of the old nodes from the front, and
+    // nodes are only allocated or freed for the difference in length
+    pub fn recycle_from<I: IntoIterator<Item = T>>(
+        mut old: LinkedList<T, A>,
+        iter: I,
+    ) -> LinkedList<T, A> {
+        let mut current = old.first;
+        let mut written = 0;
+        for value in iter {
+            written += 1;
+            match current {
+                Some(node) => unsafe {
+                    current = (*node.as_ptr()).next;
+                    drop(mem::replace(&mut (*node.as_ptr()).value, value));
+                },
+                None => old.push_back(value),
             }
-        } else {
-            std::mem::replace(self.list, LinkedList::new())
         }
+        while old.len > written {
+            old.pop_back();
+        }
+        old
     }
+}
 
-    pub fn split_after(&mut self) -> LinkedList<T> {
-        if let Some(node) = self.current {
-            let index = self.index.unwrap();
-            let next = unsafe { (*node.as_ptr()).next };
+impl<T> From<std::collections::LinkedList<T>> for LinkedList<T> {
+    fn from(list: std::collections::LinkedList<T>) -> Self {
+        list.into_iter().collect()
+    }
+}
 
-            // Self will contain everything before the cursor
-            let self_len = index + 1;
-            let self_first = self.list.first;
-            let self_last = Some(node);
-            let self_index = Some(index);
+impl<T, A: Allocator> From<LinkedList<T, A>> for std::collections::LinkedList<T> {
+    fn from(list: LinkedList<T, A>) -> Self {
+        list.into_iter().collect()
+    }
+}
 
-            // Output will contain everything after the cursor
-            let new_len = self.list.len - self_len;
-            let new_first = next;
-            let new_last = self.list.last;
+impl<T, const N: usize> From<[T; N]> for LinkedList<T> {
+    fn from(values: [T; N]) -> Self {
+        values.into_iter().collect()
+    }
+}
 
-            unsafe {
-                if let Some(next) = next {
-                    (*next.as_ptr()).prev = None;
-                    (*node.as_ptr()).next = None;
-                }
-            }
+impl<T> From<Vec<T>> for LinkedList<T> {
+    fn from(values: Vec<T>) -> Self {
+        values.into_iter().collect()
+    }
+}
 
-            self.list.len = self_len;
-            self.list.first = self_first;
-            self.list.last = self_last;
-            self.index = self_index;
+impl<T> From<Box<[T]>> for LinkedList<T> {
+    fn from(values: Box<[T]>) -> Self {
+        values.into_vec().into_iter().collect()
+    }
+}
 
-            LinkedList {
-                first: new_first,
-                last: new_last,
-                len: new_len,
-                _phantom: PhantomData,
-            }
-        } else {
-            std::mem::replace(self.list, LinkedList::new())
+impl<T, A: Allocator> LinkedList<T, A> {
+    pub fn into_vec(self) -> Vec<T> {
+        let mut values = Vec::with_capacity(self.len);
+        values.extend(self);
+        values
+    }
+}
+
+impl<T, A: Allocator> From<LinkedList<T, A>> for Vec<T> {
+    fn from(list: LinkedList<T, A>) -> Self {
+        list.into_vec()
+    }
+}
+
+pub struct IntoIter<T, A: Allocator = Global>(LinkedList<T, A>);
+
+impl<T, A: Allocator> IntoIter<T, A> {
+    // Gives back what was not iterated over yet, in the same nodes
+    pub fn into_inner(self) -> LinkedList<T, A> {
+        self.0
+    }
+}
+
+impl<T: Debug, A: Allocator> Debug for IntoIter<T, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.0).finish()
+    }
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+
+    // The nodes are freed one after the other without going through next, and
+    // the list keeps those not reached yet in case f panics
+    fn fold<B, F: FnMut(B, Self::Item) -> B>(mut self, init: B, mut f: F) -> B {
+        let mut acc = init;
+        while let Some(node) = self.0.first {
+            acc = f(acc, unsafe { self.0.free_first(node) });
         }
+        acc
     }
 
-    pub fn splice_before(&mut self, mut input: LinkedList<T>) {
-        unsafe {
-            if input.is_empty() {
-                // they're empty
-            } else if let Some(node) = self.current {
-                // insert
-                let input_first = input.first.take().unwrap();
-                let input_last = input.last.take().unwrap();
-                if let Some(prev) = (*node.as_ptr()).prev {
-                    (*prev.as_ptr()).next = Some(input_first);
-                    (*input_first.as_ptr()).prev = Some(prev);
-                } else {
-                    self.list.first = Some(input_first);
-                }
-                (*node.as_ptr()).prev = Some(input_last);
-                (*input_last.as_ptr()).next = Some(node);
-            } else if let Some(last) = self.list.last {
-                // append
-                let input_first = input.first.take().unwrap();
-                let input_last = input.last.take().unwrap();
-                (*last.as_ptr()).next = Some(input_first);
-                (*input_first.as_ptr()).prev = Some(last);
-                self.list.last = Some(input_last);
-            } else {
-                // we're empty
-                std::mem::swap(self.list, &mut input);
-            }
+    #[cfg(feature = "nightly")]
+    fn try_fold<B, F, R>(&mut self, init: B, mut f: F) -> R
+    where
+        F: FnMut(B, Self::Item) -> R,
+        R: std::ops::Try<Output = B>,
+    {
+        let mut acc = init;
+        while let Some(node) = self.0.first {
+            acc = f(acc, unsafe { self.0.free_first(node) })?;
         }
-        self.list.len += input.len;
-        input.len = 0;
+        R::from_output(acc)
     }
+}
 
-    pub fn splice_after(&mut self, mut input: LinkedList<T>) {
-        unsafe {
-            if input.is_empty() {
-                // they're empty
-            } else if let Some(node) = self.current {
-                // insert
-                let input_first = input.first.take().unwrap();
-                let input_last = input.last.take().unwrap();
-                if let Some(next) = (*node.as_ptr()).next {
-                    (*next.as_ptr()).prev = Some(input_last);
-                    (*input_last.as_ptr()).next = Some(next);
-                } else {
-                    self.list.last = Some(input_last);
-                }
-                (*node.as_ptr()).next = Some(input_first);
-                (*input_first.as_ptr()).prev = Some(node);
-            } else if let Some(first) = self.list.first {
-                // prepend
-                let input_first = input.first.take().unwrap();
-                let input_last = input.last.take().unwrap();
-                (*first.as_ptr()).prev = Some(input_last);
-                (*input_last.as_ptr()).next = Some(first);
-                self.list.first = Some(input_first);
-            } else {
-                // we're empty
-                std::mem::swap(self.list, &mut input);
-            }
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.pop_back()
+    }
+
+    fn rfold<B, F: FnMut(B, Self::Item) -> B>(mut self, init: B, mut f: F) -> B {
+        let mut acc = init;
+        while let Some(node) = self.0.last {
+            acc = f(acc, unsafe { self.0.free_last(node) });
+        }
+        acc
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> IntoIterator for LinkedList<T, A> {
+    type IntoIter = IntoIter<T, A>;
+    type Item = T;
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+// Like IntoIter, but the list is only borrowed, and whatever was not taken is
+// freed when the iterator is dropped
+pub struct Drain<'a, T, A: Allocator = Global>(&'a mut LinkedList<T, A>);
+
+impl<T, A: Allocator> LinkedList<T, A> {
+    pub fn drain(&mut self) -> Drain<'_, T, A> {
+        Drain(self)
+    }
+}
+
+impl<T: Debug, A: Allocator> Debug for Drain<'_, T, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Drain").field(&*self.0).finish()
+    }
+}
+
+impl<T, A: Allocator> Iterator for Drain<'_, T, A> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for Drain<'_, T, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.pop_back()
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for Drain<'_, T, A> {}
+
+impl<T, A: Allocator> FusedIterator for Drain<'_, T, A> {}
+
+impl<T, A: Allocator> Drop for Drain<'_, T, A> {
+    fn drop(&mut self) {
+        self.0.clear();
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T, A: Allocator> LinkedList<T, A> {
+    pub fn into_stream(self) -> IntoStream<IntoIter<T, A>> {
+        IntoStream::new(self.into_iter())
+    }
+}
+
+pub struct Iter<'a, T> {
+    front: Link<T>,
+    back: Link<T>,
+    len: usize,
+    _phantom: PhantomData<&'a T>,
+}
+
+// Shares the values like a &'a T would
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Iter { ..*self }
+    }
+}
+
+impl<T: Debug> Debug for Iter<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Iter")
+            .field(&DebugEntries(self.clone()))
+            .finish()
+    }
+}
+
+// Lists what an iterator has left without consuming it
+struct DebugEntries<'a, T>(Iter<'a, T>);
+
+impl<T: Debug> Debug for DebugEntries<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.0.clone()).finish()
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        no_panic::check(|| {
+            if self.len == 0 {
+                None
+            } else {
+                self.front.map(|node| unsafe {
+                    let ptr = node.as_ptr();
+                    self.front = (*ptr).next;
+                    self.len -= 1;
+                    &(*ptr).value
+                })
+            }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+
+    // The nodes are walked directly by the adapters that drive the iteration
+    // themselves, such as sum, instead of through next
+    fn fold<B, F: FnMut(B, Self::Item) -> B>(self, init: B, mut f: F) -> B {
+        let mut acc = init;
+        let mut front = self.front;
+        for _ in 0..self.len {
+            let Some(node) = front else { break };
+            unsafe {
+                front = (*node.as_ptr()).next;
+                acc = f(acc, &(*node.as_ptr()).value);
+            }
+        }
+        acc
+    }
+
+    #[cfg(feature = "nightly")]
+    fn try_fold<B, F, R>(&mut self, init: B, mut f: F) -> R
+    where
+        F: FnMut(B, Self::Item) -> R,
+        R: std::ops::Try<Output = B>,
+    {
+        let mut acc = init;
+        while self.len > 0 {
+            let Some(node) = self.front else { break };
+            unsafe {
+                self.front = (*node.as_ptr()).next;
+                self.len -= 1;
+                acc = f(acc, &(*node.as_ptr()).value)?;
+            }
+        }
+        R::from_output(acc)
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        no_panic::check(|| {
+            if self.len == 0 {
+                None
+            } else {
+                self.back.map(|node| unsafe {
+                    let ptr = node.as_ptr();
+                    self.back = (*ptr).prev;
+                    self.len -= 1;
+                    &(*ptr).value
+                })
+            }
+        })
+    }
+
+    fn rfold<B, F: FnMut(B, Self::Item) -> B>(self, init: B, mut f: F) -> B {
+        let mut acc = init;
+        let mut back = self.back;
+        for _ in 0..self.len {
+            let Some(node) = back else { break };
+            unsafe {
+                back = (*node.as_ptr()).prev;
+                acc = f(acc, &(*node.as_ptr()).value);
+            }
+        }
+        acc
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a LinkedList<T, A> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+    fn into_iter(self) -> Self::IntoIter {
+        Iter {
+            front: self.first,
+            back: self.last,
+            len: self.len,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+pub struct IterMut<'a, T> {
+    front: Link<T>,
+    back: Link<T>,
+    len: usize,
+    _phantom: PhantomData<&'a mut T>,
+}
+
+// And like a &'a mut T here
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
+impl<T: Debug> Debug for IterMut<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rest = Iter {
+            front: self.front,
+            back: self.back,
+            len: self.len,
+            _phantom: PhantomData,
+        };
+        f.debug_tuple("IterMut").field(&DebugEntries(rest)).finish()
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+    fn next(&mut self) -> Option<Self::Item> {
+        no_panic::check(|| {
+            if self.len == 0 {
+                None
+            } else {
+                self.front.map(|node| unsafe {
+                    let ptr = node.as_ptr();
+                    self.front = (*ptr).next;
+                    self.len -= 1;
+                    &mut (*ptr).value
+                })
+            }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+
+    fn fold<B, F: FnMut(B, Self::Item) -> B>(self, init: B, mut f: F) -> B {
+        let mut acc = init;
+        let mut front = self.front;
+        for _ in 0..self.len {
+            let Some(node) = front else { break };
+            unsafe {
+                front = (*node.as_ptr()).next;
+                acc = f(acc, &mut (*node.as_ptr()).value);
+            }
+        }
+        acc
+    }
+
+    #[cfg(feature = "nightly")]
+    fn try_fold<B, F, R>(&mut self, init: B, mut f: F) -> R
+    where
+        F: FnMut(B, Self::Item) -> R,
+        R: std::ops::Try<Output = B>,
+    {
+        let mut acc = init;
+        while self.len > 0 {
+            let Some(node) = self.front else { break };
+            unsafe {
+                self.front = (*node.as_ptr()).next;
+                self.len -= 1;
+                acc = f(acc, &mut (*node.as_ptr()).value)?;
+            }
+        }
+        R::from_output(acc)
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        no_panic::check(|| {
+            if self.len == 0 {
+                None
+            } else {
+                self.back.map(|node| unsafe {
+                    let ptr = node.as_ptr();
+                    self.back = (*ptr).prev;
+                    self.len -= 1;
+                    &mut (*ptr).value
+                })
+            }
+        })
+    }
+
+    fn rfold<B, F: FnMut(B, Self::Item) -> B>(self, init: B, mut f: F) -> B {
+        let mut acc = init;
+        let mut back = self.back;
+        for _ in 0..self.len {
+            let Some(node) = back else { break };
+            unsafe {
+                back = (*node.as_ptr()).prev;
+                acc = f(acc, &mut (*node.as_ptr()).value);
+            }
+        }
+        acc
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a mut LinkedList<T, A> {
+    type IntoIter = IterMut<'a, T>;
+    type Item = &'a mut T;
+    fn into_iter(self) -> Self::IntoIter {
+        IterMut {
+            front: self.first,
+            back: self.last,
+            len: self.len,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, A: Allocator> LinkedList<T, A> {
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.into_iter()
+    }
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.into_iter()
+    }
+
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|e| e == x)
+    }
+}
+
+// Each chunk is an iterator over the size next values, or fewer for the last
+// one, like the chunks of a slice
+pub struct Chunks<'a, T> {
+    front: Link<T>,
+    len: usize,
+    size: usize,
+    _phantom: PhantomData<&'a T>,
+}
+
+unsafe impl<T: Sync> Send for Chunks<'_, T> {}
+unsafe impl<T: Sync> Sync for Chunks<'_, T> {}
+
+pub struct ChunksMut<'a, T> {
+    front: Link<T>,
+    len: usize,
+    size: usize,
+    _phantom: PhantomData<&'a mut T>,
+}
+
+unsafe impl<T: Send> Send for ChunksMut<'_, T> {}
+unsafe impl<T: Sync> Sync for ChunksMut<'_, T> {}
+
+impl<T, A: Allocator> LinkedList<T, A> {
+    pub fn chunks(&self, size: usize) -> Chunks<'_, T> {
+        assert!(size != 0, "chunk size must be non-zero");
+        Chunks {
+            front: self.first,
+            len: self.len,
+            size,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn chunks_mut(&mut self, size: usize) -> ChunksMut<'_, T> {
+        assert!(size != 0, "chunk size must be non-zero");
+        ChunksMut {
+            front: self.first,
+            len: self.len,
+            size,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+// Walks to the end of the next chunk, and returns its bounds and length
+fn next_chunk<T>(front: &mut Link<T>, len: &mut usize, size: usize) -> (Link<T>, Link<T>, usize) {
+    let first = *front;
+    let n = size.min(*len);
+    let mut last = first;
+    for _ in 1..n {
+        last = last.and_then(|node| unsafe { (*node.as_ptr()).next });
+    }
+    *front = last.and_then(|node| unsafe { (*node.as_ptr()).next });
+    *len -= n;
+    (first, last, n)
+}
+
+impl<'a, T> Iterator for Chunks<'a, T> {
+    type Item = Iter<'a, T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let (front, back, len) = next_chunk(&mut self.front, &mut self.len, self.size);
+        Some(Iter {
+            front,
+            back,
+            len,
+            _phantom: PhantomData,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let chunks = self.len.div_ceil(self.size);
+        (chunks, Some(chunks))
+    }
+}
+
+impl<T> ExactSizeIterator for Chunks<'_, T> {}
+
+impl<T> FusedIterator for Chunks<'_, T> {}
+
+// The chunks never overlap, so each one can borrow its values mutably
+impl<'a, T> Iterator for ChunksMut<'a, T> {
+    type Item = IterMut<'a, T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let (front, back, len) = next_chunk(&mut self.front, &mut self.len, self.size);
+        Some(IterMut {
+            front,
+            back,
+            len,
+            _phantom: PhantomData,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let chunks = self.len.div_ceil(self.size);
+        (chunks, Some(chunks))
+    }
+}
+
+impl<T> ExactSizeIterator for ChunksMut<'_, T> {}
+
+impl<T> FusedIterator for ChunksMut<'_, T> {}
+
+impl<T: PartialEq, A: Allocator> PartialEq for LinkedList<T, A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other)
+    }
+}
+
+impl<T: Eq, A: Allocator> Eq for LinkedList<T, A> {}
+
+impl<T: PartialOrd, A: Allocator> PartialOrd for LinkedList<T, A> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.iter().partial_cmp(other)
+    }
+}
+
+impl<T: Ord, A: Allocator> Ord for LinkedList<T, A> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.iter().cmp(other)
+    }
+}
+
+impl<T: Debug, A: Allocator> Debug for LinkedList<T, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone> Clone for LinkedList<T, A> {
+    fn clone(&self) -> Self {
+        let mut l = LinkedList::new_in(self.alloc.clone());
+        for v in self {
+            l.push_back(v.clone())
+        }
+        l
+    }
+
+    // Clones into the nodes already there, and only allocates or frees the
+    // difference in length
+    fn clone_from(&mut self, source: &Self) {
+        self.truncate(source.len);
+        let mut values = source.iter();
+        for (value, source) in self.iter_mut().zip(&mut values) {
+            value.clone_from(source);
+        }
+        self.extend(values.cloned());
+    }
+}
+
+impl<T: Hash, A: Allocator> Hash for LinkedList<T, A> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        for v in self {
+            v.hash(state);
+        }
+    }
+}
+
+#[cfg(feature = "dot")]
+impl<T: Debug, A: Allocator> LinkedList<T, A> {
+    pub fn write_dot(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        let id = |node: NonNull<Node<T>>| node.as_ptr() as usize;
+        let mut current = self.first;
+        let nodes = std::iter::from_fn(move || {
+            let node = current?;
+            let node_ref = unsafe { node.as_ref() };
+            current = node_ref.next;
+            Some(DotNode {
+                id: id(node),
+                label: format!("{:?}", node_ref.value),
+                next: node_ref.next.map(id),
+                prev: node_ref.prev.map(id),
+            })
+        });
+        dot::write_graph(w, self.first.map(id), self.last.map(id), nodes)
+    }
+
+    pub fn dot(&self) -> String {
+        dot::to_string(|w| self.write_dot(w))
+    }
+}
+
+// A node taken out of a list along with its value, which can be freed with
+// the allocator it keeps. Another list can only take it back in without
+// allocating again when it frees its nodes with the same allocator, which is
+// only known of Global: two instances of another allocator type may not be
+// able to free what the other allocated.
+pub struct OwnedNode<T, A: Allocator = Global> {
+    node: NonNull<Node<T>>,
+    alloc: A,
+}
+
+// It owns the node like a Box would
+unsafe impl<T: Send, A: Allocator + Send> Send for OwnedNode<T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for OwnedNode<T, A> {}
+
+impl<T, A: Allocator> OwnedNode<T, A> {
+    pub fn value(&self) -> &T {
+        unsafe { &(*self.node.as_ptr()).value }
+    }
+
+    pub fn value_mut(&mut self) -> &mut T {
+        unsafe { &mut (*self.node.as_ptr()).value }
+    }
+
+    pub fn into_value(self) -> T {
+        let (node, alloc) = self.into_parts();
+        unsafe { Node::free(node, &alloc).value }
+    }
+
+    fn into_parts(self) -> (NonNull<Node<T>>, A) {
+        let this = ManuallyDrop::new(self);
+        (this.node, unsafe { ptr::read(&this.alloc) })
+    }
+}
+
+impl<T, A: Allocator> Drop for OwnedNode<T, A> {
+    fn drop(&mut self) {
+        unsafe {
+            Node::free(self.node, &self.alloc);
+        }
+    }
+}
+
+impl<T, A: Allocator + Clone> LinkedList<T, A> {
+    pub fn pop_front_node(&mut self) -> Option<OwnedNode<T, A>> {
+        let node = self.first?;
+        Some(self.take_node(node))
+    }
+
+    pub fn pop_back_node(&mut self) -> Option<OwnedNode<T, A>> {
+        let node = self.last?;
+        Some(self.take_node(node))
+    }
+
+    fn take_node(&mut self, node: NonNull<Node<T>>) -> OwnedNode<T, A> {
+        unsafe { self.unlink(node) };
+        #[cfg(feature = "stats")]
+        self.stats.moved_out(1);
+        #[cfg(feature = "tracing")]
+        tracing::trace!(len = self.len, "pop_node");
+        OwnedNode {
+            node,
+            alloc: self.alloc.clone(),
+        }
+    }
+
+    unsafe fn give_node(&mut self, prev: Link<T>, node: NonNull<Node<T>>) {
+        self.link_after(prev, node);
+        #[cfg(feature = "stats")]
+        self.stats.moved_in(1);
+        #[cfg(feature = "tracing")]
+        tracing::trace!(len = self.len, "push_node");
+    }
+}
+
+impl<T> LinkedList<T> {
+    pub fn push_front_node(&mut self, node: OwnedNode<T>) {
+        let (node, _) = node.into_parts();
+        unsafe { self.give_node(None, node) }
+    }
+
+    pub fn push_back_node(&mut self, node: OwnedNode<T>) {
+        let (node, _) = node.into_parts();
+        unsafe { self.give_node(self.last, node) }
+    }
+}
+
+pub struct NodeHandle<T>(NonNull<Node<T>>);
+
+impl<T> Clone for NodeHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for NodeHandle<T> {}
+
+impl<T> PartialEq for NodeHandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T> Eq for NodeHandle<T> {}
+
+// A handle stays valid for as long as its node is in the list that issued it;
+// the unsafe methods below rely on the caller to uphold that
+impl<T, A: Allocator> LinkedList<T, A> {
+    pub fn push_front_handle(&mut self, value: T) -> NodeHandle<T> {
+        unsafe {
+            let node = Node::alloc(value, &self.alloc);
+            self.link_front(node);
+            #[cfg(feature = "stats")]
+            self.stats.allocated();
+            #[cfg(feature = "tracing")]
+            tracing::trace!(len = self.len, "push_front");
+            NodeHandle(node)
+        }
+    }
+
+    pub fn push_back_handle(&mut self, value: T) -> NodeHandle<T> {
+        unsafe {
+            let node = Node::alloc(value, &self.alloc);
+            self.link_back(node);
+            #[cfg(feature = "stats")]
+            self.stats.allocated();
+            #[cfg(feature = "tracing")]
+            tracing::trace!(len = self.len, "push_back");
+            NodeHandle(node)
+        }
+    }
+
+    pub fn front_handle(&self) -> Option<NodeHandle<T>> {
+        self.first.map(NodeHandle)
+    }
+
+    pub fn back_handle(&self) -> Option<NodeHandle<T>> {
+        self.last.map(NodeHandle)
+    }
+
+    /// # Safety
+    ///
+    /// The handle must come from this list, and its node must not have been
+    /// removed since.
+    pub unsafe fn get_handle(&self, handle: NodeHandle<T>) -> &T {
+        &(*handle.0.as_ptr()).value
+    }
+
+    /// # Safety
+    ///
+    /// Same as [`LinkedList::get_handle`].
+    pub unsafe fn get_handle_mut(&mut self, handle: NodeHandle<T>) -> &mut T {
+        &mut (*handle.0.as_ptr()).value
+    }
+
+    /// # Safety
+    ///
+    /// Same as [`LinkedList::get_handle`].
+    pub unsafe fn next_handle(&self, handle: NodeHandle<T>) -> Option<NodeHandle<T>> {
+        (*handle.0.as_ptr()).next.map(NodeHandle)
+    }
+
+    /// # Safety
+    ///
+    /// Same as [`LinkedList::get_handle`].
+    pub unsafe fn prev_handle(&self, handle: NodeHandle<T>) -> Option<NodeHandle<T>> {
+        (*handle.0.as_ptr()).prev.map(NodeHandle)
+    }
+
+    /// Takes the value out in constant time, after which the handle must not
+    /// be used again.
+    ///
+    /// It is unsafe because a handle is only a pointer to its node: finding
+    /// out whether the node is still in this list would take a walk over the
+    /// list, which is what the handle is there to avoid. [`BrandedList`]
+    /// checks that at compile time instead, and removes values safely.
+    ///
+    /// [`BrandedList`]: crate::branded::BrandedList
+    ///
+    /// # Safety
+    ///
+    /// Same as [`LinkedList::get_handle`].
+    pub unsafe fn remove_handle(&mut self, handle: NodeHandle<T>) -> T {
+        self.unlink(handle.0);
+        #[cfg(feature = "stats")]
+        self.stats.freed();
+        #[cfg(feature = "tracing")]
+        tracing::trace!(len = self.len, "remove");
+        Node::free(handle.0, &self.alloc).value
+    }
+
+    /// # Safety
+    ///
+    /// Same as [`LinkedList::get_handle`].
+    pub unsafe fn move_handle_to_front(&mut self, handle: NodeHandle<T>) {
+        if self.first != Some(handle.0) {
+            self.unlink(handle.0);
+            self.link_front(handle.0);
+        }
+    }
+
+    unsafe fn link_front(&mut self, node: NonNull<Node<T>>) {
+        (*node.as_ptr()).prev = None;
+        (*node.as_ptr()).next = self.first;
+        match self.first {
+            None => self.last = Some(node),
+            Some(first) => (*first.as_ptr()).prev = Some(node),
+        }
+        self.first = Some(node);
+        self.len += 1;
+    }
+
+    unsafe fn link_back(&mut self, node: NonNull<Node<T>>) {
+        (*node.as_ptr()).next = None;
+        (*node.as_ptr()).prev = self.last;
+        match self.last {
+            None => self.first = Some(node),
+            Some(last) => (*last.as_ptr()).next = Some(node),
+        }
+        self.last = Some(node);
+        self.len += 1;
+    }
+
+    // Moves all the nodes to a new list, while the counters stay with this one
+    fn take_nodes(&mut self) -> LinkedList<T, A>
+    where
+        A: Clone,
+    {
+        let alloc = self.alloc.clone();
+        #[cfg_attr(not(feature = "stats"), allow(unused_mut))]
+        let mut output = mem::replace(self, LinkedList::new_in(alloc));
+        #[cfg(feature = "stats")]
+        {
+            mem::swap(&mut self.stats, &mut output.stats);
+            self.stats.moved_out(output.len);
+            output.stats.moved_in(output.len);
+        }
+        output
+    }
+
+    // Links the node after prev, or at the front if there is none
+    unsafe fn link_after(&mut self, prev: Link<T>, node: NonNull<Node<T>>) {
+        let next = match prev {
+            None => self.first,
+            Some(prev) => (*prev.as_ptr()).next,
+        };
+        (*node.as_ptr()).prev = prev;
+        (*node.as_ptr()).next = next;
+        match prev {
+            None => self.first = Some(node),
+            Some(prev) => (*prev.as_ptr()).next = Some(node),
+        }
+        match next {
+            None => self.last = Some(node),
+            Some(next) => (*next.as_ptr()).prev = Some(node),
+        }
+        self.len += 1;
+    }
+
+    // Detaches the node from its neighbours without freeing it
+    unsafe fn unlink(&mut self, node: NonNull<Node<T>>) {
+        let node = &mut *node.as_ptr();
+        match node.prev {
+            None => self.first = node.next,
+            Some(prev) => (*prev.as_ptr()).next = node.next,
+        }
+        match node.next {
+            None => self.last = node.prev,
+            Some(next) => (*next.as_ptr()).prev = node.prev,
+        }
+        node.prev = None;
+        node.next = None;
+        self.len -= 1;
+    }
+}
+
+pub struct CursorMut<'a, T, A: Allocator = Global> {
+    list: &'a mut LinkedList<T, A>,
+    current: Link<T>,
+    index: Option<usize>,
+}
+
+// It borrows the list mutably, and the links are only into that list
+unsafe impl<T: Send, A: Allocator + Send> Send for CursorMut<'_, T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for CursorMut<'_, T, A> {}
+
+impl<T: Debug, A: Allocator> Debug for CursorMut<'_, T, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let current = self.current.map(|node| unsafe { &(*node.as_ptr()).value });
+        f.debug_struct("CursorMut")
+            .field("index", &self.index())
+            .field("current", &current)
+            .finish()
+    }
+}
+
+impl<T, A: Allocator> LinkedList<T, A> {
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T, A> {
+        CursorMut {
+            list: self,
+            current: None,
+            index: None,
+        }
+    }
+}
+
+impl<T, A: Allocator> LinkedList<T, A> {
+    /// Points a cursor at the node of the handle, in constant time. The index
+    /// of the cursor is only counted when [`CursorMut::index`] asks for it,
+    /// or when the cursor is split at.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`LinkedList::get_handle`].
+    pub unsafe fn cursor_at_handle(&mut self, handle: NodeHandle<T>) -> CursorMut<'_, T, A> {
+        CursorMut {
+            list: self,
+            current: Some(handle.0),
+            index: None,
+        }
+    }
+
+    // The handle must be the one of the node at the given index
+    pub(crate) unsafe fn cursor_at_indexed_handle(
+        &mut self,
+        handle: NodeHandle<T>,
+        index: usize,
+    ) -> CursorMut<'_, T, A> {
+        CursorMut {
+            list: self,
+            current: Some(handle.0),
+            index: Some(index),
+        }
+    }
+}
+
+// A cursor put on a node by its handle does not know its index, which is left
+// as None until it is needed: it is then the number of nodes before the node
+fn resolve_index<T>(current: Link<T>, index: Option<usize>) -> Option<usize> {
+    let mut node = current?;
+    if index.is_some() {
+        return index;
+    }
+    let mut index = 0;
+    while let Some(prev) = unsafe { (*node.as_ptr()).prev } {
+        node = prev;
+        index += 1;
+    }
+    Some(index)
+}
+
+impl<'a, T, A: Allocator> CursorMut<'a, T, A> {
+    pub fn index(&self) -> Option<usize> {
+        resolve_index(self.current, self.index)
+    }
+
+    pub fn move_next(&mut self) {
+        if let Some(node) = self.current {
+            unsafe {
+                self.current = node.as_ref().next;
+                self.index = self.current.and(self.index).map(|index| index + 1);
+            }
+        } else if !self.list.is_empty() {
+            self.current = self.list.first;
+            self.index = Some(0);
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        if let Some(node) = self.current {
+            unsafe {
+                self.current = node.as_ref().prev;
+                self.index = self.current.and(self.index).map(|index| index - 1);
+            }
+        } else if !self.list.is_empty() {
+            self.current = self.list.last;
+            self.index = Some(self.list.len - 1);
+        }
+    }
+
+    // Moves to the next value that matches, wrapping to the front from the
+    // ghost. Stops at the ghost when there is none.
+    pub fn find<P: FnMut(&T) -> bool>(&mut self, mut predicate: P) -> bool {
+        loop {
+            self.move_next();
+            match self.current {
+                None => return false,
+                Some(node) => {
+                    if predicate(unsafe { &(*node.as_ptr()).value }) {
+                        return true;
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn rfind<P: FnMut(&T) -> bool>(&mut self, mut predicate: P) -> bool {
+        loop {
+            self.move_prev();
+            match self.current {
+                None => return false,
+                Some(node) => {
+                    if predicate(unsafe { &(*node.as_ptr()).value }) {
+                        return true;
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        unsafe { self.current.as_mut().map(|node| &mut node.as_mut().value) }
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        unsafe {
+            let next_node = if let Some(node) = self.current {
+                (*node.as_ptr()).next
+            } else {
+                self.list.first
+            };
+            next_node.map(|node| &mut (*node.as_ptr()).value)
+        }
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        unsafe {
+            let prev_node = if let Some(node) = self.current {
+                (*node.as_ptr()).prev
+            } else {
+                self.list.last
+            };
+            prev_node.map(|node| &mut (*node.as_ptr()).value)
+        }
+    }
+
+    // The cursor moves on to the next value, or to the ghost after the last
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.current?;
+        unsafe {
+            self.current = (*node.as_ptr()).next;
+            if self.current.is_none() {
+                self.index = None;
+            }
+            self.list.unlink(node);
+            #[cfg(feature = "stats")]
+            self.list.stats.freed();
+            #[cfg(feature = "tracing")]
+            tracing::trace!(len = self.list.len, "remove");
+            Some(Node::free(node, &self.list.alloc).value)
+        }
+    }
+
+    // Like remove_current, but the node is kept, in a list of its own
+    pub fn remove_current_as_list(&mut self) -> Option<LinkedList<T, A>>
+    where
+        A: Clone,
+    {
+        let node = self.current?;
+        let mut output = LinkedList::new_in(self.list.alloc.clone());
+        unsafe {
+            self.current = (*node.as_ptr()).next;
+            if self.current.is_none() {
+                self.index = None;
+            }
+            self.list.unlink(node);
+            output.link_after(None, node);
+        }
+        #[cfg(feature = "stats")]
+        {
+            self.list.stats.moved_out(1);
+            output.stats.moved_in(1);
+        }
+        #[cfg(feature = "tracing")]
+        tracing::trace!(len = self.list.len, "remove_as_list");
+        Some(output)
+    }
+
+    pub fn split_before(&mut self) -> LinkedList<T, A>
+    where
+        A: Clone,
+    {
+        let output = if let Some(node) = self.current {
+            let index = self.index().unwrap();
+            let prev = unsafe { (*node.as_ptr()).prev };
+
+            // Self will contain everything after the cursor
+            let self_len = self.list.len - index;
+            let self_first = Some(node);
+            let self_last = self.list.last;
+            let self_index = Some(0);
+
+            // Output will contain everything before the cursor
+            let new_len = self.list.len - self_len;
+            // at the front, there is nothing before the cursor
+            let new_first = prev.and(self.list.first);
+            let new_last = prev;
+
+            unsafe {
+                if let Some(prev) = prev {
+                    (*prev.as_ptr()).next = None;
+                    (*node.as_ptr()).prev = None;
+                }
+            }
+
+            self.list.len = self_len;
+            self.list.first = self_first;
+            self.list.last = self_last;
+            self.index = self_index;
+
+            #[cfg(feature = "stats")]
+            self.list.stats.moved_out(new_len);
+            LinkedList {
+                first: new_first,
+                last: new_last,
+                len: new_len,
+                alloc: self.list.alloc.clone(),
+                _phantom: PhantomData,
+                #[cfg(feature = "stats")]
+                stats: Stats {
+                    nodes: new_len,
+                    peak: new_len,
+                    ..Stats::new()
+                },
+            }
+        } else {
+            self.list.take_nodes()
+        };
+        #[cfg(feature = "tracing")]
+        tracing::trace!(moved = output.len, len = self.list.len, "split_before");
+        output
+    }
+
+    pub fn split_after(&mut self) -> LinkedList<T, A>
+    where
+        A: Clone,
+    {
+        let output = if let Some(node) = self.current {
+            let index = self.index().unwrap();
+            let next = unsafe { (*node.as_ptr()).next };
+
+            // Self will contain everything before the cursor
+            let self_len = index + 1;
+            let self_first = self.list.first;
+            let self_last = Some(node);
+            let self_index = Some(index);
+
+            // Output will contain everything after the cursor
+            let new_len = self.list.len - self_len;
+            let new_first = next;
+            let new_last = next.and(self.list.last);
+
+            unsafe {
+                if let Some(next) = next {
+                    (*next.as_ptr()).prev = None;
+                    (*node.as_ptr()).next = None;
+                }
+            }
+
+            self.list.len = self_len;
+            self.list.first = self_first;
+            self.list.last = self_last;
+            self.index = self_index;
+
+            #[cfg(feature = "stats")]
+            self.list.stats.moved_out(new_len);
+            LinkedList {
+                first: new_first,
+                last: new_last,
+                len: new_len,
+                alloc: self.list.alloc.clone(),
+                _phantom: PhantomData,
+                #[cfg(feature = "stats")]
+                stats: Stats {
+                    nodes: new_len,
+                    peak: new_len,
+                    ..Stats::new()
+                },
+            }
+        } else {
+            self.list.take_nodes()
+        };
+        #[cfg(feature = "tracing")]
+        tracing::trace!(moved = output.len, len = self.list.len, "split_after");
+        output
+    }
+
+    pub fn splice_before(&mut self, mut input: LinkedList<T, A>) {
+        #[cfg(feature = "tracing")]
+        let moved = input.len;
+        #[cfg(feature = "stats")]
+        self.list.stats.moved_in(input.len);
+        unsafe {
+            if input.is_empty() {
+                // they're empty
+            } else if let Some(node) = self.current {
+                // insert
+                let input_first = input.first.take().unwrap();
+                let input_last = input.last.take().unwrap();
+                if let Some(prev) = (*node.as_ptr()).prev {
+                    (*prev.as_ptr()).next = Some(input_first);
+                    (*input_first.as_ptr()).prev = Some(prev);
+                } else {
+                    self.list.first = Some(input_first);
+                }
+                (*node.as_ptr()).prev = Some(input_last);
+                (*input_last.as_ptr()).next = Some(node);
+            } else if let Some(last) = self.list.last {
+                // append
+                let input_first = input.first.take().unwrap();
+                let input_last = input.last.take().unwrap();
+                (*last.as_ptr()).next = Some(input_first);
+                (*input_first.as_ptr()).prev = Some(last);
+                self.list.last = Some(input_last);
+            } else {
+                // we're empty
+                std::mem::swap(self.list, &mut input);
+                #[cfg(feature = "stats")]
+                std::mem::swap(&mut self.list.stats, &mut input.stats);
+            }
+        }
+        self.list.len += input.len;
+        if let Some(index) = self.index.as_mut() {
+            *index += input.len;
+        }
+        input.len = 0;
+        #[cfg(feature = "tracing")]
+        tracing::trace!(moved, len = self.list.len, "splice_before");
+    }
+
+    pub fn splice_after(&mut self, mut input: LinkedList<T, A>) {
+        #[cfg(feature = "tracing")]
+        let moved = input.len;
+        #[cfg(feature = "stats")]
+        self.list.stats.moved_in(input.len);
+        unsafe {
+            if input.is_empty() {
+                // they're empty
+            } else if let Some(node) = self.current {
+                // insert
+                let input_first = input.first.take().unwrap();
+                let input_last = input.last.take().unwrap();
+                if let Some(next) = (*node.as_ptr()).next {
+                    (*next.as_ptr()).prev = Some(input_last);
+                    (*input_last.as_ptr()).next = Some(next);
+                } else {
+                    self.list.last = Some(input_last);
+                }
+                (*node.as_ptr()).next = Some(input_first);
+                (*input_first.as_ptr()).prev = Some(node);
+            } else if let Some(first) = self.list.first {
+                // prepend
+                let input_first = input.first.take().unwrap();
+                let input_last = input.last.take().unwrap();
+                (*first.as_ptr()).prev = Some(input_last);
+                (*input_last.as_ptr()).next = Some(first);
+                self.list.first = Some(input_first);
+            } else {
+                // we're empty
+                std::mem::swap(self.list, &mut input);
+                #[cfg(feature = "stats")]
+                std::mem::swap(&mut self.list.stats, &mut input.stats);
+            }
+        }
+        self.list.len += input.len;
+        input.len = 0;
+        #[cfg(feature = "tracing")]
+        tracing::trace!(moved, len = self.list.len, "splice_after");
+    }
+
+    pub fn insert_before(&mut self, value: T) {
+        let prev = match self.current {
+            None => self.list.last,
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+        };
+        let node = Node::alloc(value, &self.list.alloc);
+        unsafe { self.list.link_after(prev, node) };
+        #[cfg(feature = "stats")]
+        self.list.stats.allocated();
+        if let Some(index) = self.index.as_mut() {
+            *index += 1;
+        }
+        #[cfg(feature = "tracing")]
+        tracing::trace!(len = self.list.len, "insert_before");
+    }
+
+    pub fn insert_after(&mut self, value: T) {
+        let node = Node::alloc(value, &self.list.alloc);
+        unsafe { self.list.link_after(self.current, node) };
+        #[cfg(feature = "stats")]
+        self.list.stats.allocated();
+        #[cfg(feature = "tracing")]
+        tracing::trace!(len = self.list.len, "insert_after");
+    }
+
+    // Like splice_before and splice_after, but the nodes are allocated and
+    // linked one by one, without building a list first. If the iterator
+    // panics, the values it gave are already in the list.
+    pub fn splice_before_iter<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut prev = match self.current {
+            None => self.list.last,
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+        };
+        for value in iter {
+            let node = Node::alloc(value, &self.list.alloc);
+            unsafe { self.list.link_after(prev, node) };
+            #[cfg(feature = "stats")]
+            self.list.stats.allocated();
+            if let Some(index) = self.index.as_mut() {
+                *index += 1;
+            }
+            prev = Some(node);
+        }
+        #[cfg(feature = "tracing")]
+        tracing::trace!(len = self.list.len, "splice_before_iter");
+    }
+
+    pub fn splice_after_iter<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut prev = self.current;
+        for value in iter {
+            let node = Node::alloc(value, &self.list.alloc);
+            unsafe { self.list.link_after(prev, node) };
+            #[cfg(feature = "stats")]
+            self.list.stats.allocated();
+            prev = Some(node);
+        }
+        #[cfg(feature = "tracing")]
+        tracing::trace!(len = self.list.len, "splice_after_iter");
+    }
+}
+
+// Like CursorMut, but it only reads, so several of them can point into the
+// same list, and the values outlive the cursor
+pub struct Cursor<'a, T, A: Allocator = Global> {
+    list: &'a LinkedList<T, A>,
+    current: Link<T>,
+    index: Option<usize>,
+}
+
+unsafe impl<T: Sync, A: Allocator + Sync> Send for Cursor<'_, T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for Cursor<'_, T, A> {}
+
+impl<T, A: Allocator> Clone for Cursor<'_, T, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, A: Allocator> Copy for Cursor<'_, T, A> {}
+
+impl<T: Debug, A: Allocator> Debug for Cursor<'_, T, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cursor")
+            .field("index", &self.index())
+            .field("current", &self.current())
+            .finish()
+    }
+}
+
+impl<T, A: Allocator> LinkedList<T, A> {
+    pub fn cursor(&self) -> Cursor<'_, T, A> {
+        Cursor {
+            list: self,
+            current: None,
+            index: None,
+        }
+    }
+}
+
+impl<T, A: Allocator> CursorMut<'_, T, A> {
+    pub fn as_cursor(&self) -> Cursor<'_, T, A> {
+        Cursor {
+            list: self.list,
+            current: self.current,
+            index: self.index,
+        }
+    }
+}
+
+impl<'a, T, A: Allocator> Cursor<'a, T, A> {
+    pub fn index(&self) -> Option<usize> {
+        resolve_index(self.current, self.index)
+    }
+
+    pub fn move_next(&mut self) {
+        match self.current {
+            Some(node) => {
+                self.current = unsafe { (*node.as_ptr()).next };
+                self.index = self.current.and(self.index).map(|index| index + 1);
+            }
+            None if !self.list.is_empty() => {
+                self.current = self.list.first;
+                self.index = Some(0);
+            }
+            None => {}
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        match self.current {
+            Some(node) => {
+                self.current = unsafe { (*node.as_ptr()).prev };
+                self.index = self.current.and(self.index).map(|index| index - 1);
+            }
+            None if !self.list.is_empty() => {
+                self.current = self.list.last;
+                self.index = Some(self.list.len - 1);
+            }
+            None => {}
+        }
+    }
+
+    pub fn current(&self) -> Option<&'a T> {
+        self.current.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    pub fn peek_next(&self) -> Option<&'a T> {
+        let next = match self.current {
+            None => self.list.first,
+            Some(node) => unsafe { (*node.as_ptr()).next },
+        };
+        next.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        let prev = match self.current {
+            None => self.list.last,
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+        };
+        prev.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    pub fn as_list(&self) -> &'a LinkedList<T, A> {
+        self.list
+    }
+}
+
+// A cursor that owns its list, so that it can be kept around without
+// borrowing anything. Each method runs the one of CursorMut, on a cursor
+// rebuilt from the position saved here.
+pub struct CursorOwned<T, A: Allocator = Global> {
+    list: LinkedList<T, A>,
+    current: Link<T>,
+    index: Option<usize>,
+}
+
+// Like the list, it owns the nodes it points to
+unsafe impl<T: Send, A: Allocator + Send> Send for CursorOwned<T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for CursorOwned<T, A> {}
+
+impl<T: Debug, A: Allocator> Debug for CursorOwned<T, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let current = self.current.map(|node| unsafe { &(*node.as_ptr()).value });
+        f.debug_struct("CursorOwned")
+            .field("index", &self.index())
+            .field("current", &current)
+            .field("list", &self.list)
+            .finish()
+    }
+}
+
+impl<T, A: Allocator> LinkedList<T, A> {
+    pub fn into_cursor(self) -> CursorOwned<T, A> {
+        CursorOwned {
+            list: self,
+            current: None,
+            index: None,
+        }
+    }
+}
+
+impl<T, A: Allocator> CursorOwned<T, A> {
+    // The index is left unknown until f returns: if it panics after changing
+    // the list, the index is counted again rather than off
+    fn with_cursor<R>(&mut self, f: impl FnOnce(&mut CursorMut<'_, T, A>) -> R) -> R {
+        let mut cursor = CursorMut {
+            list: &mut self.list,
+            current: self.current,
+            index: self.index.take(),
+        };
+        let result = f(&mut cursor);
+        self.current = cursor.current;
+        self.index = cursor.index;
+        result
+    }
+
+    pub fn index(&self) -> Option<usize> {
+        resolve_index(self.current, self.index)
+    }
+
+    pub fn as_list(&self) -> &LinkedList<T, A> {
+        &self.list
+    }
+
+    pub fn move_next(&mut self) {
+        self.with_cursor(|cursor| cursor.move_next());
+    }
+
+    pub fn move_prev(&mut self) {
+        self.with_cursor(|cursor| cursor.move_prev());
+    }
+
+    pub fn find<P: FnMut(&T) -> bool>(&mut self, predicate: P) -> bool {
+        self.with_cursor(|cursor| cursor.find(predicate))
+    }
+
+    pub fn rfind<P: FnMut(&T) -> bool>(&mut self, predicate: P) -> bool {
+        self.with_cursor(|cursor| cursor.rfind(predicate))
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        unsafe { self.current.map(|node| &mut (*node.as_ptr()).value) }
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = match self.current {
+            None => self.list.first,
+            Some(node) => unsafe { (*node.as_ptr()).next },
+        };
+        unsafe { next.map(|node| &mut (*node.as_ptr()).value) }
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let prev = match self.current {
+            None => self.list.last,
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+        };
+        unsafe { prev.map(|node| &mut (*node.as_ptr()).value) }
+    }
+
+    // Removes the current value, and moves to the next one
+    pub fn take_current(&mut self) -> Option<T> {
+        self.with_cursor(|cursor| cursor.remove_current())
+    }
+
+    pub fn insert_before(&mut self, value: T) {
+        self.with_cursor(|cursor| cursor.insert_before(value));
+    }
+
+    pub fn insert_after(&mut self, value: T) {
+        self.with_cursor(|cursor| cursor.insert_after(value));
+    }
+
+    pub fn splice_before_iter<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.with_cursor(|cursor| cursor.splice_before_iter(iter));
+    }
+
+    pub fn splice_after_iter<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.with_cursor(|cursor| cursor.splice_after_iter(iter));
+    }
+
+    pub fn splice_before(&mut self, input: LinkedList<T, A>) {
+        self.with_cursor(|cursor| cursor.splice_before(input));
+    }
+
+    pub fn splice_after(&mut self, input: LinkedList<T, A>) {
+        self.with_cursor(|cursor| cursor.splice_after(input));
+    }
+
+    pub fn split_before(&mut self) -> LinkedList<T, A>
+    where
+        A: Clone,
+    {
+        self.with_cursor(|cursor| cursor.split_before())
+    }
+
+    pub fn split_after(&mut self) -> LinkedList<T, A>
+    where
+        A: Clone,
+    {
+        self.with_cursor(|cursor| cursor.split_after())
+    }
+
+    pub fn into_list(self) -> LinkedList<T, A> {
+        self.list
+    }
+}
+
+impl<T, A: Allocator> From<CursorOwned<T, A>> for LinkedList<T, A> {
+    fn from(cursor: CursorOwned<T, A>) -> Self {
+        cursor.into_list()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for LinkedList<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for LinkedList<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(PushVisitor::new(LinkedList::push_back))
+    }
+}
+
+// Archived as a contiguous sequence, which is accessed without deserializing
+// and deserializes back into a list
+#[cfg(feature = "rkyv")]
+impl<T: rkyv::Archive> rkyv::Archive for LinkedList<T> {
+    type Archived = rkyv::vec::ArchivedVec<T::Archived>;
+    type Resolver = rkyv::vec::VecResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: rkyv::Place<Self::Archived>) {
+        rkyv::vec::ArchivedVec::resolve_from_len(self.len, resolver, out);
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<T, S> rkyv::Serialize<S> for LinkedList<T>
+where
+    T: rkyv::Serialize<S>,
+    S: rkyv::rancor::Fallible + rkyv::ser::Allocator + rkyv::ser::Writer + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        // the values are written straight from the list, as its iterator
+        // knows its length and can be cloned
+        rkyv::vec::ArchivedVec::<T::Archived>::serialize_from_iter::<T, _, _>(
+            self.iter(),
+            serializer,
+        )
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<T, D> rkyv::Deserialize<LinkedList<T>, D> for rkyv::vec::ArchivedVec<T::Archived>
+where
+    T: rkyv::Archive,
+    T::Archived: rkyv::Deserialize<T, D>,
+    D: rkyv::rancor::Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<LinkedList<T>, D::Error> {
+        let mut list = LinkedList::new();
+        for value in self.iter() {
+            list.push_back(value.deserialize(deserializer)?);
+        }
+        Ok(list)
+    }
+}
+
+// The nodes cannot be split in halves without walking to the middle, so the
+// values are gathered in a Vec, which rayon knows how to split
+#[cfg(feature = "rayon")]
+impl<T: Send> IntoParallelIterator for LinkedList<T> {
+    type Item = T;
+    type Iter = rayon::vec::IntoIter<T>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.into_iter().collect::<Vec<_>>().into_par_iter()
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<'a, T: Sync> IntoParallelIterator for &'a LinkedList<T> {
+    type Item = &'a T;
+    type Iter = rayon::vec::IntoIter<&'a T>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.iter().collect::<Vec<_>>().into_par_iter()
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<'a, T: Send> IntoParallelIterator for &'a mut LinkedList<T> {
+    type Item = &'a mut T;
+    type Iter = rayon::vec::IntoIter<&'a mut T>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.iter_mut().collect::<Vec<_>>().into_par_iter()
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T: Send> ParallelExtend<T> for LinkedList<T> {
+    fn par_extend<I: IntoParallelIterator<Item = T>>(&mut self, par_iter: I) {
+        let values: Vec<_> = par_iter.into_par_iter().collect();
+        self.extend(values);
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T: Send> FromParallelIterator<T> for LinkedList<T> {
+    fn from_par_iter<I: IntoParallelIterator<Item = T>>(par_iter: I) -> Self {
+        let mut list = LinkedList::new();
+        list.par_extend(par_iter);
+        list
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, T: arbitrary::Arbitrary<'a>> arbitrary::Arbitrary<'a> for LinkedList<T> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut list = LinkedList::new();
+        for value in u.arbitrary_iter()? {
+            list.push_back(value?);
+        }
+        Ok(list)
+    }
+}
+
+// The nodes are shuffled and relinked in their new order, which moves none
+// of the values
+#[cfg(feature = "rand")]
+impl<T, A: Allocator> LinkedList<T, A> {
+    pub fn shuffle<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let mut nodes = Vec::with_capacity(self.len);
+        let mut current = self.first;
+        while let Some(node) = current {
+            nodes.push(node);
+            current = unsafe { (*node.as_ptr()).next };
+        }
+        nodes.shuffle(rng);
+        let mut prev: Link<T> = None;
+        for &node in &nodes {
+            unsafe {
+                (*node.as_ptr()).prev = prev;
+                if let Some(prev) = prev {
+                    (*prev.as_ptr()).next = Some(node);
+                }
+            }
+            prev = Some(node);
+        }
+        if let Some(last) = prev {
+            unsafe { (*last.as_ptr()).next = None };
+        }
+        self.first = nodes.first().copied();
+        self.last = nodes.last().copied();
+    }
+
+    pub fn choose<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<&T> {
+        if self.is_empty() {
+            return None;
+        }
+        // walk from whichever end is closer
+        let index = rng.gen_range(0..self.len);
+        if index < self.len / 2 {
+            self.iter().nth(index)
+        } else {
+            self.iter().nth_back(self.len - 1 - index)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cmp::Ordering;
+    use std::rc::Rc;
+
+    use super::{CursorOwned, LinkedList};
+
+    fn generate_test() -> LinkedList<i32> {
+        list_from(&[0, 1, 2, 3, 4, 5, 6])
+    }
+
+    fn list_from<T: Clone>(v: &[T]) -> LinkedList<T> {
+        v.iter().map(|x| (*x).clone()).collect()
+    }
+
+    #[test]
+    fn test_basic_front() {
+        let mut list = LinkedList::new();
+
+        // Try to break an empty list
+        assert_eq!(list.len(), 0);
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.len(), 0);
+
+        // Try to break a one item list
+        list.push_front(10);
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.pop_front(), Some(10));
+        assert_eq!(list.len(), 0);
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.len(), 0);
+
+        // Mess around
+        list.push_front(10);
+        assert_eq!(list.len(), 1);
+        list.push_front(20);
+        assert_eq!(list.len(), 2);
+        list.push_front(30);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.pop_front(), Some(30));
+        assert_eq!(list.len(), 2);
+        list.push_front(40);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.pop_front(), Some(40));
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.pop_front(), Some(20));
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.pop_front(), Some(10));
+        assert_eq!(list.len(), 0);
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.len(), 0);
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.len(), 0);
+    }
+
+    #[test]
+    fn test_basic() {
+        let mut m = LinkedList::new();
+        assert_eq!(m.pop_front(), None);
+        assert_eq!(m.pop_back(), None);
+        assert_eq!(m.pop_front(), None);
+        m.push_front(1);
+        assert_eq!(m.pop_front(), Some(1));
+        m.push_back(2);
+        m.push_back(3);
+        assert_eq!(m.len(), 2);
+        assert_eq!(m.pop_front(), Some(2));
+        assert_eq!(m.pop_front(), Some(3));
+        assert_eq!(m.len(), 0);
+        assert_eq!(m.pop_front(), None);
+        m.push_back(1);
+        m.push_back(3);
+        m.push_back(5);
+        m.push_back(7);
+        assert_eq!(m.pop_front(), Some(1));
+
+        let mut n = LinkedList::new();
+        n.push_front(2);
+        n.push_front(3);
+        {
+            assert_eq!(n.front().unwrap(), &3);
+            let x = n.front_mut().unwrap();
+            assert_eq!(*x, 3);
+            *x = 0;
+        }
+        {
+            assert_eq!(n.back().unwrap(), &2);
+            let y = n.back_mut().unwrap();
+            assert_eq!(*y, 2);
+            *y = 1;
+        }
+        assert_eq!(n.pop_front(), Some(0));
+        assert_eq!(n.pop_front(), Some(1));
+    }
+
+    #[test]
+    fn test_iterator() {
+        let m = generate_test();
+        for (i, elt) in m.iter().enumerate() {
+            assert_eq!(i as i32, *elt);
+        }
+        let mut n = LinkedList::new();
+        assert_eq!(n.iter().next(), None);
+        n.push_front(4);
+        let mut it = n.iter();
+        assert_eq!(it.size_hint(), (1, Some(1)));
+        assert_eq!(it.next().unwrap(), &4);
+        assert_eq!(it.size_hint(), (0, Some(0)));
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    fn test_iterator_double_end() {
+        let mut n = LinkedList::new();
+        assert_eq!(n.iter().next(), None);
+        n.push_front(4);
+        n.push_front(5);
+        n.push_front(6);
+        let mut it = n.iter();
+        assert_eq!(it.size_hint(), (3, Some(3)));
+        assert_eq!(it.next().unwrap(), &6);
+        assert_eq!(it.size_hint(), (2, Some(2)));
+        assert_eq!(it.next_back().unwrap(), &4);
+        assert_eq!(it.size_hint(), (1, Some(1)));
+        assert_eq!(it.next_back().unwrap(), &5);
+        assert_eq!(it.next_back(), None);
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    #[allow(clippy::manual_next_back)]
+    fn test_rev_iter() {
+        let m = generate_test();
+        for (i, elt) in m.iter().rev().enumerate() {
+            assert_eq!(6 - i as i32, *elt);
+        }
+        let mut n = LinkedList::new();
+        assert_eq!(n.iter().rev().next(), None);
+        n.push_front(4);
+        let mut it = n.iter().rev();
+        assert_eq!(it.size_hint(), (1, Some(1)));
+        assert_eq!(it.next().unwrap(), &4);
+        assert_eq!(it.size_hint(), (0, Some(0)));
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    fn test_mut_iter() {
+        let mut m = generate_test();
+        let mut len = m.len();
+        for (i, elt) in m.iter_mut().enumerate() {
+            assert_eq!(i as i32, *elt);
+            len -= 1;
+        }
+        assert_eq!(len, 0);
+        let mut n = LinkedList::new();
+        assert!(n.iter_mut().next().is_none());
+        n.push_front(4);
+        n.push_back(5);
+        let mut it = n.iter_mut();
+        assert_eq!(it.size_hint(), (2, Some(2)));
+        assert!(it.next().is_some());
+        assert!(it.next().is_some());
+        assert_eq!(it.size_hint(), (0, Some(0)));
+        assert!(it.next().is_none());
+    }
+
+    #[test]
+    fn test_iterator_mut_double_end() {
+        let mut n = LinkedList::new();
+        assert!(n.iter_mut().next_back().is_none());
+        n.push_front(4);
+        n.push_front(5);
+        n.push_front(6);
+        let mut it = n.iter_mut();
+        assert_eq!(it.size_hint(), (3, Some(3)));
+        assert_eq!(*it.next().unwrap(), 6);
+        assert_eq!(it.size_hint(), (2, Some(2)));
+        assert_eq!(*it.next_back().unwrap(), 4);
+        assert_eq!(it.size_hint(), (1, Some(1)));
+        assert_eq!(*it.next_back().unwrap(), 5);
+        assert!(it.next_back().is_none());
+        assert!(it.next().is_none());
+    }
+
+    #[test]
+    fn test_eq() {
+        let mut n: LinkedList<u8> = list_from(&[]);
+        let mut m = list_from(&[]);
+        assert!(n == m);
+        n.push_front(1);
+        assert!(n != m);
+        m.push_back(1);
+        assert!(n == m);
+
+        let n = list_from(&[2, 3, 4]);
+        let m = list_from(&[1, 2, 3]);
+        assert!(n != m);
+    }
+
+    #[test]
+    fn test_ord() {
+        let n = list_from(&[]);
+        let m = list_from(&[1, 2, 3]);
+        assert!(n < m);
+        assert!(m > n);
+        assert!(n <= n);
+        assert!(n >= n);
+    }
+
+    #[test]
+    #[allow(clippy::neg_cmp_op_on_partial_ord)]
+    fn test_ord_nan() {
+        let nan = f64::NAN;
+        let n = list_from(&[nan]);
+        let m = list_from(&[nan]);
+        assert!(!(n < m));
+        assert!(!(n > m));
+        assert!(!(n <= m));
+        assert!(!(n >= m));
+
+        let n = list_from(&[nan]);
+        let one = list_from(&[1.0f64]);
+        assert!(!(n < one));
+        assert!(!(n > one));
+        assert!(!(n <= one));
+        assert!(!(n >= one));
+
+        let u = list_from(&[1.0f64, 2.0, nan]);
+        let v = list_from(&[1.0f64, 2.0, 3.0]);
+        assert!(!(u < v));
+        assert!(!(u > v));
+        assert!(!(u <= v));
+        assert!(!(u >= v));
+
+        let s = list_from(&[1.0f64, 2.0, 4.0, 2.0]);
+        let t = list_from(&[1.0f64, 2.0, 3.0, 2.0]);
+        assert!(!(s < t));
+        assert!(s > one);
+        assert!(!(s <= one));
+        assert!(s >= one);
+    }
+
+    #[test]
+    fn test_debug() {
+        let list: LinkedList<i32> = (0..10).collect();
+        assert_eq!(format!("{:?}", list), "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]");
+
+        let list: LinkedList<&str> = ["just", "one", "test", "more"].iter().copied().collect();
+        assert_eq!(format!("{:?}", list), r#"["just", "one", "test", "more"]"#);
+    }
+
+    #[test]
+    fn test_hashmap() {
+        // Check that HashMap works with this as a key
+
+        let list1: LinkedList<i32> = (0..10).collect();
+        let list2: LinkedList<i32> = (1..11).collect();
+        let mut map = std::collections::HashMap::new();
+
+        assert_eq!(map.insert(list1.clone(), "list1"), None);
+        assert_eq!(map.insert(list2.clone(), "list2"), None);
+
+        assert_eq!(map.len(), 2);
+
+        assert_eq!(map.get(&list1), Some(&"list1"));
+        assert_eq!(map.get(&list2), Some(&"list2"));
+
+        assert_eq!(map.remove(&list1), Some("list1"));
+        assert_eq!(map.remove(&list2), Some("list2"));
+
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_cursor_move_peek() {
+        let mut m: LinkedList<u32> = LinkedList::new();
+        m.extend([1, 2, 3, 4, 5, 6]);
+        let mut cursor = m.cursor_mut();
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 1));
+        assert_eq!(cursor.peek_next(), Some(&mut 2));
+        assert_eq!(cursor.peek_prev(), None);
+        assert_eq!(cursor.index(), Some(0));
+        cursor.move_prev();
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), Some(&mut 1));
+        assert_eq!(cursor.peek_prev(), Some(&mut 6));
+        assert_eq!(cursor.index(), None);
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 2));
+        assert_eq!(cursor.peek_next(), Some(&mut 3));
+        assert_eq!(cursor.peek_prev(), Some(&mut 1));
+        assert_eq!(cursor.index(), Some(1));
+
+        let mut cursor = m.cursor_mut();
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some(&mut 6));
+        assert_eq!(cursor.peek_next(), None);
+        assert_eq!(cursor.peek_prev(), Some(&mut 5));
+        assert_eq!(cursor.index(), Some(5));
+        cursor.move_next();
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), Some(&mut 1));
+        assert_eq!(cursor.peek_prev(), Some(&mut 6));
+        assert_eq!(cursor.index(), None);
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some(&mut 5));
+        assert_eq!(cursor.peek_next(), Some(&mut 6));
+        assert_eq!(cursor.peek_prev(), Some(&mut 4));
+        assert_eq!(cursor.index(), Some(4));
+    }
+
+    #[test]
+    fn test_cursor_mut_insert() {
+        let mut m: LinkedList<u32> = LinkedList::new();
+        m.extend([1, 2, 3, 4, 5, 6]);
+        let mut cursor = m.cursor_mut();
+        cursor.move_next();
+        cursor.splice_before(Some(7).into_iter().collect());
+        cursor.splice_after(Some(8).into_iter().collect());
+        // check_links(&m);
+        assert_eq!(
+            m.iter().cloned().collect::<Vec<_>>(),
+            &[7, 1, 8, 2, 3, 4, 5, 6]
+        );
+        let mut cursor = m.cursor_mut();
+        cursor.move_next();
+        cursor.move_prev();
+        cursor.splice_before(Some(9).into_iter().collect());
+        cursor.splice_after(Some(10).into_iter().collect());
+        check_links(&m);
+        assert_eq!(
+            m.iter().cloned().collect::<Vec<_>>(),
+            &[10, 7, 1, 8, 2, 3, 4, 5, 6, 9]
+        );
+
+        let mut cursor = m.cursor_mut();
+        cursor.move_next();
+        cursor.move_prev();
+        assert_eq!(cursor.remove_current(), None);
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(7));
+        cursor.move_prev();
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(cursor.remove_current(), Some(9));
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(10));
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.current(), Some(&mut 1));
+        check_links(&m);
+        assert_eq!(
+            m.iter().cloned().collect::<Vec<_>>(),
+            &[1, 8, 2, 3, 4, 5, 6]
+        );
+
+        let mut m: LinkedList<u32> = LinkedList::new();
+        m.extend([1, 8, 2, 3, 4, 5, 6]);
+        let mut cursor = m.cursor_mut();
+        cursor.move_next();
+        let mut p: LinkedList<u32> = LinkedList::new();
+        p.extend([100, 101, 102, 103]);
+        let mut q: LinkedList<u32> = LinkedList::new();
+        q.extend([200, 201, 202, 203]);
+        cursor.splice_after(p);
+        cursor.splice_before(q);
+        check_links(&m);
+        assert_eq!(
+            m.iter().cloned().collect::<Vec<_>>(),
+            &[200, 201, 202, 203, 1, 100, 101, 102, 103, 8, 2, 3, 4, 5, 6]
+        );
+        let mut cursor = m.cursor_mut();
+        cursor.move_next();
+        cursor.move_prev();
+        let tmp = cursor.split_before();
+        assert_eq!(m.into_iter().collect::<Vec<_>>(), &[] as &[u32]);
+        m = tmp;
+        let mut cursor = m.cursor_mut();
+        cursor.move_next();
+        cursor.move_next();
+        cursor.move_next();
+        cursor.move_next();
+        cursor.move_next();
+        cursor.move_next();
+        cursor.move_next();
+        let tmp = cursor.split_after();
+        assert_eq!(
+            tmp.into_iter().collect::<Vec<_>>(),
+            &[102, 103, 8, 2, 3, 4, 5, 6]
+        );
+        check_links(&m);
+        assert_eq!(
+            m.iter().cloned().collect::<Vec<_>>(),
+            &[200, 201, 202, 203, 1, 100, 101]
+        );
+    }
+
+    fn check_links<T: Eq + std::fmt::Debug>(list: &LinkedList<T>) {
+        let from_front: Vec<_> = list.iter().collect();
+        let mut from_back: Vec<_> = list.iter().rev().collect();
+        from_back.reverse();
+        assert_eq!(from_front, from_back);
+    }
+
+    #[test]
+    fn test_recycle_from() {
+        let list = LinkedList::recycle_from(generate_test(), 10..13);
+        assert_eq!(list, list_from(&[10, 11, 12]));
+        let mut list = LinkedList::recycle_from(list, 20..25);
+        assert_eq!(list, list_from(&[20, 21, 22, 23, 24]));
+        list.push_front(19);
+        assert_eq!(list.back(), Some(&24));
+        let list = LinkedList::recycle_from(list, 0..0);
+        assert!(list.is_empty());
+        assert_eq!(list.front(), None);
+        assert_eq!(list.back(), None);
+    }
+
+    #[test]
+    fn test_concatenation() {
+        let lists = vec![
+            list_from(&[1, 2]),
+            LinkedList::new(),
+            list_from(&[3]),
+            list_from(&[4, 5, 6]),
+        ];
+        let mut m: LinkedList<u32> = lists.into_iter().collect();
+        check_links(&m);
+        assert_eq!(m, list_from(&[1, 2, 3, 4, 5, 6]));
+        m.extend([LinkedList::new(), list_from(&[7])]);
+        m.extend(std::iter::empty::<LinkedList<u32>>());
+        assert_eq!(m.len(), 7);
+        assert_eq!(m.back(), Some(&7));
+
+        let empty: LinkedList<u32> = std::iter::repeat_with(LinkedList::new).take(3).collect();
+        assert!(empty.is_empty());
+        check_links(&empty);
+    }
+
+    #[test]
+    fn test_append_and_prepend() {
+        let mut list = list_from(&[3, 4]);
+        let mut other = list_from(&[5]);
+        list.append(&mut other);
+        assert!(other.is_empty());
+        // a list built back to front, then attached in one go
+        for i in (0..3).rev() {
+            other.push_front(i);
         }
-        self.list.len += input.len;
-        input.len = 0;
+        list.prepend(&mut other);
+        assert!(other.is_empty());
+        list.prepend(&mut other);
+        other.append(&mut list);
+        check_links(&other);
+        assert_eq!(other, list_from(&[0, 1, 2, 3, 4, 5]));
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::LinkedList;
+    #[test]
+    fn test_clear() {
+        let mut list = list_from(&[1, 2, 3]);
+        list.clear();
+        check_links(&list);
+        assert!(list.is_empty());
+        list.push_back(4);
+        list.clear();
+        list.clear();
+        assert_eq!(list, LinkedList::new());
+    }
 
-    fn generate_test() -> LinkedList<i32> {
-        list_from(&[0, 1, 2, 3, 4, 5, 6])
+    #[test]
+    fn test_clone_from() {
+        let mut list = list_from(&[1, 2, 3]);
+        list.clone_from(&list_from(&[4, 5]));
+        check_links(&list);
+        assert_eq!(list, list_from(&[4, 5]));
+        list.clone_from(&list_from(&[6, 7, 8, 9]));
+        check_links(&list);
+        assert_eq!(list, list_from(&[6, 7, 8, 9]));
+        list.clone_from(&LinkedList::new());
+        assert!(list.is_empty());
     }
 
-    fn list_from<T: Clone>(v: &[T]) -> LinkedList<T> {
-        v.iter().map(|x| (*x).clone()).collect()
+    #[test]
+    fn test_resize() {
+        let mut list = list_from(&[1, 2, 3, 4]);
+        list.truncate(5);
+        list.truncate(2);
+        check_links(&list);
+        assert_eq!(list, list_from(&[1, 2]));
+        list.resize(4, 0);
+        assert_eq!(list, list_from(&[1, 2, 0, 0]));
+        list.resize(3, 9);
+        assert_eq!(list, list_from(&[1, 2, 0]));
+        let mut next = 10;
+        list.resize_with(5, || {
+            next += 1;
+            next
+        });
+        check_links(&list);
+        assert_eq!(list, list_from(&[1, 2, 0, 11, 12]));
+        list.resize_with(0, || unreachable!());
+        assert!(list.is_empty());
+
+        // the value is moved in last, and only cloned for the others
+        let mut values = LinkedList::new();
+        values.resize(3, Rc::new(()));
+        assert_eq!(Rc::strong_count(values.front().unwrap()), 3);
     }
 
     #[test]
-    fn test_basic_front() {
+    fn test_insert_and_remove() {
         let mut list = LinkedList::new();
+        list.insert(0, 2);
+        list.insert(0, 0);
+        list.insert(2, 4);
+        list.insert(1, 1);
+        list.insert(3, 3);
+        check_links(&list);
+        assert_eq!(list, list_from(&[0, 1, 2, 3, 4]));
+        assert_eq!(list.remove(3), 3);
+        assert_eq!(list.remove(0), 0);
+        assert_eq!(list.remove(2), 4);
+        check_links(&list);
+        assert_eq!(list, list_from(&[1, 2]));
+    }
 
-        // Try to break an empty list
-        assert_eq!(list.len(), 0);
-        assert_eq!(list.pop_front(), None);
-        assert_eq!(list.len(), 0);
+    #[test]
+    fn test_get() {
+        let mut list = list_from(&[0, 1, 2, 3, 4]);
+        for i in 0..5 {
+            assert_eq!(list.get(i), Some(&i));
+        }
+        assert_eq!(list.get(5), None);
+        assert_eq!(list.get(usize::MAX), None);
+        *list.get_mut(1).unwrap() = 10;
+        *list.get_mut(3).unwrap() = 30;
+        assert_eq!(list.get_mut(5), None);
+        assert_eq!(list, list_from(&[0, 10, 2, 30, 4]));
+        assert_eq!(LinkedList::<i32>::new().get(0), None);
+    }
 
-        // Try to break a one item list
-        list.push_front(10);
-        assert_eq!(list.len(), 1);
-        assert_eq!(list.pop_front(), Some(10));
-        assert_eq!(list.len(), 0);
-        assert_eq!(list.pop_front(), None);
-        assert_eq!(list.len(), 0);
+    #[test]
+    fn test_swap() {
+        let mut list = list_from(&[0, 1, 2, 3, 4]);
+        list.swap(0, 4);
+        assert_eq!(list, list_from(&[4, 1, 2, 3, 0]));
+        list.swap(2, 1);
+        assert_eq!(list, list_from(&[4, 2, 1, 3, 0]));
+        list.swap(3, 4);
+        assert_eq!(list, list_from(&[4, 2, 1, 0, 3]));
+        list.swap(0, 1);
+        assert_eq!(list, list_from(&[2, 4, 1, 0, 3]));
+        list.swap(1, 3);
+        list.swap(2, 2);
+        assert_eq!(list, list_from(&[2, 0, 1, 4, 3]));
+        check_links(&list);
 
-        // Mess around
-        list.push_front(10);
-        assert_eq!(list.len(), 1);
-        list.push_front(20);
-        assert_eq!(list.len(), 2);
-        list.push_front(30);
-        assert_eq!(list.len(), 3);
-        assert_eq!(list.pop_front(), Some(30));
-        assert_eq!(list.len(), 2);
-        list.push_front(40);
-        assert_eq!(list.len(), 3);
-        assert_eq!(list.pop_front(), Some(40));
-        assert_eq!(list.len(), 2);
-        assert_eq!(list.pop_front(), Some(20));
-        assert_eq!(list.len(), 1);
-        assert_eq!(list.pop_front(), Some(10));
-        assert_eq!(list.len(), 0);
-        assert_eq!(list.pop_front(), None);
-        assert_eq!(list.len(), 0);
-        assert_eq!(list.pop_front(), None);
-        assert_eq!(list.len(), 0);
+        let mut pair = list_from(&[0, 1]);
+        pair.swap(1, 0);
+        check_links(&pair);
+        assert_eq!(pair, list_from(&[1, 0]));
+        assert_eq!(pair.front(), Some(&1));
+        assert_eq!(pair.back(), Some(&0));
     }
 
     #[test]
-    fn test_basic() {
-        let mut m = LinkedList::new();
-        assert_eq!(m.pop_front(), None);
-        assert_eq!(m.pop_back(), None);
-        assert_eq!(m.pop_front(), None);
-        m.push_front(1);
-        assert_eq!(m.pop_front(), Some(1));
-        m.push_back(2);
-        m.push_back(3);
-        assert_eq!(m.len(), 2);
-        assert_eq!(m.pop_front(), Some(2));
-        assert_eq!(m.pop_front(), Some(3));
-        assert_eq!(m.len(), 0);
-        assert_eq!(m.pop_front(), None);
-        m.push_back(1);
-        m.push_back(3);
-        m.push_back(5);
-        m.push_back(7);
-        assert_eq!(m.pop_front(), Some(1));
+    #[should_panic(expected = "swap index (is 2) should be < len (is 2)")]
+    fn test_swap_out_of_bounds() {
+        list_from(&[1, 2]).swap(0, 2);
+    }
 
-        let mut n = LinkedList::new();
-        n.push_front(2);
-        n.push_front(3);
-        {
-            assert_eq!(n.front().unwrap(), &3);
-            let x = n.front_mut().unwrap();
-            assert_eq!(*x, 3);
-            *x = 0;
-        }
-        {
-            assert_eq!(n.back().unwrap(), &2);
-            let y = n.back_mut().unwrap();
-            assert_eq!(*y, 2);
-            *y = 1;
-        }
-        assert_eq!(n.pop_front(), Some(0));
-        assert_eq!(n.pop_front(), Some(1));
+    #[test]
+    #[should_panic(expected = "insertion index (is 3) should be <= len (is 2)")]
+    fn test_insert_out_of_bounds() {
+        list_from(&[1, 2]).insert(3, 0);
     }
 
     #[test]
-    fn test_iterator() {
-        let m = generate_test();
-        for (i, elt) in m.iter().enumerate() {
-            assert_eq!(i as i32, *elt);
-        }
-        let mut n = LinkedList::new();
-        assert_eq!(n.iter().next(), None);
-        n.push_front(4);
-        let mut it = n.iter();
-        assert_eq!(it.size_hint(), (1, Some(1)));
-        assert_eq!(it.next().unwrap(), &4);
-        assert_eq!(it.size_hint(), (0, Some(0)));
-        assert_eq!(it.next(), None);
+    #[should_panic(expected = "removal index (is 2) should be < len (is 2)")]
+    fn test_remove_out_of_bounds() {
+        list_from(&[1, 2]).remove(2);
     }
 
     #[test]
-    fn test_iterator_double_end() {
-        let mut n = LinkedList::new();
-        assert_eq!(n.iter().next(), None);
-        n.push_front(4);
-        n.push_front(5);
-        n.push_front(6);
-        let mut it = n.iter();
-        assert_eq!(it.size_hint(), (3, Some(3)));
-        assert_eq!(it.next().unwrap(), &6);
-        assert_eq!(it.size_hint(), (2, Some(2)));
-        assert_eq!(it.next_back().unwrap(), &4);
-        assert_eq!(it.size_hint(), (1, Some(1)));
-        assert_eq!(it.next_back().unwrap(), &5);
-        assert_eq!(it.next_back(), None);
-        assert_eq!(it.next(), None);
+    fn test_retain() {
+        let mut list: LinkedList<_> = (0..10).collect();
+        list.retain(|&x| x % 3 != 0);
+        check_links(&list);
+        assert_eq!(list, list_from(&[1, 2, 4, 5, 7, 8]));
+        list.retain_mut(|x| {
+            *x *= 10;
+            *x > 40
+        });
+        check_links(&list);
+        assert_eq!(list, list_from(&[50, 70, 80]));
+        list.retain(|_| false);
+        assert!(list.is_empty());
     }
 
     #[test]
-    fn test_rev_iter() {
-        let m = generate_test();
-        for (i, elt) in m.iter().rev().enumerate() {
-            assert_eq!(6 - i as i32, *elt);
+    fn test_dedup() {
+        let mut list = list_from(&[1, 1, 2, 3, 3, 3, 1, 4, 4]);
+        list.dedup();
+        check_links(&list);
+        assert_eq!(list, list_from(&[1, 2, 3, 1, 4]));
+
+        list.dedup_by_key(|x| *x / 2);
+        assert_eq!(list, list_from(&[1, 2, 1, 4]));
+
+        let mut words = list_from(&["a", "A", "b", "B", "c"]);
+        words.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        check_links(&words);
+        assert_eq!(words, list_from(&["a", "b", "c"]));
+
+        let mut single = list_from(&[1]);
+        single.dedup();
+        assert_eq!(single, list_from(&[1]));
+        LinkedList::<i32>::new().dedup();
+    }
+
+    #[test]
+    fn test_reverse() {
+        for len in 0..4 {
+            let mut list: LinkedList<_> = (0..len).collect();
+            list.reverse();
+            check_links(&list);
+            assert!(list.iter().copied().eq((0..len).rev()));
         }
-        let mut n = LinkedList::new();
-        assert_eq!(n.iter().rev().next(), None);
-        n.push_front(4);
-        let mut it = n.iter().rev();
-        assert_eq!(it.size_hint(), (1, Some(1)));
-        assert_eq!(it.next().unwrap(), &4);
-        assert_eq!(it.size_hint(), (0, Some(0)));
-        assert_eq!(it.next(), None);
+        let mut list = list_from(&[1, 2, 3]);
+        list.reverse();
+        list.push_back(0);
+        list.push_front(4);
+        assert_eq!(list, list_from(&[4, 3, 2, 1, 0]));
     }
 
     #[test]
-    fn test_mut_iter() {
-        let mut m = generate_test();
-        let mut len = m.len();
-        for (i, elt) in m.iter_mut().enumerate() {
-            assert_eq!(i as i32, *elt);
-            len -= 1;
+    fn test_rotate() {
+        let mut list: LinkedList<_> = (0..7).collect();
+        list.rotate_left(2);
+        check_links(&list);
+        assert_eq!(list, list_from(&[2, 3, 4, 5, 6, 0, 1]));
+        list.rotate_left(6);
+        check_links(&list);
+        assert_eq!(list, list_from(&[1, 2, 3, 4, 5, 6, 0]));
+        list.rotate_right(3);
+        check_links(&list);
+        assert_eq!(list, list_from(&[5, 6, 0, 1, 2, 3, 4]));
+        list.rotate_left(0);
+        list.rotate_right(7);
+        assert_eq!(list, list_from(&[5, 6, 0, 1, 2, 3, 4]));
+        LinkedList::<i32>::new().rotate_left(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "rotation amount (is 3) should be <= len (is 2)")]
+    fn test_rotate_too_far() {
+        list_from(&[1, 2]).rotate_right(3);
+    }
+
+    #[test]
+    fn test_extract_if() {
+        let mut list: LinkedList<_> = (0..10).collect();
+        let evens: Vec<_> = list.extract_if(|x| *x % 2 == 0).collect();
+        assert_eq!(evens, [0, 2, 4, 6, 8]);
+        check_links(&list);
+        assert_eq!(list, list_from(&[1, 3, 5, 7, 9]));
+
+        // dropped halfway, the rest is left as it was
+        let mut extract = list.extract_if(|x| {
+            *x += 1;
+            *x > 4
+        });
+        assert_eq!(extract.size_hint(), (0, Some(5)));
+        assert_eq!(extract.next(), Some(6));
+        assert_eq!(extract.size_hint(), (0, Some(2)));
+        check_links(&list);
+        assert_eq!(list, list_from(&[2, 4, 7, 9]));
+
+        let mut empty = LinkedList::<i32>::new();
+        assert_eq!(empty.extract_if(|_| true).size_hint(), (0, Some(0)));
+        assert_eq!(empty.extract_if(|_| true).next(), None);
+    }
+
+    #[test]
+    fn test_owned_nodes() {
+        let mut list = list_from(&[0, 1, 2]);
+        let mut other = LinkedList::new();
+        let mut node = list.pop_back_node().unwrap();
+        assert_eq!(node.value(), &2);
+        *node.value_mut() = 20;
+        other.push_front_node(node);
+        other.push_back_node(list.pop_front_node().unwrap());
+        other.push_front_node(list.pop_front_node().unwrap());
+        assert!(list.is_empty());
+        assert_eq!(list.pop_back_node().map(|node| node.into_value()), None);
+        check_links(&other);
+        assert_eq!(other, list_from(&[1, 20, 0]));
+
+        let node = other.pop_back_node().unwrap();
+        assert_eq!(node.into_value(), 0);
+        // the value goes with the node
+        let value = Rc::new(());
+        let mut values = LinkedList::new();
+        values.push_back(value.clone());
+        drop(values.pop_front_node());
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn test_raw_parts() {
+        let list = list_from(&[0, 1, 2]);
+        let (first, last, len) = list.into_raw_parts();
+        assert_eq!(len, 3);
+        assert!(first.is_some() && last.is_some());
+        let mut list = unsafe { LinkedList::from_raw_parts(first, last, len) };
+        check_links(&list);
+        list.push_back(3);
+        assert_eq!(list, list_from(&[0, 1, 2, 3]));
+
+        let (first, last, len) = LinkedList::<i32>::new().into_raw_parts();
+        assert_eq!((first, last, len), (None, None, 0));
+        assert!(unsafe { LinkedList::<i32>::from_raw_parts(first, last, len) }.is_empty());
+    }
+
+    #[test]
+    fn test_into_inner() {
+        let list: LinkedList<_> = (0..5).collect();
+        let mut iter = list.into_iter();
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.next_back(), Some(4));
+        let mut rest = iter.into_inner();
+        check_links(&rest);
+        assert_eq!(rest, list_from(&[1, 2, 3]));
+        rest.push_front(0);
+        assert_eq!(rest.len(), 4);
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut list: LinkedList<_> = (0..5).collect();
+        assert_eq!(list.drain().collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+        assert!(list.is_empty());
+
+        list.extend(0..5);
+        let mut drain = list.drain();
+        assert_eq!(drain.next(), Some(0));
+        assert_eq!(drain.next_back(), Some(4));
+        drop(drain);
+        check_links(&list);
+        assert!(list.is_empty());
+        list.push_back(5);
+        assert_eq!(list, list_from(&[5]));
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut list = list_from(&[5, 1, 4, 1, 5, 9, 2, 6, 5, 3]);
+        list.sort();
+        check_links(&list);
+        assert_eq!(list, list_from(&[1, 1, 2, 3, 4, 5, 5, 5, 6, 9]));
+
+        for len in 0..20 {
+            let mut list: LinkedList<_> = (0..len).rev().collect();
+            list.sort();
+            check_links(&list);
+            assert!(list.iter().copied().eq(0..len));
         }
-        assert_eq!(len, 0);
-        let mut n = LinkedList::new();
-        assert!(n.iter_mut().next().is_none());
-        n.push_front(4);
-        n.push_back(5);
-        let mut it = n.iter_mut();
-        assert_eq!(it.size_hint(), (2, Some(2)));
-        assert!(it.next().is_some());
-        assert!(it.next().is_some());
-        assert_eq!(it.size_hint(), (0, Some(0)));
-        assert!(it.next().is_none());
     }
 
     #[test]
-    fn test_iterator_mut_double_end() {
-        let mut n = LinkedList::new();
-        assert!(n.iter_mut().next_back().is_none());
-        n.push_front(4);
-        n.push_front(5);
-        n.push_front(6);
-        let mut it = n.iter_mut();
-        assert_eq!(it.size_hint(), (3, Some(3)));
-        assert_eq!(*it.next().unwrap(), 6);
-        assert_eq!(it.size_hint(), (2, Some(2)));
-        assert_eq!(*it.next_back().unwrap(), 4);
-        assert_eq!(it.size_hint(), (1, Some(1)));
-        assert_eq!(*it.next_back().unwrap(), 5);
-        assert!(it.next_back().is_none());
-        assert!(it.next().is_none());
+    fn test_sort_by() {
+        let mut list = list_from(&["ccc", "a", "bb", "dd", "e"]);
+        list.sort_by(|a, b| b.cmp(a));
+        assert_eq!(list, list_from(&["e", "dd", "ccc", "bb", "a"]));
+        list.sort_by_key(|s| s.len());
+        check_links(&list);
+        assert_eq!(list, list_from(&["e", "a", "dd", "bb", "ccc"]));
     }
 
     #[test]
-    fn test_eq() {
-        let mut n: LinkedList<u8> = list_from(&[]);
-        let mut m = list_from(&[]);
-        assert!(n == m);
-        n.push_front(1);
-        assert!(n != m);
-        m.push_back(1);
-        assert!(n == m);
+    fn test_merge() {
+        let a = list_from(&[1, 3, 5, 7]);
+        let b = list_from(&[2, 3, 4, 8, 9]);
+        let merged = a.merge(b);
+        check_links(&merged);
+        assert_eq!(merged, list_from(&[1, 2, 3, 3, 4, 5, 7, 8, 9]));
+        let merged = merged.merge(LinkedList::new());
+        let merged = LinkedList::new().merge(merged);
+        assert_eq!(merged.len(), 9);
 
-        let n = list_from(&[2, 3, 4]);
-        let m = list_from(&[1, 2, 3]);
-        assert!(n != m);
+        // sorted by length only, the values of the first list go first
+        let a = list_from(&["a", "bb", "cc"]);
+        let b = list_from(&["d", "ee", "fff"]);
+        let merged = a.merge_by(b, |x, y| x.len().cmp(&y.len()));
+        check_links(&merged);
+        assert_eq!(merged, list_from(&["a", "d", "bb", "cc", "ee", "fff"]));
     }
 
     #[test]
-    fn test_ord() {
-        let n = list_from(&[]);
-        let m = list_from(&[1, 2, 3]);
-        assert!(n < m);
-        assert!(m > n);
-        assert!(n <= n);
-        assert!(n >= n);
+    fn test_sort_is_stable() {
+        #[derive(Debug)]
+        struct Entry(u8, usize);
+        impl PartialEq for Entry {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+        impl Eq for Entry {}
+        impl PartialOrd for Entry {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for Entry {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.0.cmp(&other.0)
+            }
+        }
+
+        let keys = [3, 1, 2, 3, 1, 1, 2, 3, 2];
+        let mut list: LinkedList<_> = keys.iter().enumerate().map(|(i, &k)| Entry(k, i)).collect();
+        list.sort();
+        let order: Vec<_> = list.iter().map(|entry| entry.1).collect();
+        assert_eq!(order, [1, 4, 5, 2, 6, 8, 0, 3, 7]);
     }
 
     #[test]
-    #[allow(clippy::neg_cmp_op_on_partial_ord)]
-    fn test_ord_nan() {
-        let nan = f64::NAN;
-        let n = list_from(&[nan]);
-        let m = list_from(&[nan]);
-        assert!(!(n < m));
-        assert!(!(n > m));
-        assert!(!(n <= m));
-        assert!(!(n >= m));
+    fn test_send_and_sync() {
+        fn assert_send<T: Send>() {}
+        fn assert_sync<T: Sync>() {}
+        fn assert_both<T: Send + Sync>() {
+            assert_send::<T>();
+            assert_sync::<T>();
+        }
+        assert_both::<LinkedList<i32>>();
+        assert_both::<super::Iter<'_, i32>>();
+        assert_both::<super::IterMut<'_, i32>>();
+        assert_both::<super::IntoIter<i32>>();
+        assert_both::<super::Chunks<'_, i32>>();
+        assert_both::<super::ChunksMut<'_, i32>>();
+        assert_both::<super::Drain<'_, i32>>();
+        assert_both::<super::CursorMut<'_, i32>>();
+        assert_both::<CursorOwned<i32>>();
+        assert_both::<super::Cursor<'_, i32>>();
+        // Cell is Send but not Sync, so only what owns or borrows the cells
+        // mutably can be sent
+        assert_send::<LinkedList<std::cell::Cell<i32>>>();
+        assert_send::<super::IterMut<'_, std::cell::Cell<i32>>>();
+        assert_send::<super::CursorMut<'_, std::cell::Cell<i32>>>();
+    }
 
-        let n = list_from(&[nan]);
-        let one = list_from(&[1.0f64]);
-        assert!(!(n < one));
-        assert!(!(n > one));
-        assert!(!(n <= one));
-        assert!(!(n >= one));
+    #[test]
+    fn test_new_in() {
+        let mut list = LinkedList::new_in(super::Global);
+        list.push_back(1);
+        list.push_front(0);
+        let mut tail = list.clone();
+        list.append(&mut tail);
+        assert_eq!(list, list_from(&[0, 1, 0, 1]));
+        let _: &super::Global = list.allocator();
+        check_links(&list);
+    }
+
+    #[test]
+    fn test_exact_size() {
+        let mut list: LinkedList<_> = (0..5).collect();
+        let mut iter = list.iter();
+        iter.next();
+        iter.next_back();
+        assert_eq!(iter.len(), 3);
+        let pairs: Vec<_> = iter.rev().enumerate().collect();
+        assert_eq!(pairs, [(0, &3), (1, &2), (2, &1)]);
+        assert_eq!(list.iter_mut().len(), 5);
+        assert_eq!(list.chunks(2).len(), 3);
+        assert_eq!(list.chunks_mut(5).len(), 1);
+        assert_eq!(list.drain().len(), 5);
+
+        let list: LinkedList<_> = (0..2).collect();
+        let mut iter = list.into_iter();
+        assert_eq!(iter.len(), 2);
+        iter.next();
+        iter.next();
+        assert_eq!(iter.len(), 0);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+    }
 
-        let u = list_from(&[1.0f64, 2.0, nan]);
-        let v = list_from(&[1.0f64, 2.0, 3.0]);
-        assert!(!(u < v));
-        assert!(!(u > v));
-        assert!(!(u <= v));
-        assert!(!(u >= v));
+    #[test]
+    fn test_std_conversions() {
+        let std_list: std::collections::LinkedList<_> = (0..5).collect();
+        let list = LinkedList::from(std_list);
+        assert_eq!(list, list_from(&[0, 1, 2, 3, 4]));
+        let std_list = std::collections::LinkedList::from(list);
+        assert!(std_list.into_iter().eq(0..5));
+    }
 
-        let s = list_from(&[1.0f64, 2.0, 4.0, 2.0]);
-        let t = list_from(&[1.0f64, 2.0, 3.0, 2.0]);
-        assert!(!(s < t));
-        assert!(s > one);
-        assert!(!(s <= one));
-        assert!(s >= one);
+    #[test]
+    fn test_from_sequences() {
+        let list = LinkedList::from([1, 2, 3]);
+        check_links(&list);
+        assert_eq!(list, list_from(&[1, 2, 3]));
+        assert_eq!(LinkedList::from(vec![1, 2, 3]), list);
+        assert_eq!(LinkedList::from(vec![1, 2, 3].into_boxed_slice()), list);
+        assert!(LinkedList::<i32>::from([]).is_empty());
     }
 
     #[test]
-    fn test_debug() {
-        let list: LinkedList<i32> = (0..10).collect();
-        assert_eq!(format!("{:?}", list), "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]");
+    fn test_into_vec() {
+        let list: LinkedList<_> = (0..100).collect();
+        let values = list.into_vec();
+        assert_eq!(values.capacity(), 100);
+        assert!(values.into_iter().eq(0..100));
+        assert_eq!(Vec::from(list_from(&["a", "b"])), ["a", "b"]);
+        assert_eq!(LinkedList::<i32>::new().into_vec(), Vec::<i32>::new());
+    }
 
-        let list: LinkedList<&str> = vec!["just", "one", "test", "more"]
-            .iter()
-            .copied()
-            .collect();
-        assert_eq!(format!("{:?}", list), r#"["just", "one", "test", "more"]"#);
+    #[test]
+    fn test_fold() {
+        let mut n = generate_test();
+        assert_eq!(n.iter().sum::<i32>(), 21);
+        assert_eq!(n.iter().fold(0, |acc, v| acc * 10 + v), 123456);
+        assert_eq!(n.iter().rfold(0, |acc, v| acc * 10 + v), 6543210);
+        // what was already taken from either end is not folded
+        let mut it = n.iter();
+        it.next();
+        it.next_back();
+        assert_eq!(it.fold(0, |acc, v| acc * 10 + v), 12345);
+        let mut it = n.iter();
+        it.next();
+        it.next_back();
+        assert_eq!(it.rfold(0, |acc, v| acc * 10 + v), 54321);
+
+        let mut it = n.iter();
+        assert_eq!(it.find(|&&v| v == 2), Some(&2));
+        assert_eq!(it.size_hint(), (4, Some(4)));
+        assert!(it.all(|&v| v > 2));
+        assert_eq!(it.next(), None);
+
+        n.iter_mut().for_each(|v| *v += 1);
+        n.iter_mut().rev().fold(0, |acc, v| {
+            *v += acc;
+            *v
+        });
+        assert_eq!(n, list_from(&[28, 27, 25, 22, 18, 13, 7]));
+        let mut it = n.iter_mut();
+        assert_eq!(it.position(|v| *v == 22), Some(3));
+        assert_eq!(it.rfold(0, |acc, v| acc + *v), 38);
+
+        let mut it = n.clone().into_iter();
+        assert_eq!(it.next_back(), Some(7));
+        assert_eq!(it.find(|&v| v < 26), Some(25));
+        assert_eq!(it.sum::<i32>(), 53);
+        let mut it = n.into_iter();
+        assert_eq!(it.next(), Some(28));
+        assert_eq!(
+            it.rfold(Vec::new(), |mut acc, v| {
+                acc.push(v);
+                acc
+            }),
+            [7, 13, 18, 22, 25, 27]
+        );
     }
 
     #[test]
-    fn test_hashmap() {
-        // Check that HashMap works with this as a key
+    fn test_chunks() {
+        let mut m: LinkedList<u32> = (0..10).collect();
+        let chunks: Vec<Vec<u32>> = m.chunks(4).map(|c| c.copied().collect()).collect();
+        assert_eq!(chunks, [vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9]]);
+        assert_eq!(m.chunks(5).size_hint(), (2, Some(2)));
+        assert_eq!(m.chunks(20).count(), 1);
+        // each chunk can be iterated from both ends
+        let mut chunk = m.chunks(3).nth(1).unwrap();
+        assert_eq!(chunk.next_back(), Some(&5));
+        assert_eq!(chunk.next(), Some(&3));
+        assert_eq!(chunk.next_back(), Some(&4));
+        assert_eq!(chunk.next(), None);
 
-        let list1: LinkedList<i32> = (0..10).collect();
-        let list2: LinkedList<i32> = (1..11).collect();
-        let mut map = std::collections::HashMap::new();
+        for (i, chunk) in m.chunks_mut(3).enumerate() {
+            for value in chunk {
+                *value += 100 * i as u32;
+            }
+        }
+        assert_eq!(m, list_from(&[0, 1, 2, 103, 104, 105, 206, 207, 208, 309]));
+        let mut chunks = m.chunks_mut(4);
+        let (a, b) = (chunks.next().unwrap(), chunks.next().unwrap());
+        for (x, y) in a.zip(b) {
+            std::mem::swap(x, y);
+        }
+        assert_eq!(m.front(), Some(&104));
 
-        assert_eq!(map.insert(list1.clone(), "list1"), None);
-        assert_eq!(map.insert(list2.clone(), "list2"), None);
+        let empty: LinkedList<u32> = LinkedList::new();
+        assert_eq!(empty.chunks(1).next().map(|c| c.count()), None);
+    }
 
-        assert_eq!(map.len(), 2);
+    #[test]
+    #[should_panic(expected = "chunk size must be non-zero")]
+    fn test_chunks_of_zero() {
+        let m: LinkedList<u32> = (0..10).collect();
+        m.chunks(0);
+    }
 
-        assert_eq!(map.get(&list1), Some(&"list1"));
-        assert_eq!(map.get(&list2), Some(&"list2"));
+    #[test]
+    fn test_contains() {
+        let list = list_from(&["a", "b"]);
+        assert!(list.contains(&"b"));
+        assert!(!list.contains(&"c"));
+        assert!(!LinkedList::new().contains(&0));
+    }
 
-        assert_eq!(map.remove(&list1), Some("list1"));
-        assert_eq!(map.remove(&list2), Some("list2"));
+    #[test]
+    fn test_debug_iterators_and_cursors() {
+        let mut list: LinkedList<i32> = (0..4).collect();
+        let mut iter = list.iter();
+        iter.next();
+        assert_eq!(format!("{iter:?}"), "Iter([1, 2, 3])");
+        let mut iter = list.iter_mut();
+        iter.next_back();
+        assert_eq!(format!("{iter:?}"), "IterMut([0, 1, 2])");
+        assert_eq!(format!("{:?}", list.drain()), "Drain([0, 1, 2, 3])");
 
-        assert!(map.is_empty());
+        let mut cursor = list_from(&[5, 6]).into_cursor();
+        assert_eq!(
+            format!("{cursor:?}"),
+            "CursorOwned { index: None, current: None, list: [5, 6] }"
+        );
+        cursor.move_next();
+        let mut list = cursor.into_list();
+        let mut cursor = list.cursor_mut();
+        cursor.move_prev();
+        assert_eq!(
+            format!("{cursor:?}"),
+            "CursorMut { index: Some(1), current: Some(6) }"
+        );
+        let mut iter = list.into_iter();
+        iter.next();
+        assert_eq!(format!("{iter:?}"), "IntoIter([6])");
     }
 
     #[test]
-    fn test_cursor_move_peek() {
+    fn test_splice_iter() {
         let mut m: LinkedList<u32> = LinkedList::new();
-        m.extend([1, 2, 3, 4, 5, 6]);
         let mut cursor = m.cursor_mut();
-        cursor.move_next();
-        assert_eq!(cursor.current(), Some(&mut 1));
-        assert_eq!(cursor.peek_next(), Some(&mut 2));
-        assert_eq!(cursor.peek_prev(), None);
-        assert_eq!(cursor.index(), Some(0));
-        cursor.move_prev();
-        assert_eq!(cursor.current(), None);
-        assert_eq!(cursor.peek_next(), Some(&mut 1));
-        assert_eq!(cursor.peek_prev(), Some(&mut 6));
+        cursor.splice_before_iter([3, 4]);
+        cursor.splice_after_iter([1, 2]);
         assert_eq!(cursor.index(), None);
         cursor.move_next();
         cursor.move_next();
         assert_eq!(cursor.current(), Some(&mut 2));
-        assert_eq!(cursor.peek_next(), Some(&mut 3));
-        assert_eq!(cursor.peek_prev(), Some(&mut 1));
-        assert_eq!(cursor.index(), Some(1));
+        cursor.splice_before_iter([10, 11]);
+        assert_eq!(cursor.index(), Some(3));
+        assert_eq!(cursor.current(), Some(&mut 2));
+        cursor.splice_after_iter([20, 21]);
+        assert_eq!(cursor.index(), Some(3));
+        cursor.splice_after_iter([]);
+        cursor.splice_before_iter([]);
+        assert_eq!(cursor.index(), Some(3));
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 20));
+        check_links(&m);
+        assert_eq!(m, list_from(&[1, 10, 11, 2, 20, 21, 3, 4]));
 
         let mut cursor = m.cursor_mut();
         cursor.move_prev();
-        assert_eq!(cursor.current(), Some(&mut 6));
-        assert_eq!(cursor.peek_next(), None);
-        assert_eq!(cursor.peek_prev(), Some(&mut 5));
-        assert_eq!(cursor.index(), Some(5));
+        cursor.splice_after_iter([30]);
+        assert_eq!(cursor.index(), Some(7));
+        cursor.splice_before_iter([40]);
+        assert_eq!(cursor.index(), Some(8));
+        check_links(&m);
+        assert_eq!(m, list_from(&[1, 10, 11, 2, 20, 21, 3, 40, 4, 30]));
+        assert_eq!(m.len(), 10);
+    }
+
+    #[test]
+    fn test_cursor() {
+        let list = list_from(&[1, 2, 3]);
+        let mut cursor = list.cursor();
+        assert_eq!((cursor.index(), cursor.current()), (None, None));
+        assert_eq!(
+            (cursor.peek_next(), cursor.peek_prev()),
+            (Some(&1), Some(&3))
+        );
+        cursor.move_prev();
+        let other = cursor;
         cursor.move_next();
-        assert_eq!(cursor.current(), None);
-        assert_eq!(cursor.peek_next(), Some(&mut 1));
-        assert_eq!(cursor.peek_prev(), Some(&mut 6));
+        cursor.move_next();
+        assert_eq!((cursor.index(), cursor.current()), (Some(0), Some(&1)));
+        assert_eq!(cursor.peek_prev(), None);
+        assert_eq!((other.index(), other.current()), (Some(2), Some(&3)));
+        assert_eq!(cursor.as_list().len(), 3);
+    }
+
+    #[test]
+    fn test_as_cursor() {
+        let mut list = list_from(&[1, 2, 3]);
+        let mut cursor = list.cursor_mut();
+        cursor.move_next();
+        cursor.move_next();
+        fn peek_around(cursor: super::Cursor<'_, i32>) -> (i32, i32) {
+            (*cursor.peek_prev().unwrap(), *cursor.peek_next().unwrap())
+        }
+        assert_eq!(peek_around(cursor.as_cursor()), (1, 3));
+        let mut view = cursor.as_cursor();
+        view.move_next();
+        assert_eq!((view.index(), view.current()), (Some(2), Some(&3)));
+        assert_eq!(
+            format!("{view:?}"),
+            "Cursor { index: Some(2), current: Some(3) }"
+        );
+        // the mutable cursor did not move
+        *cursor.current().unwrap() = 20;
+        assert_eq!(list, list_from(&[1, 20, 3]));
+    }
+
+    #[test]
+    fn test_cursor_find() {
+        let mut m = list_from(&[1, 2, 3, 4, 5, 6]);
+        let mut cursor = m.cursor_mut();
+        assert!(cursor.find(|&x| x % 2 == 0));
+        assert_eq!(cursor.index(), Some(1));
+        // the current value is skipped
+        assert!(cursor.find(|&x| x % 2 == 0));
+        assert_eq!(cursor.current(), Some(&mut 4));
+        cursor.splice_after_iter([10]);
+        assert!(cursor.rfind(|&x| x < 3));
+        assert_eq!(cursor.current(), Some(&mut 2));
+        assert!(!cursor.find(|&x| x > 10));
+        assert_eq!(cursor.index(), None);
+        assert!(cursor.rfind(|&x| x == 10));
+        assert_eq!(cursor.index(), Some(4));
+        assert!(!cursor.rfind(|&x| x == 10));
         assert_eq!(cursor.index(), None);
+        check_links(&m);
+        assert_eq!(m, list_from(&[1, 2, 3, 4, 10, 5, 6]));
+
+        let mut cursor = m.into_cursor();
+        assert!(cursor.rfind(|&x| x == 3));
+        assert_eq!(cursor.index(), Some(2));
+        assert!(!LinkedList::<i32>::new().cursor_mut().find(|_| true));
+    }
+
+    #[test]
+    fn test_cursor_owned() {
+        // a builder that keeps its cursor in a field
+        struct Builder {
+            cursor: CursorOwned<u32>,
+        }
+        let mut builder = Builder {
+            cursor: list_from(&[1, 2, 3]).into_cursor(),
+        };
+        assert_eq!(builder.cursor.index(), None);
+        builder.cursor.insert_after(0);
+        builder.cursor.insert_before(4);
+        builder.cursor.move_next();
+        assert_eq!(builder.cursor.current(), Some(&mut 0));
+        builder.cursor.move_next();
+        builder.cursor.insert_before(10);
+        assert_eq!(builder.cursor.index(), Some(2));
+        assert_eq!(builder.cursor.take_current(), Some(1));
+        assert_eq!(builder.cursor.current(), Some(&mut 2));
+        assert_eq!(builder.cursor.peek_prev(), Some(&mut 10));
+        builder.cursor.insert_after(20);
+        builder.cursor.splice_after(list_from(&[21, 22]));
+        assert_eq!(builder.cursor.peek_next(), Some(&mut 21));
+        check_links(builder.cursor.as_list());
+
+        let mut cursor = builder.cursor;
         cursor.move_prev();
         cursor.move_prev();
-        assert_eq!(cursor.current(), Some(&mut 5));
-        assert_eq!(cursor.peek_next(), Some(&mut 6));
-        assert_eq!(cursor.peek_prev(), Some(&mut 4));
-        assert_eq!(cursor.index(), Some(4));
+        assert_eq!(cursor.current(), Some(&mut 0));
+        cursor.move_next();
+        assert_eq!(cursor.split_before(), list_from(&[0]));
+        assert_eq!(cursor.index(), Some(0));
+        let tail = cursor.split_after();
+        assert_eq!(tail, list_from(&[2, 21, 22, 20, 3, 4]));
+        assert_eq!(cursor.take_current(), Some(10));
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.take_current(), None);
+        cursor.splice_before(tail);
+        let list = LinkedList::from(cursor);
+        check_links(&list);
+        assert_eq!(list, list_from(&[2, 21, 22, 20, 3, 4]));
     }
 
     #[test]
-    fn test_cursor_mut_insert() {
-        let mut m: LinkedList<u32> = LinkedList::new();
-        m.extend([1, 2, 3, 4, 5, 6]);
+    fn test_cursor_split_at_the_ends() {
+        // nothing is before the front or after the back, so both splits hand
+        // out an empty list that can be used like any other
+        let mut m = list_from(&[1, 2, 3]);
         let mut cursor = m.cursor_mut();
         cursor.move_next();
-        cursor.splice_before(Some(7).into_iter().collect());
-        cursor.splice_after(Some(8).into_iter().collect());
-        // check_links(&m);
-        assert_eq!(
-            m.iter().cloned().collect::<Vec<_>>(),
-            &[7, 1, 8, 2, 3, 4, 5, 6]
-        );
+        let mut before = cursor.split_before();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!((before.front(), before.back()), (None, None));
+        before.push_back(0);
+        check_links(&before);
         let mut cursor = m.cursor_mut();
-        cursor.move_next();
         cursor.move_prev();
-        cursor.splice_before(Some(9).into_iter().collect());
-        cursor.splice_after(Some(10).into_iter().collect());
+        let mut after = cursor.split_after();
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!((after.front(), after.back()), (None, None));
+        after.push_front(4);
+        check_links(&after);
         check_links(&m);
-        assert_eq!(
-            m.iter().cloned().collect::<Vec<_>>(),
-            &[10, 7, 1, 8, 2, 3, 4, 5, 6, 9]
-        );
+        assert_eq!(m, list_from(&[1, 2, 3]));
+    }
 
-        /* remove_current not impl'd
+    #[test]
+    fn test_cursor_splice_before_index() {
+        // the values spliced before the cursor come before it, so its index
+        // moves past them
+        let mut m = list_from(&[1, 2, 3]);
         let mut cursor = m.cursor_mut();
         cursor.move_next();
-        cursor.move_prev();
-        assert_eq!(cursor.remove_current(), None);
-        cursor.move_next();
         cursor.move_next();
-        assert_eq!(cursor.remove_current(), Some(7));
-        cursor.move_prev();
-        cursor.move_prev();
+        cursor.splice_before(list_from(&[10, 11]));
+        assert_eq!(cursor.current(), Some(&mut 2));
+        assert_eq!(cursor.index(), Some(3));
         cursor.move_prev();
-        assert_eq!(cursor.remove_current(), Some(9));
-        cursor.move_next();
-        assert_eq!(cursor.remove_current(), Some(10));
+        assert_eq!(cursor.current(), Some(&mut 11));
+        assert_eq!(cursor.index(), Some(2));
+        // at the ghost, the values are appended and there is no index
+        let mut cursor = m.cursor_mut();
+        cursor.splice_before(list_from(&[4]));
+        assert_eq!(cursor.index(), None);
         check_links(&m);
-        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[1, 8, 2, 3, 4, 5, 6]);
-        */
+        assert_eq!(m, list_from(&[1, 10, 11, 2, 3, 4]));
+    }
 
-        let mut m: LinkedList<u32> = LinkedList::new();
-        m.extend([1, 8, 2, 3, 4, 5, 6]);
+    #[test]
+    fn test_cursor_insert() {
+        let mut m = LinkedList::new();
         let mut cursor = m.cursor_mut();
+        cursor.insert_before(2);
+        cursor.insert_after(0);
+        assert_eq!(cursor.index(), None);
         cursor.move_next();
-        let mut p: LinkedList<u32> = LinkedList::new();
-        p.extend([100, 101, 102, 103]);
-        let mut q: LinkedList<u32> = LinkedList::new();
-        q.extend([200, 201, 202, 203]);
-        cursor.splice_after(p);
-        cursor.splice_before(q);
+        cursor.insert_after(1);
+        cursor.insert_before(-1);
+        assert_eq!(cursor.current(), Some(&mut 0));
+        assert_eq!(cursor.index(), Some(1));
+        cursor.move_prev();
+        cursor.move_prev();
+        cursor.insert_after(-2);
         check_links(&m);
-        assert_eq!(
-            m.iter().cloned().collect::<Vec<_>>(),
-            &[200, 201, 202, 203, 1, 100, 101, 102, 103, 8, 2, 3, 4, 5, 6]
-        );
+        assert_eq!(m, list_from(&[-2, -1, 0, 1, 2]));
+    }
+
+    #[test]
+    fn test_remove_current_as_list() {
+        let mut m = list_from(&[1, 2, 3, 4]);
         let mut cursor = m.cursor_mut();
+        cursor.move_prev();
+        cursor.move_prev();
+        let three = cursor.remove_current_as_list().unwrap();
+        assert_eq!(cursor.current(), Some(&mut 4));
+        assert_eq!(cursor.index(), Some(2));
         cursor.move_next();
+        assert_eq!(cursor.remove_current_as_list(), None);
         cursor.move_prev();
-        let tmp = cursor.split_before();
-        assert_eq!(m.into_iter().collect::<Vec<_>>(), &[]);
-        m = tmp;
+        assert_eq!(cursor.remove_current_as_list(), Some(list_from(&[4])));
+        assert_eq!(cursor.index(), None);
+        check_links(&three);
+        assert_eq!(three, list_from(&[3]));
+
+        // the node goes back without being reallocated
+        let value = three.front().unwrap() as *const i32;
         let mut cursor = m.cursor_mut();
         cursor.move_next();
+        cursor.splice_after(three);
+        check_links(&m);
+        assert_eq!(m, list_from(&[1, 3, 2]));
+        assert!(std::ptr::eq(m.iter().nth(1).unwrap(), value));
+    }
+
+    #[test]
+    fn test_handles() {
+        let mut m: LinkedList<u32> = LinkedList::new();
+        let two = m.push_back_handle(2);
+        let one = m.push_front_handle(1);
+        let three = m.push_back_handle(3);
+        unsafe {
+            assert_eq!(m.get_handle(one), &1);
+            *m.get_handle_mut(two) = 20;
+            m.move_handle_to_front(three);
+            check_links(&m);
+            assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[3, 1, 20]);
+            m.move_handle_to_front(three);
+            m.move_handle_to_front(one);
+            check_links(&m);
+            assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[1, 3, 20]);
+            assert_eq!(m.remove_handle(two), 20);
+            check_links(&m);
+            assert_eq!(m.len(), 2);
+            assert_eq!(m.remove_handle(one), 1);
+            assert_eq!(m.remove_handle(three), 3);
+        }
+        assert!(m.is_empty());
+        assert_eq!(m.front(), None);
+        assert_eq!(m.back(), None);
+    }
+
+    #[test]
+    fn test_cursor_at_handle() {
+        let mut list = list_from(&[0, 1]);
+        let two = list.push_back_handle(2);
+        list.push_back(3);
+        let mut cursor = unsafe { list.cursor_at_handle(two) };
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.current(), Some(&mut 2));
         cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 3));
+        assert_eq!(cursor.index(), Some(3));
+        let front = list.front_handle().unwrap();
+        assert_eq!(unsafe { list.cursor_at_handle(front) }.index(), Some(0));
+        assert_eq!(unsafe { list.remove_handle(two) }, 2);
+        assert_eq!(list, list_from(&[0, 1, 3]));
+
+        // the index is counted once the cursor is split at
+        let one = list
+            .front_handle()
+            .and_then(|h| unsafe { list.next_handle(h) });
+        let mut cursor = unsafe { list.cursor_at_handle(one.unwrap()) };
+        cursor.insert_before(4);
+        assert_eq!(cursor.split_before(), list_from(&[0, 4]));
+        assert_eq!(cursor.index(), Some(0));
         cursor.move_next();
+        assert_eq!(cursor.index(), Some(1));
+        assert_eq!(list, list_from(&[1, 3]));
+    }
+
+    #[cfg(feature = "dot")]
+    #[test]
+    fn test_dot() {
+        let mut list: LinkedList<i32> = (1..=3).collect();
+        let mut cursor = list.cursor_mut();
         cursor.move_next();
-        cursor.move_next();
-        cursor.move_next();
-        cursor.move_next();
-        let tmp = cursor.split_after();
-        assert_eq!(
-            tmp.into_iter().collect::<Vec<_>>(),
-            &[102, 103, 8, 2, 3, 4, 5, 6]
-        );
-        check_links(&m);
-        assert_eq!(
-            m.iter().cloned().collect::<Vec<_>>(),
-            &[200, 201, 202, 203, 1, 100, 101]
-        );
+        cursor.splice_after((10..12).collect());
+        let dot = list.dot();
+        assert_eq!(dot.matches("[label=next]").count(), 4);
+        assert_eq!(dot.matches("[label=prev, style=dashed]").count(), 4);
+        for value in [1, 10, 11, 2, 3] {
+            assert!(dot.contains(&format!("| {value}}}")));
+        }
+        assert!(dot.contains("first -> n"));
+        assert!(dot.contains("last -> n"));
     }
 
-    fn check_links<T: Eq + std::fmt::Debug>(list: &LinkedList<T>) {
-        let from_front: Vec<_> = list.iter().collect();
-        let mut from_back: Vec<_> = list.iter().rev().collect();
-        from_back.reverse();
-        assert_eq!(from_front, from_back);
+    #[test]
+    fn test_const_new() {
+        thread_local! {
+            static LIST: std::cell::RefCell<LinkedList<i32>> =
+                const { std::cell::RefCell::new(LinkedList::new()) };
+        }
+        LIST.with_borrow_mut(|list| {
+            list.push_back(1);
+            list.push_front(0);
+            assert_eq!(list.len(), 2);
+            assert_eq!(list.pop_back(), Some(1));
+        });
+    }
+}
+
+// Run with cargo kani. Each harness checks every sequence of its operations
+// on lists of at most MAX_LEN values, and Kani checks on top of the assertions
+// that no node is used after it is freed, or freed twice.
+#[cfg(kani)]
+mod proofs {
+    use super::LinkedList;
+
+    const MAX_LEN: usize = 3;
+
+    // Walks the next links, checking that the prev links and the fields of
+    // the list agree with them
+    fn check_links<T>(list: &LinkedList<T>) {
+        let mut len = 0;
+        let mut prev = None;
+        let mut cur = list.first;
+        while let Some(node) = cur {
+            unsafe {
+                assert!((*node.as_ptr()).prev == prev);
+                prev = cur;
+                cur = (*node.as_ptr()).next;
+            }
+            len += 1;
+        }
+        assert!(list.last == prev);
+        assert!(list.len == len);
+    }
+
+    fn any_list(max_len: usize, first_value: u8) -> LinkedList<u8> {
+        let len: usize = kani::any();
+        kani::assume(len <= max_len);
+        let mut list = LinkedList::new();
+        for value in 0..len as u8 {
+            list.push_back(first_value + value);
+        }
+        list
+    }
+
+    #[kani::proof]
+    #[kani::unwind(5)]
+    fn push_and_pop() {
+        // the model is a window in an array that can grow either way
+        let mut model = [0u8; 2 * MAX_LEN + 1];
+        let (mut start, mut end) = (MAX_LEN, MAX_LEN);
+        let mut list = LinkedList::new();
+        for _ in 0..MAX_LEN + 1 {
+            let value: u8 = kani::any();
+            match kani::any::<u8>() % 4 {
+                0 if end - start < MAX_LEN => {
+                    list.push_front(value);
+                    start -= 1;
+                    model[start] = value;
+                }
+                1 if end - start < MAX_LEN => {
+                    list.push_back(value);
+                    model[end] = value;
+                    end += 1;
+                }
+                2 => {
+                    let expected = (start < end).then(|| model[start]);
+                    assert!(list.pop_front() == expected);
+                    start += usize::from(start < end);
+                }
+                _ => {
+                    let expected = (start < end).then(|| model[end - 1]);
+                    assert!(list.pop_back() == expected);
+                    end -= usize::from(start < end);
+                }
+            }
+            check_links(&list);
+            assert!(list.front() == model[start..end].first());
+            assert!(list.back() == model[start..end].last());
+        }
+    }
+
+    #[kani::proof]
+    #[kani::unwind(5)]
+    fn split() {
+        let mut list = any_list(MAX_LEN, 0);
+        let len = list.len();
+        let moves: usize = kani::any();
+        kani::assume(moves <= len);
+        let before: bool = kani::any();
+
+        let mut cursor = list.cursor_mut();
+        for _ in 0..moves {
+            cursor.move_next();
+        }
+        let index = cursor.index();
+        let other = if before {
+            cursor.split_before()
+        } else {
+            cursor.split_after()
+        };
+        check_links(&list);
+        check_links(&other);
+        assert!(list.len() + other.len() == len);
+        match (index, before) {
+            (None, _) => assert!(list.is_empty()),
+            (Some(index), true) => assert!(other.len() == index),
+            (Some(index), false) => assert!(list.len() == index + 1),
+        }
+    }
+
+    #[kani::proof]
+    #[kani::unwind(5)]
+    fn splice() {
+        let mut list = any_list(2, 0);
+        let input = any_list(2, 10);
+        let (len, input_len) = (list.len(), input.len());
+        let input_front = input.front().copied();
+        let moves: usize = kani::any();
+        kani::assume(moves <= len);
+        let before: bool = kani::any();
+
+        let mut cursor = list.cursor_mut();
+        for _ in 0..moves {
+            cursor.move_next();
+        }
+        let current = cursor.current().copied();
+        let index = cursor.index();
+        if before {
+            cursor.splice_before(input);
+        } else {
+            cursor.splice_after(input);
+        }
+        assert!(cursor.current().copied() == current);
+        match index {
+            Some(index) if before => assert!(cursor.index() == Some(index + input_len)),
+            _ => assert!(cursor.index() == index),
+        }
+        if !before && input_len > 0 {
+            assert!(cursor.peek_next().copied() == input_front);
+        }
+        check_links(&list);
+        assert!(list.len() == len + input_len);
     }
 }
@@ -0,0 +1,314 @@
+use std::rc::Rc;
+
+use crate::third;
+
+// A list of complete binary trees whose sizes follow the skew binary
+// decomposition of the length: all sizes are of the form 2^k - 1, and only
+// the two smallest trees may have the same size. Elements are stored in
+// preorder, so the head of the list is the root of the first tree.
+pub struct List<T> {
+    trees: third::List<Digit<T>>,
+    len: usize,
+}
+
+struct Digit<T> {
+    size: usize,
+    tree: Rc<Tree<T>>,
+}
+
+enum Tree<T> {
+    Leaf(T),
+    Node(T, Rc<Tree<T>>, Rc<Tree<T>>),
+}
+
+impl<T> Clone for Digit<T> {
+    fn clone(&self) -> Self {
+        Digit {
+            size: self.size,
+            tree: self.tree.clone(),
+        }
+    }
+}
+
+impl<T> List<T> {
+    pub fn new() -> Self {
+        List {
+            trees: third::List::new(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn prepend(&self, value: T) -> Self {
+        let first = self.trees.head();
+        let second = self.trees.tail();
+        let trees = match (first, second.head()) {
+            (Some(first), Some(second)) if first.size == second.size => {
+                self.trees.tail().tail().prepend(Digit {
+                    size: 1 + first.size + second.size,
+                    tree: Rc::new(Tree::Node(value, first.tree.clone(), second.tree.clone())),
+                })
+            }
+            _ => self.trees.prepend(Digit {
+                size: 1,
+                tree: Rc::new(Tree::Leaf(value)),
+            }),
+        };
+        List {
+            trees,
+            len: self.len + 1,
+        }
+    }
+
+    pub fn head(&self) -> Option<&T> {
+        self.trees.head().map(|digit| match &*digit.tree {
+            Tree::Leaf(value) | Tree::Node(value, _, _) => value,
+        })
+    }
+
+    pub fn tail(&self) -> Self {
+        let Some(first) = self.trees.head() else {
+            return List::new();
+        };
+        let trees = match &*first.tree {
+            Tree::Leaf(_) => self.trees.tail(),
+            Tree::Node(_, left, right) => {
+                let size = first.size / 2;
+                self.trees
+                    .tail()
+                    .prepend(Digit {
+                        size,
+                        tree: right.clone(),
+                    })
+                    .prepend(Digit {
+                        size,
+                        tree: left.clone(),
+                    })
+            }
+        };
+        List {
+            trees,
+            len: self.len - 1,
+        }
+    }
+
+    pub fn get(&self, mut index: usize) -> Option<&T> {
+        for digit in self.trees.iter() {
+            if index < digit.size {
+                return Some(get_in_tree(&digit.tree, digit.size, index));
+            }
+            index -= digit.size;
+        }
+        None
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            trees: self.trees.iter(),
+            stack: Vec::new(),
+        }
+    }
+}
+
+impl<T: Clone> List<T> {
+    // Only copies the path to the updated element, so the result shares most
+    // of its nodes with self
+    pub fn update(&self, mut index: usize, value: T) -> Self {
+        assert!(
+            index < self.len,
+            "index (is {index}) should be < len (is {})",
+            self.len
+        );
+        let mut before = Vec::new();
+        let mut trees = self.trees.clone();
+        loop {
+            let digit = trees.head().unwrap().clone();
+            trees = trees.tail();
+            if index < digit.size {
+                trees = trees.prepend(Digit {
+                    size: digit.size,
+                    tree: update_in_tree(&digit.tree, digit.size, index, value),
+                });
+                break;
+            }
+            index -= digit.size;
+            before.push(digit);
+        }
+        for digit in before.into_iter().rev() {
+            trees = trees.prepend(digit);
+        }
+        List {
+            trees,
+            len: self.len,
+        }
+    }
+}
+
+fn get_in_tree<T>(tree: &Tree<T>, size: usize, index: usize) -> &T {
+    match tree {
+        Tree::Leaf(value) => value,
+        Tree::Node(value, left, right) => {
+            let half = size / 2;
+            if index == 0 {
+                value
+            } else if index <= half {
+                get_in_tree(left, half, index - 1)
+            } else {
+                get_in_tree(right, half, index - 1 - half)
+            }
+        }
+    }
+}
+
+fn update_in_tree<T: Clone>(tree: &Tree<T>, size: usize, index: usize, new: T) -> Rc<Tree<T>> {
+    Rc::new(match tree {
+        Tree::Leaf(_) => Tree::Leaf(new),
+        Tree::Node(value, left, right) => {
+            let half = size / 2;
+            if index == 0 {
+                Tree::Node(new, left.clone(), right.clone())
+            } else if index <= half {
+                let left = update_in_tree(left, half, index - 1, new);
+                Tree::Node(value.clone(), left, right.clone())
+            } else {
+                let right = update_in_tree(right, half, index - 1 - half, new);
+                Tree::Node(value.clone(), left.clone(), right)
+            }
+        }
+    })
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for List<T> {
+    fn clone(&self) -> Self {
+        List {
+            trees: self.trees.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let values: Vec<T> = iter.into_iter().collect();
+        values
+            .into_iter()
+            .rev()
+            .fold(List::new(), |list, value| list.prepend(value))
+    }
+}
+
+pub struct Iter<'a, T> {
+    trees: third::Iter<'a, Digit<T>>,
+    stack: Vec<&'a Tree<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.stack.is_empty() {
+            self.stack.push(&self.trees.next()?.tree);
+        }
+        match self.stack.pop()? {
+            Tree::Leaf(value) => Some(value),
+            Tree::Node(value, left, right) => {
+                self.stack.push(right);
+                self.stack.push(left);
+                Some(value)
+            }
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a List<T> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::List;
+
+    #[test]
+    fn basics() {
+        let list = List::new();
+        assert!(list.is_empty());
+        assert_eq!(list.head(), None);
+        assert_eq!(list.get(0), None);
+        assert!(list.tail().is_empty());
+
+        let list = list.prepend(3).prepend(2).prepend(1);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.head(), Some(&1));
+        assert_eq!(list.get(2), Some(&3));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        let list = list.tail();
+        assert_eq!(list.head(), Some(&2));
+        let list = list.tail().tail();
+        assert!(list.is_empty());
+        assert_eq!(list.head(), None);
+    }
+
+    #[test]
+    fn indexing() {
+        for len in 0..100 {
+            let list: List<_> = (0..len).collect();
+            assert_eq!(list.len(), len);
+            for i in 0..len {
+                assert_eq!(list.get(i), Some(&i));
+            }
+            assert_eq!(list.get(len), None);
+            assert_eq!(
+                list.iter().copied().collect::<Vec<_>>(),
+                (0..len).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn tail_keeps_order() {
+        let mut list: List<_> = (0..1000).collect();
+        for i in 0..1000 {
+            assert_eq!(list.head(), Some(&i));
+            assert_eq!(list.get(999 - i), Some(&999));
+            list = list.tail();
+        }
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn update() {
+        let base: List<_> = (0..100).collect();
+        let mut list = base.clone();
+        for i in 0..100 {
+            list = list.update(i, i * 10);
+        }
+        for i in 0..100 {
+            assert_eq!(list.get(i), Some(&(i * 10)));
+            assert_eq!(base.get(i), Some(&i));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn update_out_of_bounds() {
+        let list: List<_> = (0..3).collect();
+        list.update(3, 0);
+    }
+}
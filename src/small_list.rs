@@ -0,0 +1,348 @@
+use std::iter::Chain;
+use std::mem::{self, MaybeUninit};
+use std::ptr;
+use std::slice;
+
+use crate::sixth::{self, LinkedList};
+use crate::traits::HeapSize;
+
+// The first N elements are stored inline, the rest in a linked list. The
+// linked part is only used when the inline part is full, so a list that
+// never grows past N elements never allocates.
+pub struct SmallList<T, const N: usize> {
+    inline: [MaybeUninit<T>; N],
+    inline_len: usize,
+    spill: LinkedList<T>,
+}
+
+impl<T, const N: usize> SmallList<T, N> {
+    pub fn new() -> Self {
+        SmallList {
+            inline: [const { MaybeUninit::uninit() }; N],
+            inline_len: 0,
+            spill: LinkedList::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.inline_len + self.spill.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn spilled(&self) -> bool {
+        !self.spill.is_empty()
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        // with no inline space, everything is in the spill
+        self.inline().first().or_else(|| self.spill.front())
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.spill.back().or_else(|| self.inline().last())
+    }
+
+    pub fn push_back(&mut self, value: T) {
+        if self.inline_len < N {
+            self.inline[self.inline_len].write(value);
+            self.inline_len += 1;
+        } else {
+            self.spill.push_back(value);
+        }
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if let Some(value) = self.spill.pop_back() {
+            return Some(value);
+        }
+        if self.inline_len == 0 {
+            return None;
+        }
+        self.inline_len -= 1;
+        Some(unsafe { self.inline[self.inline_len].assume_init_read() })
+    }
+
+    pub fn push_front(&mut self, value: T) {
+        if N == 0 {
+            self.spill.push_front(value);
+            return;
+        }
+        if self.inline_len == N {
+            // make room by moving the last inline element to the linked part
+            self.inline_len -= 1;
+            let last = unsafe { self.inline[self.inline_len].assume_init_read() };
+            self.spill.push_front(last);
+        }
+        unsafe {
+            let base = self.inline.as_mut_ptr();
+            ptr::copy(base, base.add(1), self.inline_len);
+        }
+        self.inline[0].write(value);
+        self.inline_len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.inline_len == 0 {
+            return self.spill.pop_front();
+        }
+        let value = unsafe { self.inline[0].assume_init_read() };
+        self.inline_len -= 1;
+        unsafe {
+            let base = self.inline.as_mut_ptr();
+            ptr::copy(base.add(1), base, self.inline_len);
+        }
+        // keep the inline part full while there are linked elements
+        if let Some(next) = self.spill.pop_front() {
+            self.inline[self.inline_len].write(next);
+            self.inline_len += 1;
+        }
+        Some(value)
+    }
+
+    pub fn clear(&mut self) {
+        // the linked part goes first, so that if a drop panics what is left
+        // is still a valid list
+        drop(mem::take(&mut self.spill));
+        let inline = self.inline_mut() as *mut [T];
+        self.inline_len = 0;
+        unsafe { ptr::drop_in_place(inline) };
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self.inline().iter().chain(self.spill.iter()))
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let inline = unsafe {
+            slice::from_raw_parts_mut(self.inline.as_mut_ptr() as *mut T, self.inline_len)
+        };
+        IterMut(inline.iter_mut().chain(self.spill.iter_mut()))
+    }
+
+    fn inline(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.inline.as_ptr() as *const T, self.inline_len) }
+    }
+
+    fn inline_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.inline.as_mut_ptr() as *mut T, self.inline_len) }
+    }
+}
+
+impl<T, const N: usize> Default for SmallList<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The inline part is inside the list itself, so only the linked part counts
+impl<T, const N: usize> HeapSize for SmallList<T, N> {
+    fn heap_size(&self) -> usize {
+        self.spill.heap_size()
+    }
+}
+
+// The inline values are dropped in place, which keeps going if one of them
+// panics, and the linked part is dropped with the fields after
+#[cfg(not(feature = "nightly"))]
+impl<T, const N: usize> Drop for SmallList<T, N> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.inline_mut()) };
+    }
+}
+
+// The linked part owns values of type T, which tells the compiler that the
+// inline ones are dropped too
+#[cfg(feature = "nightly")]
+unsafe impl<#[may_dangle] T, const N: usize> Drop for SmallList<T, N> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.inline_mut()) };
+    }
+}
+
+impl<T, const N: usize> Extend<T> for SmallList<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push_back(value);
+        }
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for SmallList<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = SmallList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+pub struct Iter<'a, T>(Chain<slice::Iter<'a, T>, sixth::Iter<'a, T>>);
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a SmallList<T, N> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct IterMut<'a, T>(Chain<slice::IterMut<'a, T>, sixth::IterMut<'a, T>>);
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut SmallList<T, N> {
+    type IntoIter = IterMut<'a, T>;
+    type Item = &'a mut T;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+pub struct IntoIter<T, const N: usize>(SmallList<T, N>);
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.pop_back()
+    }
+}
+
+impl<T, const N: usize> IntoIterator for SmallList<T, N> {
+    type IntoIter = IntoIter<T, N>;
+    type Item = T;
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SmallList;
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+
+    #[test]
+    fn basics() {
+        let mut list: SmallList<i32, 2> = SmallList::new();
+        assert!(list.is_empty());
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.pop_back(), None);
+
+        list.push_back(2);
+        list.push_front(1);
+        assert!(!list.spilled());
+        list.push_back(3);
+        assert!(list.spilled());
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.front(), Some(&1));
+        assert_eq!(list.back(), Some(&3));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        assert_eq!(list.pop_front(), Some(1));
+        assert!(!list.spilled());
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![2, 3]);
+        for value in list.iter_mut() {
+            *value *= 10;
+        }
+        assert_eq!(list.pop_back(), Some(30));
+        assert_eq!(list.pop_back(), Some(20));
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn zero_inline() {
+        let mut list: SmallList<i32, 0> = (1..=3).collect();
+        list.push_front(0);
+        assert!(list.spilled());
+        assert!(!list.is_empty());
+        assert_eq!(list.front(), Some(&0));
+        assert_eq!(list.back(), Some(&3));
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn against_vec_deque() {
+        let mut x: u64 = 3;
+        let mut random = |bound: u64| {
+            x = x
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (x >> 33) % bound
+        };
+        let mut list: SmallList<u64, 4> = SmallList::new();
+        let mut expected = VecDeque::new();
+        for i in 0..10_000 {
+            match random(4) {
+                0 => {
+                    list.push_front(i);
+                    expected.push_front(i);
+                }
+                1 => {
+                    list.push_back(i);
+                    expected.push_back(i);
+                }
+                2 => assert_eq!(list.pop_front(), expected.pop_front()),
+                _ => assert_eq!(list.pop_back(), expected.pop_back()),
+            }
+            assert_eq!(list.len(), expected.len());
+            assert_eq!(list.front(), expected.front());
+            assert_eq!(list.back(), expected.back());
+        }
+        assert!(list.iter().eq(expected.iter()));
+        assert!(list.iter().rev().eq(expected.iter().rev()));
+    }
+
+    #[test]
+    fn drops_everything() {
+        let value = Rc::new(());
+        let mut list: SmallList<_, 3> = (0..10).map(|_| value.clone()).collect();
+        list.pop_front();
+        assert_eq!(Rc::strong_count(&value), 10);
+        drop(list);
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+}
@@ -0,0 +1,290 @@
+#[cfg(feature = "stats")]
+use crate::stats::Stats;
+use crate::traits::{FromValues, HeapSize, SortedList};
+
+type Link<T> = Option<Box<Node<T>>>;
+
+struct Node<T> {
+    value: T,
+    next: Link<T>,
+}
+
+pub struct List<T> {
+    head: Link<T>,
+    #[cfg(feature = "stats")]
+    stats: Stats,
+}
+
+impl<T> List<T> {
+    pub fn new() -> Self {
+        List {
+            head: None,
+            #[cfg(feature = "stats")]
+            stats: Stats::new(),
+        }
+    }
+
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    pub fn peek_min(&self) -> Option<&T> {
+        self.head.as_ref().map(|node| &node.value)
+    }
+
+    pub fn pop_min(&mut self) -> Option<T> {
+        self.head.take().map(|node| {
+            self.head = node.next;
+            #[cfg(feature = "stats")]
+            self.stats.freed();
+            node.value
+        })
+    }
+}
+
+impl<T: Ord> List<T> {
+    pub fn insert(&mut self, value: T) {
+        // equal values go after the existing ones, so that insertion is stable
+        let mut cur = &mut self.head;
+        while cur.as_ref().is_some_and(|node| node.value <= value) {
+            cur = &mut cur.as_mut().unwrap().next;
+        }
+        *cur = Some(Box::new(Node {
+            value,
+            next: cur.take(),
+        }));
+        #[cfg(feature = "stats")]
+        self.stats.allocated();
+    }
+
+    pub fn merge(&mut self, mut other: List<T>) {
+        let mut left = self.head.take();
+        let mut right = other.head.take();
+        let mut tail = &mut self.head;
+        while let (Some(l), Some(r)) = (&left, &right) {
+            let source = if l.value <= r.value {
+                &mut left
+            } else {
+                &mut right
+            };
+            let mut node = source.take().unwrap();
+            *source = node.next.take();
+            tail = &mut tail.insert(node).next;
+        }
+        *tail = left.or(right);
+        #[cfg(feature = "stats")]
+        self.stats.moved_in(other.stats.nodes);
+    }
+}
+
+impl<T: Ord> SortedList<T> for List<T> {
+    fn insert(&mut self, value: T) {
+        self.insert(value);
+    }
+
+    fn pop_min(&mut self) -> Option<T> {
+        self.pop_min()
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> HeapSize for List<T> {
+    fn heap_size(&self) -> usize {
+        self.iter().count() * size_of::<Node<T>>()
+    }
+}
+
+impl<T> Drop for List<T> {
+    fn drop(&mut self) {
+        let mut cur = self.head.take();
+        while let Some(mut node) = cur {
+            cur = node.next.take();
+        }
+    }
+}
+
+impl<T: Ord> Extend<T> for List<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<T: Ord> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut values: Vec<T> = iter.into_iter().collect();
+        values.sort();
+        let mut list = List::new();
+        for value in values.into_iter().rev() {
+            list.head = Some(Box::new(Node {
+                value,
+                next: list.head.take(),
+            }));
+            #[cfg(feature = "stats")]
+            list.stats.allocated();
+        }
+        list
+    }
+}
+
+// The values are sorted, so the order they come in is lost
+impl<T: Ord> FromValues<T> for List<T> {
+    fn from_values<I: IntoIterator<Item = T>>(values: I) -> Self {
+        values.into_iter().collect()
+    }
+}
+
+pub struct IntoIter<T>(List<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_min()
+    }
+}
+
+impl<T> IntoIterator for List<T> {
+    type IntoIter = IntoIter<T>;
+    type Item = T;
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+pub struct Iter<'a, T> {
+    current: Option<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.current.map(|node| {
+            self.current = node.next.as_deref();
+            &node.value
+        })
+    }
+}
+
+impl<T> List<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            current: self.head.as_deref(),
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, T: arbitrary::Arbitrary<'a> + Ord> arbitrary::Arbitrary<'a> for List<T> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut list = List::new();
+        for value in u.arbitrary_iter()? {
+            list.insert(value?);
+        }
+        Ok(list)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::List;
+
+    #[test]
+    fn basics() {
+        let mut list = List::new();
+        assert!(list.is_empty());
+        assert_eq!(list.peek_min(), None);
+        assert_eq!(list.pop_min(), None);
+
+        list.insert(5);
+        list.insert(1);
+        list.insert(3);
+        list.insert(7);
+        list.insert(3);
+        assert_eq!(list.peek_min(), Some(&1));
+        assert_eq!(list.pop_min(), Some(1));
+        assert_eq!(list.pop_min(), Some(3));
+
+        list.insert(0);
+        list.insert(6);
+        assert_eq!(list.pop_min(), Some(0));
+        assert_eq!(list.pop_min(), Some(3));
+        assert_eq!(list.pop_min(), Some(5));
+        assert_eq!(list.pop_min(), Some(6));
+        assert_eq!(list.pop_min(), Some(7));
+        assert_eq!(list.pop_min(), None);
+        assert!(list.is_empty());
+    }
+
+    // compares on the key only, to observe stability
+    #[derive(Debug)]
+    struct ByKey(u8, &'static str);
+
+    impl PartialEq for ByKey {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+
+    impl Eq for ByKey {}
+
+    impl PartialOrd for ByKey {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for ByKey {
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            self.0.cmp(&other.0)
+        }
+    }
+
+    #[test]
+    fn stable() {
+        let mut list = List::new();
+        list.insert(ByKey(1, "a"));
+        list.insert(ByKey(0, "b"));
+        list.insert(ByKey(1, "c"));
+        assert_eq!(list.pop_min().map(|b| b.1), Some("b"));
+        list.insert(ByKey(1, "d"));
+        list.merge([ByKey(1, "e"), ByKey(2, "f")].into_iter().collect());
+        let values: Vec<_> = list.iter().map(|b| b.1).collect();
+        assert_eq!(values, vec!["a", "c", "d", "e", "f"]);
+    }
+
+    #[test]
+    fn merge() {
+        let mut list: List<_> = [9, 1, 5, 3].into_iter().collect();
+        list.merge([4, 0, 10, 2].into_iter().collect());
+        let values: Vec<_> = list.iter().copied().collect();
+        assert_eq!(values, vec![0, 1, 2, 3, 4, 5, 9, 10]);
+
+        list.merge(List::new());
+        assert_eq!(list.iter().count(), 8);
+
+        let mut empty = List::new();
+        empty.merge(list);
+        let values: Vec<_> = empty.into_iter().collect();
+        assert_eq!(values, vec![0, 1, 2, 3, 4, 5, 9, 10]);
+    }
+
+    #[test]
+    fn long() {
+        let mut list: List<_> = (0..100_000).rev().collect();
+        list.merge((0..100_000).collect());
+        assert_eq!(list.pop_min(), Some(0));
+        assert_eq!(list.pop_min(), Some(0));
+        assert_eq!(list.peek_min(), Some(&1));
+    }
+}
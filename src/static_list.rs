@@ -0,0 +1,233 @@
+use core::marker::PhantomData;
+
+// A stack whose nodes live in a pool of slots provided by the caller, linked
+// by their indices, so pushing and popping only move values in and out of the
+// slots. Unused slots are kept in a free list threaded through the same links.
+pub struct Slot<T> {
+    value: Option<T>,
+    next: Option<usize>,
+}
+
+impl<T> Slot<T> {
+    pub const fn new() -> Self {
+        Slot {
+            value: None,
+            next: None,
+        }
+    }
+}
+
+impl<T> Default for Slot<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct List<'a, T> {
+    slots: &'a mut [Slot<T>],
+    head: Option<usize>,
+    free: Option<usize>,
+    len: usize,
+}
+
+impl<'a, T> List<'a, T> {
+    // Any value left in the slots is dropped
+    pub fn new(slots: &'a mut [Slot<T>]) -> Self {
+        let len = slots.len();
+        for (i, slot) in slots.iter_mut().enumerate() {
+            slot.value = None;
+            slot.next = if i + 1 < len { Some(i + 1) } else { None };
+        }
+        List {
+            free: if len > 0 { Some(0) } else { None },
+            slots,
+            head: None,
+            len: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.free.is_none()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.head.and_then(|i| self.slots[i].value.as_ref())
+    }
+
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.head.and_then(|i| self.slots[i].value.as_mut())
+    }
+
+    // Gives the value back when there is no free slot left
+    pub fn push_front(&mut self, value: T) -> Result<(), T> {
+        let Some(i) = self.free else {
+            return Err(value);
+        };
+        let slot = &mut self.slots[i];
+        self.free = slot.next;
+        slot.value = Some(value);
+        slot.next = self.head;
+        self.head = Some(i);
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        let i = self.head?;
+        let slot = &mut self.slots[i];
+        self.head = slot.next;
+        slot.next = self.free;
+        self.free = Some(i);
+        self.len -= 1;
+        slot.value.take()
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            slots: self.slots,
+            next: self.head,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            slots: self.slots.as_mut_ptr(),
+            next: self.head,
+            _boo: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> Drop for List<'a, T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+pub struct Iter<'a, T> {
+    slots: &'a [Slot<T>],
+    next: Option<usize>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let slot = &self.slots[self.next?];
+        self.next = slot.next;
+        slot.value.as_ref()
+    }
+}
+
+impl<'a, 'b, T> IntoIterator for &'b List<'a, T> {
+    type IntoIter = Iter<'b, T>;
+    type Item = &'b T;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// Each slot is visited at most once, so the mutable references handed out
+// never alias
+pub struct IterMut<'a, T> {
+    slots: *mut Slot<T>,
+    next: Option<usize>,
+    _boo: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let slot = unsafe { &mut *self.slots.add(self.next?) };
+        self.next = slot.next;
+        slot.value.as_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{List, Slot};
+
+    #[test]
+    fn basics() {
+        let mut slots = [const { Slot::new() }; 3];
+        let mut list = List::new(&mut slots);
+        assert_eq!(list.capacity(), 3);
+        assert!(list.is_empty());
+        assert_eq!(list.pop_front(), None);
+
+        assert_eq!(list.push_front(1), Ok(()));
+        assert_eq!(list.push_front(2), Ok(()));
+        assert_eq!(list.push_front(3), Ok(()));
+        assert!(list.is_full());
+        assert_eq!(list.push_front(4), Err(4));
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.peek(), Some(&3));
+
+        assert_eq!(list.pop_front(), Some(3));
+        assert_eq!(list.push_front(5), Ok(()));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![5, 2, 1]);
+        for value in list.iter_mut() {
+            *value *= 10;
+        }
+        *list.peek_mut().unwrap() += 1;
+        assert_eq!(list.pop_front(), Some(51));
+        assert_eq!(list.pop_front(), Some(20));
+        assert_eq!(list.pop_front(), Some(10));
+        assert_eq!(list.pop_front(), None);
+    }
+
+    #[test]
+    fn empty_pool() {
+        let mut slots: [Slot<i32>; 0] = [];
+        let mut list = List::new(&mut slots);
+        assert!(list.is_full());
+        assert_eq!(list.push_front(1), Err(1));
+        assert_eq!(list.pop_front(), None);
+    }
+
+    #[test]
+    fn slots_are_reused() {
+        let mut slots = [const { Slot::new() }; 4];
+        let mut list = List::new(&mut slots);
+        for i in 0..1000 {
+            list.push_front(i).unwrap();
+            list.push_front(i).unwrap();
+            assert_eq!(list.pop_front(), Some(i));
+            if i % 2 == 1 {
+                assert_eq!(list.pop_front(), Some(i));
+                assert_eq!(list.pop_front(), Some(i - 1));
+            }
+        }
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn drop_values() {
+        use std::rc::Rc;
+        let value = Rc::new(());
+        let mut slots = [const { Slot::new() }; 2];
+        {
+            let mut list = List::new(&mut slots);
+            list.push_front(value.clone()).unwrap();
+            list.push_front(value.clone()).unwrap();
+            assert_eq!(Rc::strong_count(&value), 3);
+        }
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+}
@@ -0,0 +1,374 @@
+use std::cell::UnsafeCell;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::ptr::NonNull;
+
+use crate::guard;
+
+// Each node is owned by exactly two halves: one in the link coming from the
+// front, one in the link coming from the back. A half only gives shared
+// access, and the node is freed by joining its two halves back. Mutation goes
+// through cells that can only be opened with the token of the list's brand,
+// so holding the token mutably is what makes it exclusive.
+
+type Brand<'id> = PhantomData<fn(&'id ()) -> &'id ()>;
+
+pub struct GhostToken<'id>(Brand<'id>);
+
+impl GhostToken<'_> {
+    // The closure must work for any lifetime, so the token cannot be mixed up
+    // with one created by another call
+    pub fn scope<R>(f: impl for<'id> FnOnce(GhostToken<'id>) -> R) -> R {
+        f(GhostToken(PhantomData))
+    }
+}
+
+pub struct GhostCell<'id, T> {
+    value: UnsafeCell<T>,
+    _brand: Brand<'id>,
+}
+
+impl<'id, T> GhostCell<'id, T> {
+    pub fn new(value: T) -> Self {
+        GhostCell {
+            value: UnsafeCell::new(value),
+            _brand: PhantomData,
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    pub fn borrow<'a>(&'a self, _token: &'a GhostToken<'id>) -> &'a T {
+        unsafe { &*self.value.get() }
+    }
+
+    pub fn borrow_mut<'a>(&'a self, _token: &'a mut GhostToken<'id>) -> &'a mut T {
+        unsafe { &mut *self.value.get() }
+    }
+}
+
+// One of the two owners of a value; dropping a half without joining it back
+// leaks the value
+pub struct Half<T> {
+    ptr: NonNull<T>,
+    _boo: PhantomData<T>,
+}
+
+impl<T> Half<T> {
+    pub fn split(value: Box<T>) -> (Self, Self) {
+        let ptr = NonNull::from(Box::leak(value));
+        (
+            Half {
+                ptr,
+                _boo: PhantomData,
+            },
+            Half {
+                ptr,
+                _boo: PhantomData,
+            },
+        )
+    }
+
+    pub fn join(a: Self, b: Self) -> Box<T> {
+        assert_eq!(a.ptr, b.ptr, "joining halves of different values");
+        unsafe { Box::from_raw(a.ptr.as_ptr()) }
+    }
+}
+
+impl<T> Deref for Half<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+type Link<'id, T> = Option<Half<Node<'id, T>>>;
+
+struct Node<'id, T> {
+    value: GhostCell<'id, T>,
+    prev: GhostCell<'id, Link<'id, T>>,
+    next: GhostCell<'id, Link<'id, T>>,
+}
+
+impl<'id, T> Node<'id, T> {
+    fn new(value: T) -> Box<Self> {
+        Box::new(Node {
+            value: GhostCell::new(value),
+            prev: GhostCell::new(None),
+            next: GhostCell::new(None),
+        })
+    }
+}
+
+pub struct List<'id, T> {
+    first: Link<'id, T>,
+    last: Link<'id, T>,
+}
+
+impl<'id, T> List<'id, T> {
+    pub fn new() -> Self {
+        List {
+            first: None,
+            last: None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.first.is_none()
+    }
+
+    pub fn push_front(&mut self, value: T, token: &mut GhostToken<'id>) {
+        let (a, b) = Half::split(Node::new(value));
+        match self.first.take() {
+            None => self.last = Some(b),
+            Some(next) => {
+                *next.prev.borrow_mut(token) = Some(b);
+                *a.next.borrow_mut(token) = Some(next);
+            }
+        }
+        self.first = Some(a);
+    }
+
+    pub fn push_back(&mut self, value: T, token: &mut GhostToken<'id>) {
+        let (a, b) = Half::split(Node::new(value));
+        match self.last.take() {
+            None => self.first = Some(a),
+            Some(prev) => {
+                *prev.next.borrow_mut(token) = Some(a);
+                *b.prev.borrow_mut(token) = Some(prev);
+            }
+        }
+        self.last = Some(b);
+    }
+
+    pub fn pop_front(&mut self, token: &mut GhostToken<'id>) -> Option<T> {
+        let node = self.first.take()?;
+        let other = match node.next.borrow_mut(token).take() {
+            None => self.last.take(),
+            Some(next) => {
+                let other = next.prev.borrow_mut(token).take();
+                self.first = Some(next);
+                other
+            }
+        };
+        Some(Half::join(node, other.unwrap()).value.into_inner())
+    }
+
+    pub fn pop_back(&mut self, token: &mut GhostToken<'id>) -> Option<T> {
+        let node = self.last.take()?;
+        let other = match node.prev.borrow_mut(token).take() {
+            None => self.first.take(),
+            Some(prev) => {
+                let other = prev.next.borrow_mut(token).take();
+                self.last = Some(prev);
+                other
+            }
+        };
+        Some(Half::join(other.unwrap(), node).value.into_inner())
+    }
+
+    pub fn peek_front<'a>(&'a self, token: &'a GhostToken<'id>) -> Option<&'a T> {
+        self.first.as_ref().map(|node| node.value.borrow(token))
+    }
+
+    pub fn peek_front_mut<'a>(&'a self, token: &'a mut GhostToken<'id>) -> Option<&'a mut T> {
+        self.first.as_ref().map(|node| node.value.borrow_mut(token))
+    }
+
+    pub fn peek_back<'a>(&'a self, token: &'a GhostToken<'id>) -> Option<&'a T> {
+        self.last.as_ref().map(|node| node.value.borrow(token))
+    }
+
+    pub fn peek_back_mut<'a>(&'a self, token: &'a mut GhostToken<'id>) -> Option<&'a mut T> {
+        self.last.as_ref().map(|node| node.value.borrow_mut(token))
+    }
+
+    pub fn clear(&mut self, token: &mut GhostToken<'id>) {
+        while self.pop_front(token).is_some() {}
+    }
+
+    pub fn iter<'a>(&'a self, token: &'a GhostToken<'id>) -> Iter<'a, 'id, T> {
+        Iter {
+            front: self.first.as_deref(),
+            back: self.last.as_deref(),
+            token,
+        }
+    }
+
+    pub fn into_iter<'a>(self, token: &'a mut GhostToken<'id>) -> IntoIter<'a, 'id, T> {
+        IntoIter { list: self, token }
+    }
+}
+
+impl<T> Default for List<'_, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for List<'_, T> {
+    fn drop(&mut self) {
+        // the nodes are only reachable from this list, which we hold
+        // exclusively, so no one else can be using a token on them. Each one
+        // is popped, since unjoined halves would leak their nodes.
+        guard::drop_values(self, |list| list.pop_front(&mut GhostToken(PhantomData)));
+    }
+}
+
+pub struct Iter<'a, 'id, T> {
+    front: Option<&'a Node<'id, T>>,
+    back: Option<&'a Node<'id, T>>,
+    token: &'a GhostToken<'id>,
+}
+
+impl<'a, T> Iterator for Iter<'a, '_, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.front?;
+        if self.back.is_some_and(|back| std::ptr::eq(back, node)) {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.front = node.next.borrow(self.token).as_deref();
+        }
+        Some(node.value.borrow(self.token))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, '_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let node = self.back?;
+        if self.front.is_some_and(|front| std::ptr::eq(front, node)) {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.back = node.prev.borrow(self.token).as_deref();
+        }
+        Some(node.value.borrow(self.token))
+    }
+}
+
+pub struct IntoIter<'a, 'id, T> {
+    list: List<'id, T>,
+    token: &'a mut GhostToken<'id>,
+}
+
+impl<T> Iterator for IntoIter<'_, '_, T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_front(self.token)
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<'_, '_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.list.pop_back(self.token)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{GhostToken, List};
+    use std::rc::Rc;
+
+    #[test]
+    fn basics() {
+        GhostToken::scope(|mut token| {
+            let mut list = List::new();
+            assert_eq!(list.pop_front(&mut token), None);
+            list.push_front(1, &mut token);
+            list.push_front(2, &mut token);
+            list.push_front(3, &mut token);
+            assert_eq!(list.pop_front(&mut token), Some(3));
+            assert_eq!(list.pop_front(&mut token), Some(2));
+            list.push_front(4, &mut token);
+            assert_eq!(list.pop_front(&mut token), Some(4));
+            assert_eq!(list.pop_front(&mut token), Some(1));
+            assert_eq!(list.pop_front(&mut token), None);
+
+            assert_eq!(list.pop_back(&mut token), None);
+            list.push_back(1, &mut token);
+            list.push_back(2, &mut token);
+            list.push_front(0, &mut token);
+            assert_eq!(list.pop_back(&mut token), Some(2));
+            assert_eq!(list.pop_back(&mut token), Some(1));
+            assert_eq!(list.pop_back(&mut token), Some(0));
+            assert_eq!(list.pop_back(&mut token), None);
+            assert!(list.is_empty());
+        });
+    }
+
+    #[test]
+    fn peek() {
+        GhostToken::scope(|mut token| {
+            let mut list = List::new();
+            assert!(list.peek_front(&token).is_none());
+            assert!(list.peek_back_mut(&mut token).is_none());
+            list.push_front(1, &mut token);
+            list.push_front(2, &mut token);
+            assert_eq!(list.peek_front(&token), Some(&2));
+            assert_eq!(list.peek_back(&token), Some(&1));
+            *list.peek_back_mut(&mut token).unwrap() = 10;
+            *list.peek_front_mut(&mut token).unwrap() += 10;
+            assert_eq!(list.peek_front(&token), Some(&12));
+            assert_eq!(list.peek_back(&token), Some(&10));
+        });
+    }
+
+    #[test]
+    fn iter() {
+        GhostToken::scope(|mut token| {
+            let mut list = List::new();
+            for i in 0..5 {
+                list.push_back(i, &mut token);
+            }
+            assert_eq!(
+                list.iter(&token).copied().collect::<Vec<_>>(),
+                vec![0, 1, 2, 3, 4]
+            );
+            let mut iter = list.iter(&token);
+            assert_eq!(iter.next(), Some(&0));
+            assert_eq!(iter.next_back(), Some(&4));
+            assert_eq!(iter.next_back(), Some(&3));
+            assert_eq!(iter.next(), Some(&1));
+            assert_eq!(iter.next(), Some(&2));
+            assert_eq!(iter.next_back(), None);
+            assert_eq!(iter.next(), None);
+
+            let mut iter = list.into_iter(&mut token);
+            assert_eq!(iter.next(), Some(0));
+            assert_eq!(iter.next_back(), Some(4));
+            assert_eq!(iter.collect::<Vec<_>>(), vec![1, 2, 3]);
+        });
+    }
+
+    #[test]
+    fn shared_token() {
+        // several lists of the same brand are opened by the same token
+        GhostToken::scope(|mut token| {
+            let mut a = List::new();
+            let mut b = List::new();
+            a.push_back(1, &mut token);
+            b.push_back(2, &mut token);
+            while let Some(value) = a.pop_front(&mut token) {
+                b.push_front(value, &mut token);
+            }
+            assert_eq!(b.iter(&token).copied().collect::<Vec<_>>(), vec![1, 2]);
+        });
+    }
+
+    #[test]
+    fn drop_frees_nodes() {
+        let value = Rc::new(());
+        GhostToken::scope(|mut token| {
+            let mut list = List::new();
+            for _ in 0..100 {
+                list.push_back(value.clone(), &mut token);
+            }
+            assert_eq!(Rc::strong_count(&value), 101);
+        });
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+}
@@ -0,0 +1,46 @@
+// What a list did with its nodes since it was created. Nodes moved from one
+// list to another, by splicing, splitting or merging, are neither allocated
+// nor freed: they only leave the count of one list for the other's.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub allocations: usize,
+    pub frees: usize,
+    // the number of nodes in the list now, and the most it ever had
+    pub nodes: usize,
+    pub peak: usize,
+}
+
+// Inlined, so that the no-panic feature can see that they do not panic
+impl Stats {
+    pub(crate) const fn new() -> Self {
+        Stats {
+            allocations: 0,
+            frees: 0,
+            nodes: 0,
+            peak: 0,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn allocated(&mut self) {
+        self.allocations += 1;
+        self.moved_in(1);
+    }
+
+    #[inline]
+    pub(crate) fn freed(&mut self) {
+        self.frees += 1;
+        self.moved_out(1);
+    }
+
+    #[inline]
+    pub(crate) fn moved_in(&mut self, count: usize) {
+        self.nodes += count;
+        self.peak = self.peak.max(self.nodes);
+    }
+
+    #[inline]
+    pub(crate) fn moved_out(&mut self, count: usize) {
+        self.nodes -= count;
+    }
+}
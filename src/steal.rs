@@ -0,0 +1,361 @@
+use std::alloc::{alloc, dealloc, handle_alloc_error, Layout};
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::sync::atomic::{fence, AtomicIsize, AtomicPtr, Ordering};
+use std::sync::Arc;
+
+use crate::epoch;
+
+const MIN_CAPACITY: usize = 16;
+
+struct Buffer<T> {
+    ptr: *mut MaybeUninit<T>,
+    cap: usize,
+}
+
+impl<T> Buffer<T> {
+    fn alloc(cap: usize) -> *mut Buffer<T> {
+        debug_assert!(cap.is_power_of_two());
+        let layout = Layout::array::<MaybeUninit<T>>(cap).unwrap();
+        let ptr = if layout.size() == 0 {
+            ptr::NonNull::dangling().as_ptr()
+        } else {
+            let ptr = unsafe { alloc(layout) } as *mut MaybeUninit<T>;
+            if ptr.is_null() {
+                handle_alloc_error(layout);
+            }
+            ptr
+        };
+        Box::into_raw(Box::new(Buffer { ptr, cap }))
+    }
+
+    // Frees the slots without dropping any element still stored in them
+    unsafe fn dealloc(buffer: *mut Buffer<T>) {
+        let buffer = Box::from_raw(buffer);
+        let layout = Layout::array::<MaybeUninit<T>>(buffer.cap).unwrap();
+        if layout.size() != 0 {
+            dealloc(buffer.ptr as *mut u8, layout);
+        }
+    }
+
+    unsafe fn dealloc_erased(buffer: *mut ()) {
+        Self::dealloc(buffer as *mut Buffer<T>)
+    }
+
+    unsafe fn at(&self, index: isize) -> *mut MaybeUninit<T> {
+        self.ptr.add(index as usize & (self.cap - 1))
+    }
+
+    unsafe fn write(&self, index: isize, value: MaybeUninit<T>) {
+        ptr::write_volatile(self.at(index), value)
+    }
+
+    // The slot may be overwritten concurrently by the owner; the caller must
+    // only assume the value is initialized once it won the race for it
+    unsafe fn read(&self, index: isize) -> MaybeUninit<T> {
+        ptr::read_volatile(self.at(index))
+    }
+}
+
+struct Inner<T> {
+    top: AtomicIsize,
+    bottom: AtomicIsize,
+    // Stealers may still be reading from a buffer after the owner replaced
+    // it, so old buffers are handed to the epoch collector
+    buffer: AtomicPtr<Buffer<T>>,
+}
+
+impl<T> Drop for Inner<T> {
+    fn drop(&mut self) {
+        let top = *self.top.get_mut();
+        let bottom = *self.bottom.get_mut();
+        let buffer = *self.buffer.get_mut();
+        unsafe {
+            for index in top..bottom {
+                (*buffer).read(index).assume_init_drop();
+            }
+            Buffer::dealloc(buffer);
+        }
+    }
+}
+
+pub enum Steal<T> {
+    Empty,
+    Retry,
+    Success(T),
+}
+
+impl<T> Steal<T> {
+    pub fn success(self) -> Option<T> {
+        match self {
+            Steal::Success(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+pub struct Worker<T> {
+    inner: Arc<Inner<T>>,
+    // Only one thread may push and pop
+    _phantom: PhantomData<*mut ()>,
+}
+
+unsafe impl<T: Send> Send for Worker<T> {}
+
+pub struct Stealer<T> {
+    inner: Arc<Inner<T>>,
+}
+
+unsafe impl<T: Send> Send for Stealer<T> {}
+unsafe impl<T: Send> Sync for Stealer<T> {}
+
+impl<T> Worker<T> {
+    pub fn new() -> Self {
+        Worker {
+            inner: Arc::new(Inner {
+                top: AtomicIsize::new(0),
+                bottom: AtomicIsize::new(0),
+                buffer: AtomicPtr::new(Buffer::alloc(MIN_CAPACITY)),
+            }),
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn stealer(&self) -> Stealer<T> {
+        Stealer {
+            inner: self.inner.clone(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        let bottom = self.inner.bottom.load(Ordering::Relaxed);
+        let top = self.inner.top.load(Ordering::Relaxed);
+        (bottom - top).max(0) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn push(&self, value: T) {
+        let bottom = self.inner.bottom.load(Ordering::Relaxed);
+        let top = self.inner.top.load(Ordering::Acquire);
+        let mut buffer = self.inner.buffer.load(Ordering::Relaxed);
+        unsafe {
+            if bottom - top >= (*buffer).cap as isize {
+                buffer = self.grow(buffer, top, bottom);
+            }
+            (*buffer).write(bottom, MaybeUninit::new(value));
+        }
+        fence(Ordering::Release);
+        self.inner.bottom.store(bottom + 1, Ordering::Relaxed);
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let bottom = self.inner.bottom.load(Ordering::Relaxed) - 1;
+        let buffer = self.inner.buffer.load(Ordering::Relaxed);
+        self.inner.bottom.store(bottom, Ordering::Relaxed);
+        fence(Ordering::SeqCst);
+        let top = self.inner.top.load(Ordering::Relaxed);
+        if top > bottom {
+            // empty
+            self.inner.bottom.store(bottom + 1, Ordering::Relaxed);
+            return None;
+        }
+        let value = unsafe { (*buffer).read(bottom) };
+        if top == bottom {
+            // last element: race the stealers for it
+            let won = self
+                .inner
+                .top
+                .compare_exchange(top, top + 1, Ordering::SeqCst, Ordering::Relaxed)
+                .is_ok();
+            self.inner.bottom.store(bottom + 1, Ordering::Relaxed);
+            if !won {
+                return None;
+            }
+        }
+        Some(unsafe { value.assume_init() })
+    }
+
+    unsafe fn grow(&self, old: *mut Buffer<T>, top: isize, bottom: isize) -> *mut Buffer<T> {
+        let new = Buffer::alloc((*old).cap * 2);
+        for index in top..bottom {
+            (*new).write(index, (*old).read(index));
+        }
+        self.inner.buffer.store(new, Ordering::Release);
+        epoch::pin().defer(old as *mut (), Buffer::<T>::dealloc_erased);
+        new
+    }
+}
+
+impl<T> Default for Worker<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Stealer<T> {
+    pub fn is_empty(&self) -> bool {
+        let top = self.inner.top.load(Ordering::Acquire);
+        fence(Ordering::SeqCst);
+        let bottom = self.inner.bottom.load(Ordering::Acquire);
+        bottom <= top
+    }
+
+    pub fn steal(&self) -> Steal<T> {
+        let top = self.inner.top.load(Ordering::Acquire);
+        fence(Ordering::SeqCst);
+        let bottom = self.inner.bottom.load(Ordering::Acquire);
+        if top >= bottom {
+            return Steal::Empty;
+        }
+        let guard = epoch::pin();
+        let buffer = self.inner.buffer.load(Ordering::Acquire);
+        let value = unsafe { (*buffer).read(top) };
+        drop(guard);
+        if self
+            .inner
+            .top
+            .compare_exchange(top, top + 1, Ordering::SeqCst, Ordering::Relaxed)
+            .is_err()
+        {
+            return Steal::Retry;
+        }
+        Steal::Success(unsafe { value.assume_init() })
+    }
+}
+
+impl<T> Clone for Stealer<T> {
+    fn clone(&self) -> Self {
+        Stealer {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Steal, Worker};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn basics() {
+        let worker = Worker::new();
+        let stealer = worker.stealer();
+        assert_eq!(worker.pop(), None);
+        assert!(matches!(stealer.steal(), Steal::Empty));
+
+        worker.push(1);
+        worker.push(2);
+        worker.push(3);
+        assert_eq!(worker.len(), 3);
+
+        // the owner works LIFO, thieves FIFO
+        assert_eq!(worker.pop(), Some(3));
+        assert_eq!(stealer.steal().success(), Some(1));
+        assert_eq!(worker.pop(), Some(2));
+        assert_eq!(worker.pop(), None);
+        assert!(matches!(stealer.steal(), Steal::Empty));
+        assert!(worker.is_empty());
+        assert!(stealer.is_empty());
+    }
+
+    #[test]
+    fn grow() {
+        let worker = Worker::new();
+        let stealer = worker.stealer();
+        for i in 0..1000 {
+            worker.push(i);
+        }
+        for i in 0..500 {
+            assert_eq!(stealer.steal().success(), Some(i));
+        }
+        for i in (500..1000).rev() {
+            assert_eq!(worker.pop(), Some(i));
+        }
+        assert_eq!(worker.pop(), None);
+    }
+
+    #[test]
+    fn drop_remaining() {
+        struct Counted(Arc<AtomicUsize>);
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let drops = Arc::new(AtomicUsize::new(0));
+        let worker = Worker::new();
+        let stealer = worker.stealer();
+        for _ in 0..100 {
+            worker.push(Counted(drops.clone()));
+        }
+        drop(worker.pop());
+        drop(stealer.steal());
+        assert_eq!(drops.load(Ordering::Relaxed), 2);
+        drop(worker);
+        assert_eq!(drops.load(Ordering::Relaxed), 2);
+        drop(stealer);
+        assert_eq!(drops.load(Ordering::Relaxed), 100);
+    }
+
+    // Thieves speculatively read slots that the owner may be overwriting, and
+    // discard the value when they lose the race; Miri flags that read
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn stress() {
+        const COUNT: usize = 100_000;
+        const THIEVES: usize = 4;
+
+        let worker = Worker::new();
+        let done = Arc::new(AtomicBool::new(false));
+        let thieves: Vec<_> = (0..THIEVES)
+            .map(|_| {
+                let stealer = worker.stealer();
+                let done = done.clone();
+                thread::spawn(move || {
+                    let mut stolen = Vec::new();
+                    loop {
+                        match stealer.steal() {
+                            Steal::Success(value) => stolen.push(value),
+                            Steal::Retry => {}
+                            Steal::Empty if done.load(Ordering::Acquire) => break,
+                            Steal::Empty => thread::yield_now(),
+                        }
+                    }
+                    stolen
+                })
+            })
+            .collect();
+
+        let mut seen = vec![false; COUNT];
+        for i in 0..COUNT {
+            worker.push(i);
+            // pop every third item to race the thieves on the owner's side too
+            if i % 3 == 0 {
+                if let Some(value) = worker.pop() {
+                    assert!(!seen[value]);
+                    seen[value] = true;
+                }
+            }
+        }
+        while let Some(value) = worker.pop() {
+            assert!(!seen[value]);
+            seen[value] = true;
+        }
+        done.store(true, Ordering::Release);
+
+        for thief in thieves {
+            for value in thief.join().unwrap() {
+                assert!(!seen[value]);
+                seen[value] = true;
+            }
+        }
+        assert!(seen.into_iter().all(|seen| seen));
+    }
+}
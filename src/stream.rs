@@ -0,0 +1,290 @@
+use std::cell::{Cell, OnceCell};
+use std::rc::Rc;
+
+// Like third::List, but the tail of each node is only computed the first time
+// it is needed, and then kept for later reads
+pub struct Stream<T>(Option<Rc<Cons<T>>>);
+
+struct Cons<T> {
+    head: T,
+    tail: Thunk<T>,
+}
+
+type Init<T> = Box<dyn FnOnce() -> Stream<T>>;
+
+struct Thunk<T> {
+    value: OnceCell<Stream<T>>,
+    init: Cell<Option<Init<T>>>,
+}
+
+impl<T> Thunk<T> {
+    fn force(&self) -> &Stream<T> {
+        self.value.get_or_init(|| {
+            let init = self.init.take().expect("stream tail depends on itself");
+            init()
+        })
+    }
+}
+
+impl<T> Stream<T> {
+    pub fn empty() -> Self {
+        Stream(None)
+    }
+
+    pub fn cons<F>(value: T, tail: F) -> Self
+    where
+        F: FnOnce() -> Stream<T> + 'static,
+    {
+        Stream(Some(Rc::new(Cons {
+            head: value,
+            tail: Thunk {
+                value: OnceCell::new(),
+                init: Cell::new(Some(Box::new(tail))),
+            },
+        })))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+
+    pub fn head(&self) -> Option<&T> {
+        self.0.as_ref().map(|cons| &cons.head)
+    }
+
+    // Evaluates the tail if it was not already
+    pub fn tail(&self) -> Stream<T> {
+        self.tail_ref().map_or_else(Stream::empty, Stream::clone)
+    }
+
+    fn tail_ref(&self) -> Option<&Stream<T>> {
+        self.0.as_ref().map(|cons| cons.tail.force())
+    }
+
+    // Never ends on an infinite stream
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            stream: self,
+            started: false,
+        }
+    }
+}
+
+impl<T: Clone + 'static> Stream<T> {
+    // value, f(value), f(f(value)), ...
+    pub fn iterate<F>(value: T, f: F) -> Self
+    where
+        F: Fn(&T) -> T + 'static,
+    {
+        fn go<T: Clone + 'static>(value: T, f: Rc<dyn Fn(&T) -> T>) -> Stream<T> {
+            let next = f(&value);
+            Stream::cons(value, move || go(next, f))
+        }
+        go(value, Rc::new(f))
+    }
+
+    pub fn take(&self, n: usize) -> Self {
+        match &self.0 {
+            Some(cons) if n > 0 => {
+                let stream = self.clone();
+                Stream::cons(cons.head.clone(), move || stream.tail().take(n - 1))
+            }
+            _ => Stream::empty(),
+        }
+    }
+
+    pub fn map<U: 'static, F>(&self, f: F) -> Stream<U>
+    where
+        F: Fn(&T) -> U + 'static,
+    {
+        fn go<T: Clone + 'static, U: 'static>(
+            stream: &Stream<T>,
+            f: Rc<dyn Fn(&T) -> U>,
+        ) -> Stream<U> {
+            match stream.head() {
+                None => Stream::empty(),
+                Some(head) => {
+                    let value = f(head);
+                    let stream = stream.clone();
+                    Stream::cons(value, move || go(&stream.tail(), f))
+                }
+            }
+        }
+        go(self, Rc::new(f))
+    }
+
+    // Looks for the first matching element right away, which never ends on
+    // an infinite stream without any
+    pub fn filter<P>(&self, predicate: P) -> Self
+    where
+        P: Fn(&T) -> bool + 'static,
+    {
+        fn go<T: Clone + 'static>(
+            stream: &Stream<T>,
+            predicate: Rc<dyn Fn(&T) -> bool>,
+        ) -> Stream<T> {
+            let mut stream = stream.clone();
+            while let Some(head) = stream.head() {
+                if predicate(head) {
+                    let head = head.clone();
+                    return Stream::cons(head, move || go(&stream.tail(), predicate));
+                }
+                stream = stream.tail();
+            }
+            Stream::empty()
+        }
+        go(self, Rc::new(predicate))
+    }
+
+    // The elements of self, then those of the stream built by other once
+    // self is exhausted
+    pub fn append_with<F>(&self, other: F) -> Self
+    where
+        F: FnOnce() -> Stream<T> + 'static,
+    {
+        match &self.0 {
+            None => other(),
+            Some(cons) => {
+                let stream = self.clone();
+                Stream::cons(cons.head.clone(), move || stream.tail().append_with(other))
+            }
+        }
+    }
+}
+
+impl<T> Default for Stream<T> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<T> Clone for Stream<T> {
+    fn clone(&self) -> Self {
+        Stream(self.0.clone())
+    }
+}
+
+impl<T> Drop for Stream<T> {
+    fn drop(&mut self) {
+        let mut next = self.0.take();
+        while let Some(node) = next {
+            match Rc::try_unwrap(node) {
+                Ok(mut cons) => next = cons.tail.value.take().and_then(|mut tail| tail.0.take()),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+// Only evaluates the tail of an element when asked for the next one
+pub struct Iter<'a, T> {
+    stream: &'a Stream<T>,
+    started: bool,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.started {
+            self.stream = self.stream.tail_ref()?;
+        }
+        self.started = true;
+        self.stream.head()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Stream<T> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Stream;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn naturals() -> Stream<u64> {
+        Stream::iterate(0, |n| n + 1)
+    }
+
+    #[test]
+    fn basics() {
+        let stream: Stream<i32> = Stream::empty();
+        assert!(stream.is_empty());
+        assert_eq!(stream.head(), None);
+        assert!(stream.tail().is_empty());
+
+        let stream = Stream::cons(1, || Stream::cons(2, Stream::empty));
+        assert_eq!(stream.head(), Some(&1));
+        assert_eq!(stream.tail().head(), Some(&2));
+        assert!(stream.tail().tail().is_empty());
+        assert_eq!(stream.iter().copied().collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn memoized() {
+        let evaluations = Rc::new(Cell::new(0));
+        let counter = evaluations.clone();
+        let stream = Stream::cons(1, move || {
+            counter.set(counter.get() + 1);
+            Stream::cons(2, Stream::empty)
+        });
+        assert_eq!(evaluations.get(), 0);
+        assert_eq!(stream.tail().head(), Some(&2));
+        assert_eq!(stream.tail().head(), Some(&2));
+        let copy = stream.clone();
+        assert_eq!(copy.tail().head(), Some(&2));
+        assert_eq!(evaluations.get(), 1);
+    }
+
+    #[test]
+    fn infinite() {
+        let evens = naturals().filter(|n| n % 2 == 0).map(|n| n * 10);
+        assert_eq!(
+            evens.take(4).iter().copied().collect::<Vec<_>>(),
+            vec![0, 20, 40, 60]
+        );
+        assert_eq!(naturals().iter().nth(1000), Some(&1000));
+    }
+
+    #[test]
+    fn primes() {
+        fn sieve(stream: Stream<u64>) -> Stream<u64> {
+            let prime = *stream.head().unwrap();
+            let rest = stream.tail().filter(move |n| n % prime != 0);
+            Stream::cons(prime, move || sieve(rest))
+        }
+        let primes = sieve(naturals().filter(|&n| n >= 2));
+        assert_eq!(
+            primes.iter().take(10).copied().collect::<Vec<_>>(),
+            vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]
+        );
+    }
+
+    #[test]
+    fn append_is_lazy() {
+        let built = Rc::new(Cell::new(false));
+        let flag = built.clone();
+        let stream = naturals().take(3).append_with(move || {
+            flag.set(true);
+            Stream::cons(100, Stream::empty)
+        });
+        assert_eq!(stream.iter().take(3).count(), 3);
+        assert!(!built.get());
+        assert_eq!(
+            stream.iter().copied().collect::<Vec<_>>(),
+            vec![0, 1, 2, 100]
+        );
+        assert!(built.get());
+    }
+
+    #[test]
+    fn long_drop() {
+        let stream = naturals().take(100_000);
+        assert_eq!(stream.iter().count(), 100_000);
+    }
+}
@@ -0,0 +1,164 @@
+use std::sync::{Condvar, Mutex};
+#[cfg(feature = "futures")]
+use std::task::{Poll, Waker};
+use std::time::Duration;
+
+use crate::sixth::LinkedList;
+
+pub struct SyncDeque<T> {
+    list: Mutex<LinkedList<T>>,
+    available: Condvar,
+    // the tasks waiting in pop_front_async, all woken up on each push
+    #[cfg(feature = "futures")]
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl<T> SyncDeque<T> {
+    pub fn new() -> Self {
+        SyncDeque {
+            list: Mutex::new(LinkedList::new()),
+            available: Condvar::new(),
+            #[cfg(feature = "futures")]
+            wakers: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.lock().unwrap().is_empty()
+    }
+
+    pub fn push_back(&self, value: T) {
+        self.list.lock().unwrap().push_back(value);
+        self.available.notify_one();
+        #[cfg(feature = "futures")]
+        self.wake();
+    }
+
+    pub fn push_front(&self, value: T) {
+        self.list.lock().unwrap().push_front(value);
+        self.available.notify_one();
+        #[cfg(feature = "futures")]
+        self.wake();
+    }
+
+    pub fn try_pop_front(&self) -> Option<T> {
+        self.list.lock().unwrap().pop_front()
+    }
+
+    // Waits until a value is available, or gives up after the timeout
+    pub fn pop_front_blocking(&self, timeout: Duration) -> Option<T> {
+        let list = self.list.lock().unwrap();
+        let (mut list, _) = self
+            .available
+            .wait_timeout_while(list, timeout, |list| list.is_empty())
+            .unwrap();
+        list.pop_front()
+    }
+
+    // Waits until a value is available without blocking the thread
+    #[cfg(feature = "futures")]
+    pub async fn pop_front_async(&self) -> T {
+        std::future::poll_fn(|cx| {
+            let mut list = self.list.lock().unwrap();
+            match list.pop_front() {
+                Some(value) => Poll::Ready(value),
+                None => {
+                    // registered while the list is locked, so that a push
+                    // cannot happen between the check and the registration
+                    self.wakers.lock().unwrap().push(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+
+    #[cfg(feature = "futures")]
+    fn wake(&self) {
+        // taken out first, since a waker may poll again right away
+        let wakers = std::mem::take(&mut *self.wakers.lock().unwrap());
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Default for SyncDeque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SyncDeque;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn basics() {
+        let deque = SyncDeque::new();
+        assert!(deque.is_empty());
+        assert_eq!(deque.try_pop_front(), None);
+        deque.push_back(2);
+        deque.push_back(3);
+        deque.push_front(1);
+        assert_eq!(deque.len(), 3);
+        assert_eq!(deque.try_pop_front(), Some(1));
+        assert_eq!(deque.pop_front_blocking(Duration::ZERO), Some(2));
+        assert_eq!(deque.pop_front_blocking(Duration::from_secs(1)), Some(3));
+    }
+
+    #[test]
+    fn timeout() {
+        let deque: SyncDeque<i32> = SyncDeque::new();
+        let start = Instant::now();
+        assert_eq!(deque.pop_front_blocking(Duration::from_millis(50)), None);
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[test]
+    fn wakes_up_consumer() {
+        let deque = Arc::new(SyncDeque::new());
+        let consumer = {
+            let deque = deque.clone();
+            thread::spawn(move || deque.pop_front_blocking(Duration::from_secs(60)))
+        };
+        thread::sleep(Duration::from_millis(20));
+        deque.push_back(42);
+        assert_eq!(consumer.join().unwrap(), Some(42));
+    }
+
+    #[test]
+    fn work_queue() {
+        let deque = Arc::new(SyncDeque::new());
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let deque = deque.clone();
+                thread::spawn(move || {
+                    let mut sum = 0;
+                    while let Some(job) = deque.pop_front_blocking(Duration::from_millis(200)) {
+                        if job == 0 {
+                            break;
+                        }
+                        sum += job;
+                    }
+                    sum
+                })
+            })
+            .collect();
+        for job in 1..=1000 {
+            deque.push_back(job);
+        }
+        for _ in 0..4 {
+            deque.push_back(0);
+        }
+        let total: u64 = workers.into_iter().map(|w| w.join().unwrap()).sum();
+        assert_eq!(total, 500500);
+    }
+}
@@ -0,0 +1,246 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+// Hands out values wrapped in DropCounter, and counts how many of them were
+// created and dropped. The counts are shared by the clones of the tracker, and
+// the values can be sent to other threads.
+#[derive(Clone, Debug, Default)]
+pub struct DropTracker {
+    created: Arc<AtomicUsize>,
+    dropped: Arc<AtomicUsize>,
+}
+
+impl DropTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn track<T>(&self, value: T) -> DropCounter<T> {
+        self.created.fetch_add(1, Ordering::Relaxed);
+        DropCounter {
+            value,
+            tracker: self.clone(),
+        }
+    }
+
+    pub fn created(&self) -> usize {
+        self.created.load(Ordering::Relaxed)
+    }
+
+    pub fn dropped(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    pub fn alive(&self) -> usize {
+        self.created() - self.dropped()
+    }
+
+    #[track_caller]
+    pub fn assert_alive(&self, expected: usize) {
+        let alive = self.alive();
+        assert_eq!(
+            alive,
+            expected,
+            "{alive} values are alive, {expected} expected ({} created, {} dropped)",
+            self.created(),
+            self.dropped()
+        );
+    }
+
+    #[track_caller]
+    pub fn assert_all_dropped(&self) {
+        self.assert_alive(0);
+    }
+}
+
+// Compares, hashes and prints as the value it wraps, so that it can go in the
+// sorted lists and in the hash maps
+pub struct DropCounter<T> {
+    value: T,
+    tracker: DropTracker,
+}
+
+impl<T> Drop for DropCounter<T> {
+    fn drop(&mut self) {
+        self.tracker.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl<T> Deref for DropCounter<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for DropCounter<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+// The clone is a new value, tracked like the others
+impl<T: Clone> Clone for DropCounter<T> {
+    fn clone(&self) -> Self {
+        self.tracker.track(self.value.clone())
+    }
+}
+
+impl<T: Debug> Debug for DropCounter<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<T: PartialEq> PartialEq for DropCounter<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq> Eq for DropCounter<T> {}
+
+impl<T: PartialOrd> PartialOrd for DropCounter<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<T: Ord> Ord for DropCounter<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+impl<T: Hash> Hash for DropCounter<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AllocCounts {
+    pub allocations: usize,
+    pub frees: usize,
+    pub allocated_bytes: usize,
+    pub freed_bytes: usize,
+}
+
+// Counts what the current thread allocates and frees, so that the tests can
+// run in parallel. It only counts anything once installed with
+// #[global_allocator] in the test crate.
+pub struct CountingAllocator;
+
+thread_local! {
+    static COUNTS: Cell<AllocCounts> = const {
+        Cell::new(AllocCounts {
+            allocations: 0,
+            frees: 0,
+            allocated_bytes: 0,
+            freed_bytes: 0,
+        })
+    };
+}
+
+fn update(f: impl FnOnce(&mut AllocCounts)) {
+    // the counts may be gone while the thread is exiting
+    let _ = COUNTS.try_with(|counts| {
+        let mut value = counts.get();
+        f(&mut value);
+        counts.set(value);
+    });
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        update(|counts| {
+            counts.allocations += 1;
+            counts.allocated_bytes += layout.size();
+        });
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        update(|counts| {
+            counts.frees += 1;
+            counts.freed_bytes += layout.size();
+        });
+        System.dealloc(ptr, layout)
+    }
+}
+
+// What the current thread allocated and freed while running f
+pub fn count_allocs<R>(f: impl FnOnce() -> R) -> (R, AllocCounts) {
+    let before = COUNTS.with(Cell::get);
+    let result = f();
+    let after = COUNTS.with(Cell::get);
+    let counts = AllocCounts {
+        allocations: after.allocations - before.allocations,
+        frees: after.frees - before.frees,
+        allocated_bytes: after.allocated_bytes - before.allocated_bytes,
+        freed_bytes: after.freed_bytes - before.freed_bytes,
+    };
+    (result, counts)
+}
+
+// Checks that f frees everything it allocates
+#[track_caller]
+pub fn assert_no_leaks<R>(f: impl FnOnce() -> R) -> R {
+    let (result, counts) = count_allocs(f);
+    assert_eq!(
+        counts.allocations, counts.frees,
+        "{} allocations but {} frees",
+        counts.allocations, counts.frees
+    );
+    assert_eq!(counts.allocated_bytes, counts.freed_bytes);
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use std::thread;
+
+    use super::DropTracker;
+
+    #[test]
+    fn drop_tracker() {
+        let tracker = DropTracker::new();
+        let mut values: Vec<_> = (0..5).map(|i| tracker.track(i)).collect();
+        tracker.assert_alive(5);
+        *values[0] += 10;
+        assert_eq!(*values[0], 10);
+        values.truncate(2);
+        tracker.assert_alive(2);
+        let clone = values[1].clone();
+        assert_eq!(tracker.created(), 6);
+        assert_eq!(clone, values[1]);
+        assert_eq!(format!("{clone:?}"), "1");
+        assert!(values.contains(&clone));
+        drop(values);
+        drop(clone);
+        tracker.assert_all_dropped();
+        assert_eq!(tracker.dropped(), 6);
+    }
+
+    #[test]
+    fn across_threads() {
+        let tracker = DropTracker::new();
+        let values: Vec<_> = (0..10).map(|i| tracker.track(i)).collect();
+        thread::spawn(move || drop(values)).join().unwrap();
+        tracker.assert_all_dropped();
+    }
+
+    #[test]
+    #[should_panic(expected = "1 values are alive, 0 expected")]
+    fn leak() {
+        let tracker = DropTracker::new();
+        std::mem::forget(tracker.track(()));
+        tracker.assert_all_dropped();
+    }
+}
@@ -1,24 +1,30 @@
-use std::rc::Rc;
+use crate::pointer::{self, ArcFamily, PointerFamily, RcFamily};
+use crate::traits::{FromValues, HeapSize, Stack};
 
-type Link<T> = Option<Rc<Node<T>>>;
+type Link<T, P> = Option<<P as PointerFamily>::Pointer<Node<T, P>>>;
 
-struct Node<T> {
+struct Node<T, P: PointerFamily> {
     value: T,
-    next: Link<T>,
+    next: Link<T, P>,
 }
 
-pub struct List<T> {
-    head: Link<T>,
+pub struct List<T, P: PointerFamily = RcFamily> {
+    head: Link<T, P>,
 }
 
+// Can be shared between threads
+pub type SyncList<T> = List<T, ArcFamily>;
+
 impl<T> List<T> {
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         List { head: None }
     }
+}
 
+impl<T, P: PointerFamily> List<T, P> {
     pub fn prepend(&self, value: T) -> Self {
         List {
-            head: Some(Rc::new(Node {
+            head: Some(P::new(Node {
                 value,
                 next: self.head.clone(),
             })),
@@ -36,17 +42,38 @@ impl<T> List<T> {
     }
 }
 
-impl<T> Default for List<T> {
+impl<T, P: PointerFamily> Default for List<T, P> {
     fn default() -> Self {
-        Self::new()
+        List { head: None }
     }
 }
 
-pub struct Iter<'a, T> {
-    current: Option<&'a Node<T>>,
+impl<T, P: PointerFamily> Clone for List<T, P> {
+    fn clone(&self) -> Self {
+        List {
+            head: self.head.clone(),
+        }
+    }
 }
 
-impl<'a, T> Iterator for Iter<'a, T> {
+// Popping has to clone the value, since it may still be shared with other lists
+impl<T: Clone, P: PointerFamily> Stack<T> for List<T, P> {
+    fn push(&mut self, value: T) {
+        *self = self.prepend(value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        let value = self.head().cloned()?;
+        *self = self.tail();
+        Some(value)
+    }
+}
+
+pub struct Iter<'a, T, P: PointerFamily = RcFamily> {
+    current: Option<&'a Node<T, P>>,
+}
+
+impl<'a, T, P: PointerFamily> Iterator for Iter<'a, T, P> {
     type Item = &'a T;
     fn next(&mut self) -> Option<Self::Item> {
         self.current.map(|node| {
@@ -56,19 +83,83 @@ impl<'a, T> Iterator for Iter<'a, T> {
     }
 }
 
-impl<T> List<T> {
-    pub fn iter(&self) -> Iter<'_, T> {
+impl<T, P: PointerFamily> List<T, P> {
+    pub fn iter(&self) -> Iter<'_, T, P> {
         Iter {
             current: self.head.as_deref(),
         }
     }
 }
 
-impl<T> Drop for List<T> {
+impl<T, P: PointerFamily> List<T, P> {
+    // Only the nodes that no other list points to, which is what dropping
+    // this list would free
+    pub fn unique_heap_size(&self) -> usize {
+        let mut size = 0;
+        let mut link = &self.head;
+        while let Some(node) = link {
+            if P::strong_count(node) > 1 {
+                break;
+            }
+            size += pointer::allocation_size::<Node<T, P>>();
+            link = &node.next;
+        }
+        size
+    }
+}
+
+// Every node the list can reach, including the ones shared with other lists
+impl<T, P: PointerFamily> HeapSize for List<T, P> {
+    fn heap_size(&self) -> usize {
+        self.iter().count() * pointer::allocation_size::<Node<T, P>>()
+    }
+}
+
+// The values are moved out of the nodes this list is the only one to point
+// to, and cloned from the first node shared with another list onwards
+pub struct IntoIter<T, P: PointerFamily = RcFamily>(List<T, P>);
+
+impl<T: Clone, P: PointerFamily> Iterator for IntoIter<T, P> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.0.head.take()?;
+        match P::try_unwrap(node) {
+            Ok(node) => {
+                self.0.head = node.next;
+                Some(node.value)
+            }
+            Err(node) => {
+                self.0.head = node.next.clone();
+                Some(node.value.clone())
+            }
+        }
+    }
+}
+
+impl<T: Clone, P: PointerFamily> IntoIterator for List<T, P> {
+    type IntoIter = IntoIter<T, P>;
+    type Item = T;
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+// The nodes are immutable once created, so they are created from the back
+impl<T, P: PointerFamily> FromValues<T> for List<T, P> {
+    fn from_values<I: IntoIterator<Item = T>>(values: I) -> Self {
+        let values: Vec<T> = values.into_iter().collect();
+        values
+            .into_iter()
+            .rev()
+            .fold(List::default(), |list, value| list.prepend(value))
+    }
+}
+
+impl<T, P: PointerFamily> Drop for List<T, P> {
     fn drop(&mut self) {
         let mut current = self.head.take();
         while let Some(rc) = current {
-            if let Ok(mut node) = Rc::try_unwrap(rc) {
+            if let Ok(mut node) = P::try_unwrap(rc) {
                 current = node.next.take();
             } else {
                 break;
@@ -77,9 +168,48 @@ impl<T> Drop for List<T> {
     }
 }
 
+// Lists sharing their tails are serialized independently, and are
+// deserialized into as many copies: the sharing is lost
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize, P: PointerFamily> serde::Serialize for List<T, P> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // formats like bincode need the length first
+        use serde::ser::SerializeSeq;
+        let mut seq = serializer.serialize_seq(Some(self.iter().count()))?;
+        for value in self.iter() {
+            seq.serialize_element(value)?;
+        }
+        seq.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>, P: PointerFamily> serde::Deserialize<'de> for List<T, P> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let values = Vec::<T>::deserialize(deserializer)?;
+        let mut list = List::default();
+        for value in values.into_iter().rev() {
+            list = list.prepend(value);
+        }
+        Ok(list)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, T: arbitrary::Arbitrary<'a>, P: PointerFamily> arbitrary::Arbitrary<'a> for List<T, P> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut list = List::default();
+        for value in u.arbitrary_iter()? {
+            list = list.prepend(value?);
+        }
+        Ok(list)
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::List;
+    use super::{List, SyncList};
+    use std::thread;
 
     #[test]
     fn basics() {
@@ -112,4 +242,34 @@ mod test {
         assert_eq!(iter.next(), Some(&2));
         assert_eq!(iter.next(), Some(&1));
     }
+
+    #[test]
+    fn clone() {
+        let list = List::new().prepend(1).prepend(2);
+        let other = list.clone().prepend(3);
+        drop(list);
+        assert_eq!(other.iter().copied().collect::<Vec<_>>(), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn shared_between_threads() {
+        let list = SyncList::default().prepend(1).prepend(2);
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let list = list.clone();
+                thread::spawn(move || list.prepend(i).iter().sum::<i32>())
+            })
+            .collect();
+        drop(list);
+        let sums: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert_eq!(sums, vec![3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn const_new() {
+        thread_local! {
+            static LIST: List<i32> = const { List::new() };
+        }
+        LIST.with(|list| assert_eq!(list.prepend(1).head(), Some(&1)));
+    }
 }
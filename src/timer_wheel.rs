@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+
+use crate::sixth::{LinkedList, NodeHandle};
+
+const SLOT_BITS: u32 = 6;
+const SLOTS: usize = 1 << SLOT_BITS;
+const LEVELS: usize = 4;
+// Timers further away than the wheels can represent wait in an extra list
+const OVERFLOW: usize = LEVELS * SLOTS;
+
+struct Entry<T> {
+    id: u64,
+    deadline: u64,
+    item: T,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct TimerHandle(u64);
+
+// Level l has SLOTS slots covering SLOTS^l ticks each. A timer goes to the
+// level of the highest group of bits in which its deadline differs from the
+// current time, and is moved down to a finer level when the time reaches the
+// start of its slot.
+pub struct TimerWheel<T> {
+    now: u64,
+    slots: Vec<LinkedList<Entry<T>>>,
+    timers: HashMap<u64, (usize, NodeHandle<Entry<T>>)>,
+    next_id: u64,
+}
+
+impl<T> TimerWheel<T> {
+    pub fn new() -> Self {
+        TimerWheel {
+            now: 0,
+            slots: (0..=OVERFLOW).map(|_| LinkedList::new()).collect(),
+            timers: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    pub fn len(&self) -> usize {
+        self.timers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    // The item is returned by the tick reaching now + after; a delay of 0
+    // counts as 1
+    pub fn schedule(&mut self, after: u64, item: T) -> TimerHandle {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.insert(Entry {
+            id,
+            deadline: self.now + after.max(1),
+            item,
+        });
+        TimerHandle(id)
+    }
+
+    // Gives the item back, unless the timer already fired or was cancelled
+    pub fn cancel(&mut self, handle: TimerHandle) -> Option<T> {
+        let (slot, node) = self.timers.remove(&handle.0)?;
+        let entry = unsafe { self.slots[slot].remove_handle(node) };
+        Some(entry.item)
+    }
+
+    // Advances the time by one tick, and returns the items due at that time
+    pub fn tick(&mut self) -> Vec<T> {
+        self.now += 1;
+        if self.now.trailing_zeros() >= SLOT_BITS * LEVELS as u32 {
+            self.cascade(OVERFLOW);
+        }
+        for level in (1..LEVELS).rev() {
+            if self.now.trailing_zeros() >= SLOT_BITS * level as u32 {
+                self.cascade(Self::slot(level, self.now));
+            }
+        }
+        let slot = Self::slot(0, self.now);
+        let mut expired = Vec::with_capacity(self.slots[slot].len());
+        while let Some(entry) = self.slots[slot].pop_front() {
+            debug_assert_eq!(entry.deadline, self.now);
+            self.timers.remove(&entry.id);
+            expired.push(entry.item);
+        }
+        expired
+    }
+
+    fn slot(level: usize, time: u64) -> usize {
+        level * SLOTS + (time >> (SLOT_BITS * level as u32)) as usize % SLOTS
+    }
+
+    fn insert(&mut self, entry: Entry<T>) {
+        let diff = entry.deadline ^ self.now;
+        let level = (0..LEVELS).find(|&level| diff >> (SLOT_BITS * (level as u32 + 1)) == 0);
+        let slot = match level {
+            Some(level) => Self::slot(level, entry.deadline),
+            None => OVERFLOW,
+        };
+        let id = entry.id;
+        let node = self.slots[slot].push_back_handle(entry);
+        self.timers.insert(id, (slot, node));
+    }
+
+    fn cascade(&mut self, slot: usize) {
+        let mut entries = std::mem::take(&mut self.slots[slot]);
+        while let Some(entry) = entries.pop_front() {
+            self.insert(entry);
+        }
+    }
+}
+
+impl<T> Default for TimerWheel<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TimerWheel;
+
+    #[test]
+    fn basics() {
+        let mut wheel = TimerWheel::new();
+        assert!(wheel.is_empty());
+        wheel.schedule(2, "b");
+        wheel.schedule(1, "a");
+        wheel.schedule(0, "zero");
+        wheel.schedule(2, "c");
+        assert_eq!(wheel.len(), 4);
+
+        assert_eq!(wheel.tick(), vec!["a", "zero"]);
+        assert_eq!(wheel.tick(), vec!["b", "c"]);
+        assert!(wheel.tick().is_empty());
+        assert_eq!(wheel.now(), 3);
+        assert!(wheel.is_empty());
+    }
+
+    #[test]
+    fn cancel() {
+        let mut wheel = TimerWheel::new();
+        let a = wheel.schedule(5, 'a');
+        let b = wheel.schedule(5, 'b');
+        let c = wheel.schedule(100_000, 'c');
+        assert_eq!(wheel.cancel(a), Some('a'));
+        assert_eq!(wheel.cancel(a), None);
+        assert_eq!(wheel.cancel(c), Some('c'));
+        for _ in 0..4 {
+            assert!(wheel.tick().is_empty());
+        }
+        assert_eq!(wheel.tick(), vec!['b']);
+        // already fired
+        assert_eq!(wheel.cancel(b), None);
+        assert!(wheel.is_empty());
+    }
+
+    #[test]
+    fn fires_at_deadline() {
+        // delays crossing every level, and beyond the last one
+        let delays = [
+            1,
+            63,
+            64,
+            65,
+            100,
+            4095,
+            4096,
+            4097,
+            300_000,
+            1 << 24,
+            (1 << 24) + 3,
+        ];
+        let mut wheel = TimerWheel::new();
+        for _ in 0..1000 {
+            wheel.tick();
+        }
+        let start = wheel.now();
+        for &delay in &delays {
+            wheel.schedule(delay, delay);
+        }
+        let mut fired = Vec::new();
+        while !wheel.is_empty() {
+            for delay in wheel.tick() {
+                assert_eq!(wheel.now() - start, delay);
+                fired.push(delay);
+            }
+        }
+        assert_eq!(fired, delays);
+    }
+
+    #[test]
+    fn many_timers() {
+        let mut wheel = TimerWheel::new();
+        let handles: Vec<_> = (0..10_000u64)
+            .map(|i| wheel.schedule(i * 7 % 5000, i))
+            .collect();
+        for handle in handles.iter().step_by(2) {
+            assert!(wheel.cancel(*handle).is_some());
+        }
+        let mut fired = 0;
+        let mut last_deadline = 0;
+        while !wheel.is_empty() {
+            for i in wheel.tick() {
+                assert_eq!(i % 2, 1);
+                let deadline = (i * 7 % 5000).max(1);
+                assert_eq!(deadline, wheel.now());
+                assert!(deadline >= last_deadline);
+                last_deadline = deadline;
+                fired += 1;
+            }
+        }
+        assert_eq!(fired, 5000);
+    }
+}
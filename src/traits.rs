@@ -0,0 +1,185 @@
+// Operations shared by the lists of the crate, so that code can be written
+// once for all of them. Only owning operations are included, since the lists
+// do not all give access to their values the same way.
+
+pub trait Stack<T> {
+    fn push(&mut self, value: T);
+    fn pop(&mut self) -> Option<T>;
+}
+
+pub trait Queue<T> {
+    fn enqueue(&mut self, value: T);
+    fn dequeue(&mut self) -> Option<T>;
+}
+
+pub trait Deque<T> {
+    fn push_front(&mut self, value: T);
+    fn push_back(&mut self, value: T);
+    fn pop_front(&mut self) -> Option<T>;
+    fn pop_back(&mut self) -> Option<T>;
+}
+
+pub trait SortedList<T: Ord> {
+    fn insert(&mut self, value: T);
+    fn pop_min(&mut self) -> Option<T>;
+}
+
+// Builds a list whose values, from its front, are those of the iterator in
+// order; for a stack the front is the top, and for a persistent list the head
+pub trait FromValues<T>: Sized {
+    fn from_values<I: IntoIterator<Item = T>>(values: I) -> Self;
+}
+
+// Converts any list of the crate into any other, keeping the values in the
+// same order from the front. The values are moved, except for those of a
+// persistent list that other lists share, which are cloned.
+pub trait FromList<L>: Sized {
+    fn from_list(list: L) -> Self;
+}
+
+impl<L: IntoIterator, M: FromValues<L::Item>> FromList<L> for M {
+    fn from_list(list: L) -> Self {
+        M::from_values(list)
+    }
+}
+
+pub trait IntoList<M> {
+    fn into_list(self) -> M;
+}
+
+impl<L, M: FromList<L>> IntoList<M> for L {
+    fn into_list(self) -> M {
+        M::from_list(self)
+    }
+}
+
+// The number of bytes allocated for the nodes of a list, which does not
+// include the memory the values own themselves
+pub trait HeapSize {
+    fn heap_size(&self) -> usize;
+}
+
+#[cfg(all(
+    test,
+    feature = "first",
+    feature = "persistent",
+    feature = "rc-deque",
+    feature = "unsafe-queue",
+    feature = "unsafe-deque",
+    feature = "sorted"
+))]
+mod test {
+    use super::{Deque, FromList, IntoList, Queue, SortedList, Stack};
+    use crate::{fifth, first, fourth, sixth, sorted, third};
+
+    fn check_stack<S: Stack<i32> + Default>() {
+        let mut stack = S::default();
+        assert_eq!(stack.pop(), None);
+        for i in 0..5 {
+            stack.push(i);
+        }
+        assert_eq!(stack.pop(), Some(4));
+        stack.push(10);
+        let mut values = Vec::new();
+        while let Some(value) = stack.pop() {
+            values.push(value);
+        }
+        assert_eq!(values, vec![10, 3, 2, 1, 0]);
+    }
+
+    fn check_queue<Q: Queue<i32> + Default>() {
+        let mut queue = Q::default();
+        assert_eq!(queue.dequeue(), None);
+        for i in 0..5 {
+            queue.enqueue(i);
+        }
+        assert_eq!(queue.dequeue(), Some(0));
+        queue.enqueue(10);
+        let mut values = Vec::new();
+        while let Some(value) = queue.dequeue() {
+            values.push(value);
+        }
+        assert_eq!(values, vec![1, 2, 3, 4, 10]);
+    }
+
+    fn check_deque<D: Deque<i32> + Default>() {
+        let mut deque = D::default();
+        assert_eq!(deque.pop_front(), None);
+        assert_eq!(deque.pop_back(), None);
+        deque.push_back(2);
+        deque.push_front(1);
+        deque.push_back(3);
+        assert_eq!(deque.pop_back(), Some(3));
+        assert_eq!(deque.pop_front(), Some(1));
+        assert_eq!(deque.pop_front(), Some(2));
+        assert_eq!(deque.pop_back(), None);
+    }
+
+    fn check_sorted<S: SortedList<i32> + Default>() {
+        let mut list = S::default();
+        for value in [3, 1, 4, 1, 5, 9, 2, 6] {
+            list.insert(value);
+        }
+        let mut values = Vec::new();
+        while let Some(value) = list.pop_min() {
+            values.push(value);
+        }
+        assert_eq!(values, vec![1, 1, 2, 3, 4, 5, 6, 9]);
+    }
+
+    #[test]
+    fn stacks() {
+        check_stack::<first::List<_>>();
+        check_stack::<third::List<_>>();
+        check_stack::<fourth::List<_>>();
+        check_stack::<sixth::LinkedList<_>>();
+    }
+
+    #[test]
+    fn queues() {
+        check_queue::<fourth::List<_>>();
+        check_queue::<fifth::List<_>>();
+        check_queue::<sixth::LinkedList<_>>();
+    }
+
+    #[test]
+    fn deques() {
+        check_deque::<fourth::List<_>>();
+        check_deque::<sixth::LinkedList<_>>();
+    }
+
+    #[test]
+    fn sorted_lists() {
+        check_sorted::<sorted::List<_>>();
+    }
+
+    #[test]
+    fn conversions() {
+        let stack = first::List::from_list(vec![1, 2, 3]);
+        assert_eq!(stack.peek(), Some(&1));
+        let list: sixth::LinkedList<_> = stack.into_list();
+        assert!(list.iter().eq(&[1, 2, 3]));
+        let queue: fifth::List<_> = list.into_list();
+        let persistent: third::List<_> = queue.into_list();
+        assert!(persistent.iter().eq(&[1, 2, 3]));
+        let deque: fourth::List<_> = persistent.into_list();
+        assert_eq!(*deque.peek_back().unwrap(), 3);
+        let sorted: sorted::List<_> = deque.into_iter().rev().collect::<Vec<_>>().into_list();
+        assert_eq!(sorted.peek_min(), Some(&1));
+        let mut stack: first::List<_> = sorted.into_list();
+        assert_eq!(stack.pop_front(), Some(1));
+        assert_eq!(Vec::from_iter(stack), [2, 3]);
+    }
+
+    #[test]
+    fn shared_values_are_cloned() {
+        let shared: third::List<_> = vec![String::from("b"), String::from("c")].into_list();
+        let list = shared.prepend(String::from("a"));
+        let values: Vec<String> = list.into_iter().collect();
+        assert_eq!(values, ["a", "b", "c"]);
+        // the tail was shared, so it is still there
+        assert!(shared.iter().eq(["b", "c"].iter()));
+        let values: sixth::LinkedList<String> = shared.into_list();
+        assert_eq!(values.len(), 2);
+    }
+}
@@ -0,0 +1,181 @@
+use crate::first::List;
+
+// Values are pushed on the inbox and popped from the outbox. When the outbox
+// runs out, the whole inbox is moved onto it, which reverses it: each value is
+// moved once, so operations are O(1) amortized.
+pub struct Queue<T> {
+    inbox: List<T>,
+    outbox: List<T>,
+    len: usize,
+    moved: usize,
+}
+
+impl<T> Queue<T> {
+    pub fn new() -> Self {
+        Queue {
+            inbox: List::new(),
+            outbox: List::new(),
+            len: 0,
+            moved: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // Total number of values moved from the inbox to the outbox so far
+    pub fn moved(&self) -> usize {
+        self.moved
+    }
+
+    pub fn push_back(&mut self, value: T) {
+        self.inbox.push_front(value);
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.refill();
+        let value = self.outbox.pop_front()?;
+        self.len -= 1;
+        Some(value)
+    }
+
+    // Takes &mut self since the front may have to be moved to the outbox
+    pub fn peek_front(&mut self) -> Option<&T> {
+        self.refill();
+        self.outbox.peek()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let inbox: Vec<_> = self.inbox.iter().collect();
+        self.outbox.iter().chain(inbox.into_iter().rev())
+    }
+
+    fn refill(&mut self) {
+        if self.outbox.peek().is_some() {
+            return;
+        }
+        while let Some(value) = self.inbox.pop_front() {
+            self.outbox.push_front(value);
+            self.moved += 1;
+        }
+    }
+}
+
+impl<T> Default for Queue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Extend<T> for Queue<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push_back(value);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for Queue<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut queue = Queue::new();
+        queue.extend(iter);
+        queue
+    }
+}
+
+pub struct IntoIter<T>(Queue<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl<T> IntoIterator for Queue<T> {
+    type IntoIter = IntoIter<T>;
+    type Item = T;
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Queue;
+
+    #[test]
+    fn basics() {
+        let mut queue = Queue::new();
+        assert!(queue.is_empty());
+        assert_eq!(queue.pop_front(), None);
+        assert_eq!(queue.peek_front(), None);
+
+        queue.push_back(1);
+        queue.push_back(2);
+        assert_eq!(queue.peek_front(), Some(&1));
+        queue.push_back(3);
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        assert_eq!(queue.pop_front(), Some(1));
+        queue.push_back(4);
+        assert_eq!(queue.pop_front(), Some(2));
+        assert_eq!(queue.pop_front(), Some(3));
+        assert_eq!(queue.pop_front(), Some(4));
+        assert_eq!(queue.pop_front(), None);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn each_value_moves_once() {
+        let mut queue = Queue::new();
+        let mut expected = 0;
+        for i in 0..10_000 {
+            queue.push_back(i);
+            if i % 3 != 0 {
+                assert_eq!(queue.pop_front(), Some(expected));
+                expected += 1;
+            }
+        }
+        assert!(queue.moved() <= 10_000);
+        assert_eq!(
+            queue.into_iter().collect::<Vec<_>>(),
+            (expected..10_000).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn reversal_cost() {
+        // all the cost is paid by the first pop after a run of pushes
+        let mut queue: Queue<_> = (0..1000).collect();
+        assert_eq!(queue.moved(), 0);
+        assert_eq!(queue.pop_front(), Some(0));
+        assert_eq!(queue.moved(), 1000);
+        for i in 1..1000 {
+            assert_eq!(queue.pop_front(), Some(i));
+        }
+        assert_eq!(queue.moved(), 1000);
+
+        // pushing while the outbox is not empty does not move anything
+        queue.push_back(0);
+        queue.push_back(1);
+        queue.pop_front();
+        queue.push_back(2);
+        assert_eq!(queue.moved(), 1002);
+        assert_eq!(queue.pop_front(), Some(1));
+        assert_eq!(queue.moved(), 1002);
+        assert_eq!(queue.pop_front(), Some(2));
+        assert_eq!(queue.moved(), 1003);
+    }
+}
@@ -0,0 +1,67 @@
+use js_sys::{Array, Function};
+use wasm_bindgen::prelude::*;
+
+use crate::sixth::LinkedList;
+
+// The deque as seen from JavaScript, where it is exported as Deque. The values
+// are whatever JavaScript pushes, and popping an empty deque gives undefined.
+#[wasm_bindgen(js_name = Deque)]
+#[derive(Default)]
+pub struct JsDeque {
+    list: LinkedList<JsValue>,
+}
+
+#[wasm_bindgen(js_class = Deque)]
+impl JsDeque {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        JsDeque {
+            list: LinkedList::new(),
+        }
+    }
+
+    #[wasm_bindgen(js_name = pushFront)]
+    pub fn push_front(&mut self, value: JsValue) {
+        self.list.push_front(value);
+    }
+
+    #[wasm_bindgen(js_name = pushBack)]
+    pub fn push_back(&mut self, value: JsValue) {
+        self.list.push_back(value);
+    }
+
+    #[wasm_bindgen(js_name = popFront)]
+    pub fn pop_front(&mut self) -> Option<JsValue> {
+        self.list.pop_front()
+    }
+
+    #[wasm_bindgen(js_name = popBack)]
+    pub fn pop_back(&mut self) -> Option<JsValue> {
+        self.list.pop_back()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.list.len()
+    }
+
+    #[wasm_bindgen(js_name = isEmpty)]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    // Calls f with each value and its index, from front to back, like
+    // Array.prototype.forEach; stops at the first exception f throws
+    #[wasm_bindgen(js_name = forEach)]
+    pub fn for_each(&self, f: &Function) -> Result<(), JsValue> {
+        for (i, value) in self.list.iter().enumerate() {
+            f.call2(&JsValue::UNDEFINED, value, &JsValue::from(i as u32))?;
+        }
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = toArray)]
+    pub fn to_array(&self) -> Array {
+        self.list.iter().collect()
+    }
+}
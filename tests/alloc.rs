@@ -0,0 +1,213 @@
+#![cfg(all(
+    feature = "test-utils",
+    feature = "first",
+    feature = "persistent",
+    feature = "rc-deque",
+    feature = "unsafe-queue",
+    feature = "unsafe-deque",
+    feature = "sorted",
+    not(feature = "pool")
+))]
+
+// Counts the allocations made by the lists, to check that each node costs one
+// allocation and that everything is freed. With the pool, freed nodes are
+// kept instead, which tests/pool.rs checks.
+
+use rust_too_many_lists::test_utils::{count_allocs, CountingAllocator};
+use rust_too_many_lists::traits::HeapSize;
+use rust_too_many_lists::{fifth, first, fourth, sixth, small_list, sorted, third};
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+// Checks that pushing n values allocates n nodes, and that dropping the list
+// frees them all
+fn assert_one_node_per_value<L>(n: usize, build: impl FnOnce(usize) -> L) {
+    let (list, counts) = count_allocs(|| build(n));
+    assert_eq!((counts.allocations, counts.frees), (n, 0));
+    let ((), counts) = count_allocs(|| drop(list));
+    assert_eq!((counts.allocations, counts.frees), (0, n));
+}
+
+#[test]
+fn first() {
+    assert_one_node_per_value(100, |n| {
+        let mut list = first::List::new();
+        for i in 0..n {
+            list.push_front(i);
+        }
+        list
+    });
+}
+
+#[test]
+fn third() {
+    assert_one_node_per_value(100, |n| {
+        let mut list: third::List<_> = third::List::new();
+        for i in 0..n {
+            list = list.prepend(i);
+        }
+        list
+    });
+}
+
+#[test]
+fn fourth() {
+    assert_one_node_per_value(100, |n| {
+        let mut list = fourth::List::new();
+        for i in 0..n {
+            if i % 2 == 0 {
+                list.push_front(i);
+            } else {
+                list.push_back(i);
+            }
+        }
+        list
+    });
+}
+
+#[test]
+fn fifth() {
+    assert_one_node_per_value(100, |n| {
+        let mut list = fifth::List::new();
+        for i in 0..n {
+            list.push(i);
+        }
+        list
+    });
+}
+
+#[test]
+fn sixth() {
+    assert_one_node_per_value(100, |n| {
+        let mut list = sixth::LinkedList::new();
+        for i in 0..n {
+            if i % 2 == 0 {
+                list.push_front(i);
+            } else {
+                list.push_back(i);
+            }
+        }
+        list
+    });
+}
+
+#[test]
+fn popping_frees() {
+    let mut list = fourth::List::new();
+    let mut queue = fifth::List::new();
+    let mut deque = sixth::LinkedList::new();
+    for i in 0..10 {
+        list.push_back(i);
+        queue.push(i);
+        deque.push_back(i);
+    }
+    let (_, counts) = count_allocs(|| {
+        list.pop_front();
+        list.pop_back();
+        queue.pop();
+        deque.pop_front();
+        deque.pop_back();
+    });
+    assert_eq!((counts.allocations, counts.frees), (0, 5));
+}
+
+#[test]
+fn shared_tails_are_freed_once() {
+    let (_, counts) = count_allocs(|| {
+        let base: third::List<_> = third::List::new().prepend(1).prepend(2);
+        let a = base.prepend(3);
+        let b = base.prepend(4);
+        drop(base);
+        drop(a);
+        drop(b);
+    });
+    assert_eq!((counts.allocations, counts.frees), (4, 4));
+}
+
+#[test]
+fn cursor_splice_and_split() {
+    // relinking nodes must not allocate or free any of them
+    let mut list: sixth::LinkedList<_> = (0..10).collect();
+    let other: sixth::LinkedList<_> = (10..20).collect();
+    let (rest, counts) = count_allocs(|| {
+        let mut cursor = list.cursor_mut();
+        cursor.move_next();
+        cursor.move_next();
+        cursor.splice_after(other);
+        cursor.move_next();
+        cursor.split_after()
+    });
+    assert_eq!((counts.allocations, counts.frees), (0, 0));
+    assert_eq!(list.len() + rest.len(), 20);
+    let ((), counts) = count_allocs(|| {
+        drop(list);
+        drop(rest);
+    });
+    assert_eq!((counts.allocations, counts.frees), (0, 20));
+}
+
+// Checks that the size reported by the list is what building it allocated,
+// and what dropping it frees
+fn assert_heap_size<L: HeapSize>(build: impl FnOnce() -> L) {
+    let (list, counts) = count_allocs(build);
+    let heap_size = list.heap_size();
+    assert_eq!(heap_size, counts.allocated_bytes - counts.freed_bytes);
+    let ((), counts) = count_allocs(|| drop(list));
+    assert_eq!(counts.freed_bytes, heap_size);
+}
+
+#[test]
+fn heap_size() {
+    assert_heap_size(|| {
+        let mut list = first::List::new();
+        for i in 0..10u8 {
+            list.push_front(i);
+        }
+        list
+    });
+    assert_heap_size(|| (0..10u16).fold(third::List::<_>::new(), |list, i| list.prepend(i)));
+    assert_heap_size(|| {
+        let mut list = fourth::List::new();
+        for i in 0..10u32 {
+            list.push_back(i);
+        }
+        list
+    });
+    assert_heap_size(|| {
+        let mut list = fifth::List::new();
+        for i in 0..10u64 {
+            list.push(i);
+        }
+        list
+    });
+    assert_heap_size(|| (0..10u128).collect::<sixth::LinkedList<_>>());
+    assert_heap_size(|| (0..10u8).rev().collect::<sorted::List<_>>());
+    assert_heap_size(|| (0..10u8).collect::<small_list::SmallList<_, 4>>());
+    assert_heap_size(|| (0..3u8).collect::<small_list::SmallList<_, 4>>());
+}
+
+#[test]
+fn heap_size_of_shared_tails() {
+    let ((base, a, b), counts) = count_allocs(|| {
+        let base: third::List<_> = third::List::new().prepend(1).prepend(2);
+        let a = base.prepend(3);
+        let b = base.prepend(4);
+        (base, a, b)
+    });
+    let allocated = counts.allocated_bytes - counts.freed_bytes;
+    // each list reaches three nodes, one of which only it points to
+    assert_eq!(a.heap_size(), b.heap_size());
+    assert_eq!(a.heap_size(), 3 * a.unique_heap_size());
+    assert_eq!(base.unique_heap_size(), 0);
+    assert_eq!(
+        a.unique_heap_size() + b.unique_heap_size() + base.heap_size(),
+        allocated
+    );
+    // once b is gone, a is the only one left to point to the tail
+    drop(base);
+    drop(b);
+    assert_eq!(a.unique_heap_size(), a.heap_size());
+    let ((), counts) = count_allocs(|| drop(a));
+    assert_eq!(counts.freed_bytes, allocated / 4 * 3);
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "allocator-api")]
+#![feature(allocator_api)]
+
+// sixth::LinkedList with an allocator of its own, counting what goes through
+// it. Every node is allocated and freed by the allocator of its list, which
+// the lists split from it share.
+
+use std::alloc::{AllocError, Allocator, Global, Layout};
+use std::cell::Cell;
+use std::ptr::NonNull;
+use std::rc::Rc;
+
+use rust_too_many_lists::sixth::LinkedList;
+
+#[derive(Clone, Default)]
+struct Counting {
+    allocs: Rc<Cell<usize>>,
+    frees: Rc<Cell<usize>>,
+}
+
+impl Counting {
+    fn live(&self) -> usize {
+        self.allocs.get() - self.frees.get()
+    }
+}
+
+unsafe impl Allocator for Counting {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.allocs.set(self.allocs.get() + 1);
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.frees.set(self.frees.get() + 1);
+        Global.deallocate(ptr, layout)
+    }
+}
+
+#[test]
+fn nodes_go_through_the_allocator() {
+    let alloc = Counting::default();
+    let mut list = LinkedList::new_in(alloc.clone());
+    for i in 0..10 {
+        list.push_back(i);
+    }
+    assert_eq!(alloc.allocs.get(), 10);
+    assert_eq!(list.pop_front(), Some(0));
+    assert_eq!(alloc.frees.get(), 1);
+
+    drop(list);
+    assert_eq!(alloc.live(), 0);
+}
+
+#[test]
+fn split_lists_share_the_allocator() {
+    let alloc = Counting::default();
+    let mut list = LinkedList::new_in(alloc.clone());
+    list.extend(0..6);
+
+    let mut cursor = list.cursor_mut();
+    cursor.move_next();
+    cursor.move_next();
+    let tail = cursor.split_after();
+    assert_eq!(tail.iter().copied().collect::<Vec<_>>(), [2, 3, 4, 5]);
+
+    let clone = tail.clone();
+    assert_eq!(alloc.live(), 10);
+    drop(tail);
+    drop(list);
+    assert_eq!(alloc.live(), 4);
+    drop(clone);
+    assert_eq!(alloc.live(), 0);
+}
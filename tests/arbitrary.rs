@@ -0,0 +1,105 @@
+#![cfg(all(
+    feature = "arbitrary",
+    feature = "first",
+    feature = "persistent",
+    feature = "rc-deque",
+    feature = "unsafe-queue",
+    feature = "unsafe-deque",
+    feature = "sorted"
+))]
+
+// The lists are generated from the same bytes as a Vec would be, so each one
+// is checked against the Vec generated from the same input.
+
+use arbitrary::{Arbitrary, Unstructured};
+
+use rust_too_many_lists::ops::Op;
+use rust_too_many_lists::{fifth, first, fourth, sixth, sorted, third};
+
+// Deterministic bytes, so that the tests do not depend on a fuzzer
+fn bytes(seed: u64, len: usize) -> Vec<u8> {
+    let mut x = seed;
+    (0..len)
+        .map(|_| {
+            x = x
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (x >> 33) as u8
+        })
+        .collect()
+}
+
+fn generate<'a, T: Arbitrary<'a>>(data: &'a [u8]) -> T {
+    T::arbitrary(&mut Unstructured::new(data)).unwrap()
+}
+
+#[test]
+fn same_values_as_vec() {
+    for seed in 0..20 {
+        let data = bytes(seed, 256);
+        let values: Vec<u16> = generate(&data);
+
+        let list: first::List<u16> = generate(&data);
+        let mut stack = values.clone();
+        stack.reverse();
+        assert!(list.iter().eq(stack.iter()));
+
+        let list: third::List<u16> = generate(&data);
+        assert!(list.iter().eq(stack.iter()));
+
+        let list: fourth::List<u16> = generate(&data);
+        assert!(list.into_iter().eq(values.iter().copied()));
+
+        let mut list: fifth::List<u16> = generate(&data);
+        for &value in &values {
+            assert_eq!(list.pop(), Some(value));
+        }
+        assert_eq!(list.pop(), None);
+
+        let list: sixth::LinkedList<u16> = generate(&data);
+        assert!(list.iter().eq(values.iter()));
+
+        let mut list: sorted::List<u16> = generate(&data);
+        let mut sorted = values.clone();
+        sorted.sort();
+        for value in sorted {
+            assert_eq!(list.pop_min(), Some(value));
+        }
+        assert!(list.is_empty());
+    }
+}
+
+#[test]
+fn empty_input() {
+    let list: sixth::LinkedList<u32> = generate(&[]);
+    assert!(list.is_empty());
+    let ops: Vec<Op<u32>> = generate(&[]);
+    assert!(ops.is_empty());
+}
+
+#[test]
+fn replay_ops() {
+    // whatever the sequence, a deque agrees with the model it is replayed on
+    for seed in 0..20 {
+        let data = bytes(seed, 1024);
+        let ops: Vec<Op<u8>> = generate(&data);
+        let mut model = std::collections::VecDeque::new();
+        let mut list = sixth::LinkedList::new();
+        for op in ops {
+            let expected = match op {
+                Op::PushFront(value) => {
+                    model.push_front(value);
+                    None
+                }
+                Op::PushBack(value) => {
+                    model.push_back(value);
+                    None
+                }
+                Op::PopFront => model.pop_front(),
+                Op::PopBack => model.pop_back(),
+            };
+            assert_eq!(op.apply_to_deque(&mut list), expected);
+        }
+        assert!(list.iter().eq(model.iter()));
+    }
+}
@@ -0,0 +1,83 @@
+#![cfg(all(
+    feature = "rc-deque",
+    feature = "unsafe-queue",
+    feature = "unsafe-deque"
+))]
+
+// The values of the lists are dropped from the front to the back, unless
+// drop_back_to_front is used.
+
+use std::cell::RefCell;
+
+use rust_too_many_lists::{fifth, fourth, sixth};
+
+struct Recorder<'a> {
+    id: usize,
+    dropped: &'a RefCell<Vec<usize>>,
+}
+
+impl Drop for Recorder<'_> {
+    fn drop(&mut self) {
+        self.dropped.borrow_mut().push(self.id);
+    }
+}
+
+fn recorders(dropped: &RefCell<Vec<usize>>) -> impl Iterator<Item = Recorder<'_>> {
+    (0..5).map(move |id| Recorder { id, dropped })
+}
+
+fn check(dropped: &RefCell<Vec<usize>>, front_to_back: bool) {
+    let mut expected: Vec<_> = (0..5).collect();
+    if !front_to_back {
+        expected.reverse();
+    }
+    assert_eq!(dropped.take(), expected);
+}
+
+#[test]
+fn fourth() {
+    let dropped = RefCell::new(Vec::new());
+    let build = || {
+        let mut list = fourth::List::new();
+        for recorder in recorders(&dropped) {
+            list.push_back(recorder);
+        }
+        list
+    };
+    drop(build());
+    check(&dropped, true);
+    build().drop_back_to_front();
+    check(&dropped, false);
+}
+
+#[test]
+fn fifth() {
+    let dropped = RefCell::new(Vec::new());
+    let build = || {
+        let mut list = fifth::List::new();
+        for recorder in recorders(&dropped) {
+            list.push(recorder);
+        }
+        list
+    };
+    drop(build());
+    check(&dropped, true);
+    build().drop_back_to_front();
+    check(&dropped, false);
+    fifth::List::<Recorder>::new().drop_back_to_front();
+}
+
+#[test]
+fn sixth() {
+    let dropped = RefCell::new(Vec::new());
+    let build = || recorders(&dropped).collect::<sixth::LinkedList<_>>();
+    drop(build());
+    check(&dropped, true);
+    build().drop_back_to_front();
+    check(&dropped, false);
+    // the iterator drops what is left the same way
+    let mut iter = build().into_iter();
+    iter.next();
+    drop(iter);
+    check(&dropped, true);
+}
@@ -0,0 +1,48 @@
+#![cfg(all(
+    feature = "nightly",
+    feature = "unsafe-queue",
+    feature = "unsafe-deque",
+    feature = "ring"
+))]
+
+// Like std's LinkedList, the unsafe lists let their values borrow something
+// that is dropped before them, as long as the values do not use it when they
+// are dropped. The patterns that must keep being rejected are in tests/ui.
+
+use std::collections::LinkedList as StdList;
+
+use rust_too_many_lists::small_list::SmallList;
+use rust_too_many_lists::{fifth, ring, sixth};
+
+#[test]
+fn references_declared_after_the_list() {
+    let mut std_list = StdList::new();
+    let mut list = sixth::LinkedList::new();
+    let mut queue = fifth::List::new();
+    let mut ring = ring::Ring::new();
+    let mut small: SmallList<_, 1> = SmallList::new();
+    let s = String::from("dangling");
+    std_list.push_back(&s);
+    list.push_back(&s);
+    queue.push(&s);
+    ring.push_back(&s);
+    small.push_back(&s);
+    small.push_back(&s);
+    assert_eq!(list.front(), Some(&&s));
+}
+
+#[test]
+fn lists_of_lists_of_references() {
+    let mut list = sixth::LinkedList::new();
+    let s = String::from("dangling");
+    let mut inner = sixth::LinkedList::new();
+    inner.push_back(&s);
+    list.push_back(inner);
+    assert_eq!(list.len(), 1);
+}
+
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/dropck/*.rs");
+}
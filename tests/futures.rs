@@ -0,0 +1,103 @@
+#![cfg(all(
+    feature = "futures",
+    feature = "first",
+    feature = "rc-deque",
+    feature = "concurrent"
+))]
+
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::Duration;
+
+use futures_util::StreamExt;
+
+use rust_too_many_lists::sixth::LinkedList;
+use rust_too_many_lists::sync_deque::SyncDeque;
+use rust_too_many_lists::{first, fourth};
+
+// Just enough of an executor to run the tests: the thread sleeps until it is
+// woken up
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[test]
+fn streams() {
+    block_on(async {
+        let list: LinkedList<_> = (0..10).collect();
+        let doubled: Vec<_> = list.into_stream().map(|v| v * 2).collect().await;
+        assert_eq!(doubled, (0..10).map(|v| v * 2).collect::<Vec<_>>());
+
+        let mut list = fourth::List::new();
+        list.push_back(1);
+        list.push_back(2);
+        let mut stream = list.into_stream();
+        assert_eq!(stream.next().await, Some(1));
+        assert_eq!(stream.next().await, Some(2));
+        assert_eq!(stream.next().await, None);
+
+        let mut list = first::List::new();
+        list.push_front(1);
+        list.push_front(2);
+        assert_eq!(
+            list.into_stream()
+                .fold(0, |a, b| async move { a + b })
+                .await,
+            3
+        );
+    });
+}
+
+#[test]
+fn pop_front_async_ready() {
+    let deque = SyncDeque::new();
+    deque.push_back(1);
+    deque.push_front(0);
+    assert_eq!(block_on(deque.pop_front_async()), 0);
+    assert_eq!(block_on(deque.pop_front_async()), 1);
+}
+
+#[test]
+fn pop_front_async_waits() {
+    let deque = Arc::new(SyncDeque::new());
+    let producer = {
+        let deque = deque.clone();
+        thread::spawn(move || {
+            for i in 0..100 {
+                if i % 10 == 0 {
+                    thread::sleep(Duration::from_millis(5));
+                }
+                deque.push_back(i);
+            }
+        })
+    };
+    let sum = block_on(async {
+        let mut sum = 0;
+        for _ in 0..100 {
+            sum += deque.pop_front_async().await;
+        }
+        sum
+    });
+    producer.join().unwrap();
+    assert_eq!(sum, 4950);
+    assert!(deque.is_empty());
+}
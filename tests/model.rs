@@ -0,0 +1,226 @@
+#![cfg(all(
+    feature = "first",
+    feature = "persistent",
+    feature = "rc-deque",
+    feature = "unsafe-queue",
+    feature = "unsafe-deque"
+))]
+
+// Applies random sequences of operations to the lists and to a VecDeque, and
+// checks after each step that they agree on everything that can be observed.
+
+use std::collections::VecDeque;
+
+use proptest::prelude::*;
+
+use rust_too_many_lists::sixth::LinkedList;
+use rust_too_many_lists::{fifth, fourth, persistent_deque, small_list, two_stack_queue};
+
+#[derive(Clone, Debug)]
+enum Op {
+    PushFront(i32),
+    PushBack(i32),
+    PopFront,
+    PopBack,
+    // the indices are taken modulo the length of the list
+    Insert(usize, i32),
+    Remove(usize),
+    Split(usize),
+    Splice(usize, Vec<i32>),
+    Swap(usize, usize),
+}
+
+fn deque_op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        any::<i32>().prop_map(Op::PushFront),
+        any::<i32>().prop_map(Op::PushBack),
+        Just(Op::PopFront),
+        Just(Op::PopBack),
+    ]
+}
+
+fn queue_op() -> impl Strategy<Value = Op> {
+    prop_oneof![any::<i32>().prop_map(Op::PushBack), Just(Op::PopFront)]
+}
+
+fn any_op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        4 => deque_op(),
+        1 => (any::<usize>(), any::<i32>()).prop_map(|(i, v)| Op::Insert(i, v)),
+        1 => any::<usize>().prop_map(Op::Remove),
+        1 => any::<usize>().prop_map(Op::Split),
+        1 => (any::<usize>(), prop::collection::vec(any::<i32>(), 0..5))
+            .prop_map(|(i, values)| Op::Splice(i, values)),
+        1 => (any::<usize>(), any::<usize>()).prop_map(|(i, j)| Op::Swap(i, j)),
+    ]
+}
+
+// Applies the operation on the model, and returns what popping gave
+fn apply_model(model: &mut VecDeque<i32>, op: &Op) -> Option<i32> {
+    match *op {
+        Op::PushFront(v) => model.push_front(v),
+        Op::PushBack(v) => model.push_back(v),
+        Op::PopFront => return model.pop_front(),
+        Op::PopBack => return model.pop_back(),
+        Op::Insert(i, v) => model.insert(i % (model.len() + 1), v),
+        Op::Remove(i) if !model.is_empty() => return model.remove(i % model.len()),
+        Op::Remove(_) | Op::Split(_) => {}
+        Op::Splice(i, ref values) => {
+            let at = i % (model.len() + 1);
+            let tail = model.split_off(at);
+            model.extend(values.iter().copied());
+            model.extend(tail);
+        }
+        Op::Swap(i, j) if !model.is_empty() => model.swap(i % model.len(), j % model.len()),
+        Op::Swap(..) => {}
+    }
+    None
+}
+
+// sixth does not have split_off yet, so it is written with the cursor
+fn split_off(list: &mut LinkedList<i32>, at: usize) -> LinkedList<i32> {
+    let mut cursor = list.cursor_mut();
+    for _ in 0..at {
+        cursor.move_next();
+    }
+    cursor.split_after()
+}
+
+fn apply_sixth(list: &mut LinkedList<i32>, op: &Op) -> Option<i32> {
+    match *op {
+        Op::PushFront(v) => list.push_front(v),
+        Op::PushBack(v) => list.push_back(v),
+        Op::PopFront => return list.pop_front(),
+        Op::PopBack => return list.pop_back(),
+        Op::Insert(i, v) => list.insert(i % (list.len() + 1), v),
+        Op::Remove(i) if !list.is_empty() => return Some(list.remove(i % list.len())),
+        Op::Remove(_) => {}
+        Op::Split(i) => {
+            let mut tail = split_off(list, i % (list.len() + 1));
+            list.append(&mut tail);
+        }
+        Op::Splice(i, ref values) => {
+            let at = i % (list.len() + 1);
+            let values: LinkedList<_> = values.iter().copied().collect();
+            let mut cursor = list.cursor_mut();
+            for _ in 0..=at {
+                cursor.move_next();
+            }
+            cursor.splice_before(values);
+        }
+        Op::Swap(i, j) if !list.is_empty() => list.swap(i % list.len(), j % list.len()),
+        Op::Swap(..) => {}
+    }
+    None
+}
+
+fn check_sixth(list: &LinkedList<i32>, model: &VecDeque<i32>) {
+    assert_eq!(list.len(), model.len());
+    assert_eq!(list.front(), model.front());
+    assert_eq!(list.back(), model.back());
+    assert!(list.iter().eq(model.iter()));
+    assert!(list.iter().rev().eq(model.iter().rev()));
+}
+
+proptest! {
+    #[test]
+    fn sixth_all_operations(ops in prop::collection::vec(any_op(), 0..200)) {
+        let mut list = LinkedList::new();
+        let mut model = VecDeque::new();
+        for op in &ops {
+            prop_assert_eq!(apply_sixth(&mut list, op), apply_model(&mut model, op));
+            check_sixth(&list, &model);
+        }
+    }
+
+    #[test]
+    fn fourth_deque(ops in prop::collection::vec(deque_op(), 0..200)) {
+        let mut list = fourth::List::new();
+        let mut model = VecDeque::new();
+        for op in &ops {
+            let popped = match *op {
+                Op::PushFront(v) => { list.push_front(v); None }
+                Op::PushBack(v) => { list.push_back(v); None }
+                Op::PopFront => list.pop_front(),
+                _ => list.pop_back(),
+            };
+            prop_assert_eq!(popped, apply_model(&mut model, op));
+            prop_assert_eq!(list.peek_front().map(|v| *v), model.front().copied());
+            prop_assert_eq!(list.peek_back().map(|v| *v), model.back().copied());
+        }
+    }
+
+    #[test]
+    fn small_list_deque(ops in prop::collection::vec(deque_op(), 0..200)) {
+        let mut list: small_list::SmallList<i32, 3> = small_list::SmallList::new();
+        let mut model = VecDeque::new();
+        for op in &ops {
+            let popped = match *op {
+                Op::PushFront(v) => { list.push_front(v); None }
+                Op::PushBack(v) => { list.push_back(v); None }
+                Op::PopFront => list.pop_front(),
+                _ => list.pop_back(),
+            };
+            prop_assert_eq!(popped, apply_model(&mut model, op));
+            prop_assert_eq!(list.len(), model.len());
+            prop_assert!(list.iter().eq(model.iter()));
+        }
+    }
+
+    #[test]
+    fn persistent_deque(ops in prop::collection::vec(deque_op(), 0..200)) {
+        let mut deque = persistent_deque::Deque::new();
+        let mut model = VecDeque::new();
+        for op in &ops {
+            let popped = match *op {
+                Op::PushFront(v) => { deque = deque.push_front(v); None }
+                Op::PushBack(v) => { deque = deque.push_back(v); None }
+                Op::PopFront => {
+                    let front = deque.front().copied();
+                    deque = deque.pop_front();
+                    front
+                }
+                _ => {
+                    let back = deque.back().copied();
+                    deque = deque.pop_back();
+                    back
+                }
+            };
+            prop_assert_eq!(popped, apply_model(&mut model, op));
+            prop_assert_eq!(deque.len(), model.len());
+            prop_assert!(deque.iter().eq(model.iter()));
+        }
+    }
+
+    #[test]
+    fn fifth_queue(ops in prop::collection::vec(queue_op(), 0..200)) {
+        let mut list = fifth::List::new();
+        let mut model = VecDeque::new();
+        for op in &ops {
+            let popped = match *op {
+                Op::PushBack(v) => { list.push(v); None }
+                _ => list.pop(),
+            };
+            prop_assert_eq!(popped, apply_model(&mut model, op));
+        }
+        while let Some(v) = model.pop_front() {
+            prop_assert_eq!(list.pop(), Some(v));
+        }
+        prop_assert_eq!(list.pop(), None);
+    }
+
+    #[test]
+    fn two_stack_queue(ops in prop::collection::vec(queue_op(), 0..200)) {
+        let mut queue = two_stack_queue::Queue::new();
+        let mut model = VecDeque::new();
+        for op in &ops {
+            let popped = match *op {
+                Op::PushBack(v) => { queue.push_back(v); None }
+                _ => queue.pop_front(),
+            };
+            prop_assert_eq!(popped, apply_model(&mut model, op));
+            prop_assert_eq!(queue.len(), model.len());
+            prop_assert!(queue.iter().eq(model.iter()));
+        }
+    }
+}
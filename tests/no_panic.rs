@@ -0,0 +1,52 @@
+#![cfg(all(
+    feature = "no-panic",
+    feature = "unsafe-queue",
+    feature = "unsafe-deque"
+))]
+
+// The check is done by the linker, on the functions instantiated here, so
+// this test only checks anything when it builds, with optimizations:
+//
+//     cargo test --release --features no-panic --test no_panic
+
+use rust_too_many_lists::fifth;
+use rust_too_many_lists::sixth::LinkedList;
+
+// Not inlined, so that each function is instantiated on its own
+#[inline(never)]
+fn sixth<T: PartialEq + std::fmt::Debug>(a: T, b: T) {
+    let mut list = LinkedList::new();
+    list.push_back(a);
+    list.push_front(b);
+    assert!(list.front().is_some());
+    assert!(list.back().is_some());
+    assert!(list.front_mut().is_some());
+    assert!(list.back_mut().is_some());
+    assert_eq!(list.iter().next(), list.front());
+    assert_eq!(list.iter().next_back(), list.back());
+    assert!(list.iter_mut().next().is_some());
+    assert!(list.iter_mut().next_back().is_some());
+    assert!(list.pop_back().is_some());
+    assert!(list.pop_front().is_some());
+    assert!(list.pop_front().is_none());
+}
+
+#[inline(never)]
+fn fifth<T: PartialEq + std::fmt::Debug>(a: T, b: T) {
+    let mut list = fifth::List::new();
+    list.push(a);
+    list.push(b);
+    assert!(list.pop().is_some());
+    assert!(list.pop().is_some());
+    assert!(list.pop().is_none());
+}
+
+#[test]
+fn hot_paths() {
+    sixth(1u8, 2);
+    sixth(String::from("a"), String::from("b"));
+    sixth([0u64; 32], [1; 32]);
+    fifth(1u8, 2);
+    fifth(String::from("a"), String::from("b"));
+    fifth([0u64; 32], [1; 32]);
+}
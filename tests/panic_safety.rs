@@ -0,0 +1,271 @@
+#![cfg(all(
+    feature = "unsafe-queue",
+    feature = "unsafe-deque",
+    feature = "ring",
+    feature = "static-rc"
+))]
+
+// Makes dropping, cloning and iterating panic halfway through the lists'
+// Drop, Clone and Extend, and checks that every value is still dropped
+// exactly once. Under miri, this also checks that no node is leaked. A sort
+// whose comparison panics must also leave all the values in the list.
+
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+
+use rust_too_many_lists::static_rc::GhostToken;
+use rust_too_many_lists::{fifth, ring, sixth, small_list, static_rc};
+
+thread_local! {
+    static DROPS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn drops() -> usize {
+    DROPS.with(Cell::get)
+}
+
+// Panics when dropped or cloned if it is armed
+struct Bomb {
+    armed: bool,
+}
+
+impl Bomb {
+    fn new(i: usize) -> Bomb {
+        Bomb { armed: i == 3 }
+    }
+}
+
+impl Drop for Bomb {
+    fn drop(&mut self) {
+        DROPS.with(|drops| drops.set(drops.get() + 1));
+        if self.armed && !std::thread::panicking() {
+            panic!("boom");
+        }
+    }
+}
+
+impl Clone for Bomb {
+    fn clone(&self) -> Bomb {
+        if self.armed {
+            panic!("boom");
+        }
+        Bomb { armed: false }
+    }
+}
+
+const N: usize = 10;
+
+// Checks that dropping what build returns panics, and still drops all of its
+// N values
+fn assert_drops_all<L>(build: impl FnOnce() -> L) {
+    let list = build();
+    let before = drops();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| drop(list)));
+    assert!(result.is_err());
+    assert_eq!(drops() - before, N);
+}
+
+#[test]
+fn drop_fifth() {
+    assert_drops_all(|| {
+        let mut list = fifth::List::new();
+        for i in 0..N {
+            list.push(Bomb::new(i));
+        }
+        list
+    });
+}
+
+#[test]
+fn drop_sixth() {
+    assert_drops_all(|| (0..N).map(Bomb::new).collect::<sixth::LinkedList<_>>());
+}
+
+// Drops the values from within for_each, which goes through fold, and checks
+// that those not reached when one panics are dropped with the iterator
+fn assert_folds_all(iter: impl Iterator<Item = Bomb>) {
+    let before = drops();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| iter.for_each(drop)));
+    assert!(result.is_err());
+    assert_eq!(drops() - before, N);
+}
+
+#[test]
+fn into_iter_fold() {
+    let list: sixth::LinkedList<_> = (0..N).map(Bomb::new).collect();
+    assert_folds_all(list.into_iter());
+    let list: sixth::LinkedList<_> = (0..N).map(Bomb::new).collect();
+    assert_folds_all(list.into_iter().rev());
+    let mut list = fifth::List::new();
+    for i in 0..N {
+        list.push(Bomb::new(i));
+    }
+    assert_folds_all(list.into_iter());
+}
+
+#[test]
+fn drop_ring() {
+    assert_drops_all(|| (0..N).map(Bomb::new).collect::<ring::Ring<_>>());
+}
+
+#[test]
+fn drop_small_list() {
+    // the bomb is in the inline part here, and in the linked part below
+    assert_drops_all(|| {
+        (0..N)
+            .map(Bomb::new)
+            .collect::<small_list::SmallList<_, 5>>()
+    });
+    assert_drops_all(|| {
+        (0..N)
+            .map(Bomb::new)
+            .collect::<small_list::SmallList<_, 2>>()
+    });
+}
+
+#[test]
+fn clear_small_list() {
+    let mut list: small_list::SmallList<_, 5> = (0..N).map(Bomb::new).collect();
+    let before = drops();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| list.clear()));
+    assert!(result.is_err());
+    assert_eq!(drops() - before, N);
+    assert!(list.is_empty());
+    assert_eq!(list.len(), 0);
+}
+
+#[test]
+fn drop_static_rc() {
+    GhostToken::scope(|mut token| {
+        assert_drops_all(|| {
+            let mut list = static_rc::List::new();
+            for i in 0..N {
+                list.push_back(Bomb::new(i), &mut token);
+            }
+            list
+        });
+    });
+}
+
+#[test]
+fn clone_sixth() {
+    let list: sixth::LinkedList<_> = (0..N).map(Bomb::new).collect();
+    let before = drops();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| list.clone()));
+    assert!(result.is_err());
+    // the three values cloned before the panic were dropped with the clone
+    assert_eq!(drops() - before, 3);
+    assert_eq!(list.len(), N);
+    assert_drops_all(|| list);
+}
+
+#[test]
+fn extend_sixth() {
+    let mut list: sixth::LinkedList<_> = (0..N).map(|_| Bomb::new(0)).collect();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        list.extend((0..N).map(|i| {
+            if i == 5 {
+                panic!("boom");
+            }
+            Bomb::new(0)
+        }))
+    }));
+    assert!(result.is_err());
+    // the values given before the panic are in the list
+    assert_eq!(list.len(), N + 5);
+    assert_eq!(list.iter().count(), N + 5);
+    assert_eq!(list.iter().rev().count(), N + 5);
+    let before = drops();
+    drop(list);
+    assert_eq!(drops() - before, N + 5);
+}
+
+#[test]
+fn splice_iter_sixth() {
+    let mut list: sixth::LinkedList<_> = (0..N).map(|_| Bomb::new(0)).collect();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut cursor = list.cursor_mut();
+        cursor.move_next();
+        cursor.splice_before_iter((0..N).map(|i| {
+            if i == 5 {
+                panic!("boom");
+            }
+            Bomb::new(0)
+        }))
+    }));
+    assert!(result.is_err());
+    assert_eq!(list.len(), N + 5);
+    assert_eq!(list.iter().count(), N + 5);
+    assert_eq!(list.iter().rev().count(), N + 5);
+    let before = drops();
+    drop(list);
+    assert_eq!(drops() - before, N + 5);
+}
+
+#[test]
+fn splice_iter_cursor_owned() {
+    let mut cursor = (0..N).collect::<sixth::LinkedList<_>>().into_cursor();
+    cursor.move_prev();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        cursor.splice_before_iter((0..N).inspect(|&i| {
+            if i == 5 {
+                panic!("boom");
+            }
+        }))
+    }));
+    assert!(result.is_err());
+    // the cursor is still on the last value, after the ones spliced in
+    assert_eq!(cursor.current(), Some(&mut (N - 1)));
+    assert_eq!(cursor.index(), Some(N + 4));
+}
+
+#[test]
+fn retain_sixth() {
+    let mut list: sixth::LinkedList<_> = (0..N).map(Bomb::new).collect();
+    let before = drops();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| list.retain(|_| false)));
+    assert!(result.is_err());
+    // the value that panicked was unlinked before being dropped
+    assert_eq!(list.len(), N - 4);
+    assert_eq!(list.iter().rev().count(), N - 4);
+    drop(list);
+    assert_eq!(drops() - before, N);
+}
+
+#[test]
+fn drain_sixth() {
+    let mut list: sixth::LinkedList<_> = (0..N).map(Bomb::new).collect();
+    let before = drops();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut drain = list.drain();
+        drain.next();
+    }));
+    assert!(result.is_err());
+    // the values after the one that panicked were freed all the same
+    assert!(list.is_empty());
+    assert_eq!(drops() - before, N);
+}
+
+#[test]
+fn sort_sixth() {
+    let mut list: sixth::LinkedList<_> = (0..N).rev().map(Box::new).collect();
+    let mut comparisons = 0;
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        list.sort_by(|a, b| {
+            comparisons += 1;
+            if comparisons == 8 {
+                panic!("boom");
+            }
+            a.cmp(b)
+        })
+    }));
+    assert!(result.is_err());
+    // the values are all still linked, in some order
+    assert_eq!(list.len(), N);
+    let mut values: Vec<_> = list.iter().map(|value| **value).collect();
+    let mut backward: Vec<_> = list.iter().rev().map(|value| **value).collect();
+    backward.reverse();
+    assert_eq!(values, backward);
+    values.sort();
+    assert!(values.into_iter().eq(0..N));
+}
@@ -0,0 +1,71 @@
+#![cfg(all(
+    feature = "pool",
+    feature = "test-utils",
+    feature = "unsafe-queue",
+    feature = "unsafe-deque"
+))]
+
+use rust_too_many_lists::pool::{drain_pool, pooled};
+use rust_too_many_lists::test_utils::{count_allocs, CountingAllocator, DropTracker};
+use rust_too_many_lists::{fifth, sixth};
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[test]
+fn short_lived_lists_reuse_nodes() {
+    drain_pool();
+    let ((), counts) = count_allocs(|| {
+        for _ in 0..100 {
+            let list: sixth::LinkedList<u64> = (0..10).collect();
+            drop(list);
+        }
+    });
+    // only the first list allocates, and the pool itself a little
+    assert!(counts.allocations < 20, "{counts:?}");
+    assert_eq!(pooled(), 10);
+
+    // fifth has nodes of another layout
+    let ((), counts) = count_allocs(|| {
+        for _ in 0..100 {
+            let mut queue = fifth::List::new();
+            for i in 0..10u64 {
+                queue.push(i);
+            }
+        }
+    });
+    assert!(counts.allocations < 20, "{counts:?}");
+    assert_eq!(pooled(), 20);
+
+    let ((), counts) = count_allocs(|| assert_eq!(drain_pool(), 20));
+    // the blocks, and the vectors that held them
+    assert!(counts.frees >= 20, "{counts:?}");
+    assert_eq!(pooled(), 0);
+}
+
+#[test]
+fn values_are_dropped() {
+    let tracker = DropTracker::new();
+    let mut list: sixth::LinkedList<_> = (0..10).map(|i| tracker.track(i)).collect();
+    assert_eq!(list.pop_front().as_deref(), Some(&0));
+    tracker.assert_alive(9);
+    list.push_back(tracker.track(10));
+    drop(list);
+    tracker.assert_all_dropped();
+}
+
+#[test]
+fn nodes_freed_on_another_thread() {
+    drain_pool();
+    let mut queue = fifth::List::new();
+    for i in 0..10u64 {
+        queue.push(i);
+    }
+    std::thread::spawn(move || {
+        drop(queue);
+        assert_eq!(pooled(), 10);
+    })
+    .join()
+    .unwrap();
+    assert_eq!(pooled(), 0);
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "rand")]
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use rust_too_many_lists::sixth::LinkedList;
+
+#[test]
+fn shuffle_keeps_the_values() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut list: LinkedList<u32> = (0..100).collect();
+    list.shuffle(&mut rng);
+    assert_eq!(list.len(), 100);
+    assert!(!list.iter().copied().eq(0..100));
+    let mut values: Vec<_> = list.iter().copied().collect();
+    values.sort();
+    assert!(values.into_iter().eq(0..100));
+    // the links are consistent in both directions
+    let mut backward: Vec<_> = list.iter().rev().copied().collect();
+    backward.reverse();
+    assert!(list.iter().copied().eq(backward));
+    list.push_back(100);
+    list.push_front(101);
+    assert_eq!(list.pop_back(), Some(100));
+    assert_eq!(list.pop_front(), Some(101));
+}
+
+#[test]
+fn shuffle_small_lists() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut list: LinkedList<u32> = LinkedList::new();
+    list.shuffle(&mut rng);
+    assert!(list.is_empty());
+    list.push_back(1);
+    list.shuffle(&mut rng);
+    assert_eq!(list.front(), Some(&1));
+    assert_eq!(list.back(), Some(&1));
+}
+
+#[test]
+fn shuffle_is_uniform() {
+    // each of the 6 orders of 3 values comes up about a sixth of the time
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut counts = [0; 6];
+    for _ in 0..6000 {
+        let mut list: LinkedList<usize> = (0..3).collect();
+        list.shuffle(&mut rng);
+        let order: Vec<_> = list.into_iter().collect();
+        let index = order[0] * 2 + usize::from(order[1] > order[2]);
+        counts[index] += 1;
+    }
+    assert!(counts.iter().all(|&count| (900..1100).contains(&count)));
+}
+
+#[test]
+fn choose() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let list: LinkedList<usize> = LinkedList::new();
+    assert_eq!(list.choose(&mut rng), None);
+
+    let list: LinkedList<usize> = (0..5).collect();
+    let mut counts = [0; 5];
+    for _ in 0..5000 {
+        counts[*list.choose(&mut rng).unwrap()] += 1;
+    }
+    assert!(counts.iter().all(|&count| (900..1100).contains(&count)));
+}
@@ -0,0 +1,42 @@
+#![cfg(feature = "rayon")]
+
+use rayon::prelude::*;
+
+use rust_too_many_lists::sixth::LinkedList;
+
+#[test]
+fn par_iter() {
+    let list: LinkedList<u64> = (0..1000).collect();
+    assert_eq!(list.par_iter().sum::<u64>(), 499500);
+    assert_eq!(list.par_iter().filter(|&&v| v % 7 == 0).count(), 143);
+}
+
+#[test]
+fn par_iter_mut() {
+    let mut list: LinkedList<u64> = (0..1000).collect();
+    list.par_iter_mut().for_each(|v| *v *= 2);
+    assert!(list.iter().copied().eq((0..1000).map(|v| v * 2)));
+}
+
+#[test]
+fn collect_keeps_order() {
+    let list: LinkedList<u64> = (0..1000).collect();
+    let squares: LinkedList<u64> = list.into_par_iter().map(|v| v * v).collect();
+    assert_eq!(squares.len(), 1000);
+    assert!(squares.iter().copied().eq((0..1000).map(|v| v * v)));
+    assert!(squares
+        .iter()
+        .rev()
+        .copied()
+        .eq((0..1000).rev().map(|v| v * v)));
+}
+
+#[test]
+fn par_extend() {
+    let mut list: LinkedList<u64> = (0..10).collect();
+    list.par_extend((10..1000).into_par_iter().filter(|v| v % 2 == 0));
+    assert!(list
+        .iter()
+        .copied()
+        .eq((0..10).chain((10..1000).filter(|v| v % 2 == 0))));
+}
@@ -0,0 +1,49 @@
+#![cfg(feature = "rkyv")]
+
+use rkyv::rancor::Error;
+use rkyv::vec::ArchivedVec;
+use rkyv::Archived;
+
+use rust_too_many_lists::sixth::LinkedList;
+
+#[test]
+fn round_trip() {
+    let list: LinkedList<u32> = (0..100).collect();
+    let bytes = rkyv::to_bytes::<Error>(&list).unwrap();
+
+    // the archive is read in place, as a contiguous sequence
+    let archived = rkyv::access::<ArchivedVec<Archived<u32>>, Error>(&bytes).unwrap();
+    assert_eq!(archived.len(), 100);
+    assert_eq!(archived[42], 42);
+    assert!(archived.iter().map(|v| v.to_native()).eq(0..100));
+
+    let restored: LinkedList<u32> = rkyv::deserialize::<_, Error>(archived).unwrap();
+    assert_eq!(restored, list);
+}
+
+#[test]
+fn nested() {
+    let mut list = LinkedList::new();
+    list.push_back("a".to_string());
+    list.push_back(String::new());
+    list.push_front("front".to_string());
+    let lists: LinkedList<LinkedList<String>> = [list, LinkedList::new()].into_iter().collect();
+
+    let bytes = rkyv::to_bytes::<Error>(&lists).unwrap();
+    let archived = rkyv::access::<Archived<LinkedList<LinkedList<String>>>, Error>(&bytes).unwrap();
+    assert_eq!(archived[0][0], "front");
+    assert!(archived[1].is_empty());
+
+    let restored: LinkedList<LinkedList<String>> = rkyv::deserialize::<_, Error>(archived).unwrap();
+    assert_eq!(restored, lists);
+}
+
+#[test]
+fn empty() {
+    let list: LinkedList<u8> = LinkedList::new();
+    let bytes = rkyv::to_bytes::<Error>(&list).unwrap();
+    let archived = rkyv::access::<ArchivedVec<u8>, Error>(&bytes).unwrap();
+    assert!(archived.is_empty());
+    let restored: LinkedList<u8> = rkyv::deserialize::<_, Error>(archived).unwrap();
+    assert!(restored.is_empty());
+}
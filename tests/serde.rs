@@ -0,0 +1,93 @@
+#![cfg(all(
+    feature = "serde",
+    feature = "first",
+    feature = "persistent",
+    feature = "rc-deque",
+    feature = "unsafe-queue",
+    feature = "unsafe-deque"
+))]
+
+use std::fmt::Debug;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use rust_too_many_lists::{fifth, first, fourth, sixth, third};
+
+// Round trips through both a self-describing and a compact format, and
+// returns the JSON
+fn round_trip<L: Serialize + DeserializeOwned>(list: &L, check: impl Fn(L)) -> String {
+    let json = serde_json::to_string(list).unwrap();
+    check(serde_json::from_str(&json).unwrap());
+    let bytes = bincode::serialize(list).unwrap();
+    check(bincode::deserialize(&bytes).unwrap());
+    json
+}
+
+fn drain<T: Debug>(mut pop: impl FnMut() -> Option<T>) -> Vec<T> {
+    std::iter::from_fn(&mut pop).collect()
+}
+
+#[test]
+fn first() {
+    let mut list = first::List::new();
+    for i in [3, 2, 1] {
+        list.push_front(i);
+    }
+    let json = round_trip(&list, |list| {
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+    });
+    assert_eq!(json, "[1,2,3]");
+}
+
+#[test]
+fn third() {
+    let base: third::List<_> = third::List::new().prepend("tail".to_string());
+    let list = base.prepend("b".to_string()).prepend("a".to_string());
+    let json = round_trip(&list, |list| {
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec!["a", "b", "tail"]);
+    });
+    assert_eq!(json, r#"["a","b","tail"]"#);
+
+    let shared: third::SyncList<i32> = serde_json::from_str("[1, 2]").unwrap();
+    assert_eq!(shared.iter().copied().collect::<Vec<_>>(), vec![1, 2]);
+}
+
+#[test]
+fn fourth() {
+    let mut list = fourth::List::new();
+    list.push_back(2);
+    list.push_front(1);
+    list.push_back(3);
+    let json = round_trip(&list, |mut list| {
+        assert_eq!(drain(|| list.pop_front()), vec![1, 2, 3]);
+    });
+    assert_eq!(json, "[1,2,3]");
+}
+
+#[test]
+fn fifth() {
+    let mut list = fifth::List::new();
+    for i in 1..=3 {
+        list.push(i);
+    }
+    let json = round_trip(&list, |mut list| {
+        assert_eq!(drain(|| list.pop()), vec![1, 2, 3]);
+    });
+    assert_eq!(json, "[1,2,3]");
+}
+
+#[test]
+fn sixth() {
+    let list: sixth::LinkedList<_> = vec![vec![1], vec![], vec![2, 3]].into_iter().collect();
+    let json = round_trip(&list, |copy| assert_eq!(copy, list));
+    assert_eq!(json, "[[1],[],[2,3]]");
+}
+
+#[test]
+fn errors() {
+    assert!(serde_json::from_str::<sixth::LinkedList<i32>>("{}").is_err());
+    assert!(serde_json::from_str::<fifth::List<i32>>("[1, \"a\"]").is_err());
+    let empty: sixth::LinkedList<i32> = serde_json::from_str("[]").unwrap();
+    assert!(empty.is_empty());
+}
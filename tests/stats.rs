@@ -0,0 +1,167 @@
+#![cfg(all(
+    feature = "stats",
+    feature = "first",
+    feature = "rc-deque",
+    feature = "unsafe-queue",
+    feature = "unsafe-deque",
+    feature = "sorted"
+))]
+
+use rust_too_many_lists::sixth::LinkedList;
+use rust_too_many_lists::stats::Stats;
+use rust_too_many_lists::{fifth, first, fourth, sorted};
+
+fn stats(allocations: usize, frees: usize, nodes: usize, peak: usize) -> Stats {
+    Stats {
+        allocations,
+        frees,
+        nodes,
+        peak,
+    }
+}
+
+#[test]
+fn push_and_pop() {
+    let mut stack = first::List::new();
+    let mut deque = fourth::List::new();
+    let mut queue = fifth::List::new();
+    let mut list = LinkedList::new();
+    assert_eq!(stack.stats(), Stats::default());
+    for i in 0..5 {
+        stack.push_front(i);
+        deque.push_back(i);
+        queue.push(i);
+        list.push_front(i);
+    }
+    for _ in 0..3 {
+        stack.pop_front();
+        deque.pop_front();
+        queue.pop();
+        list.pop_back();
+    }
+    stack.push_front(0);
+    deque.push_front(0);
+    queue.push(0);
+    list.push_back(0);
+    let expected = stats(6, 3, 3, 5);
+    assert_eq!(stack.stats(), expected);
+    assert_eq!(deque.stats(), expected);
+    assert_eq!(queue.stats(), expected);
+    assert_eq!(list.stats(), expected);
+}
+
+#[test]
+fn clear() {
+    let mut list: LinkedList<_> = (0..4).collect();
+    list.clear();
+    assert_eq!(list.stats(), stats(4, 4, 0, 4));
+}
+
+#[test]
+fn clone_from() {
+    let source: LinkedList<_> = (0..4).collect();
+    let mut list: LinkedList<_> = (0..6).collect();
+    list.clone_from(&source);
+    assert_eq!(list.stats(), stats(6, 2, 4, 6));
+    list.clone_from(&source);
+    assert_eq!(list.stats(), stats(6, 2, 4, 6));
+    list.clone_from(&(0..5).collect());
+    assert_eq!(list.stats(), stats(7, 2, 5, 6));
+}
+
+#[test]
+fn popping_empty_lists() {
+    let mut list: LinkedList<i32> = LinkedList::new();
+    list.pop_front();
+    list.pop_back();
+    assert_eq!(list.stats(), Stats::default());
+}
+
+#[test]
+fn recycling() {
+    let list: LinkedList<_> = (0..10).collect();
+    let list = LinkedList::recycle_from(list, 10..20);
+    assert_eq!(list.stats(), stats(10, 0, 10, 10));
+    let list = LinkedList::recycle_from(list, 0..4);
+    assert_eq!(list.stats(), stats(10, 6, 4, 10));
+    let list = LinkedList::recycle_from(list, 0..6);
+    assert_eq!(list.stats(), stats(12, 6, 6, 10));
+
+    let mut queue = fifth::List::new();
+    for i in 0..10 {
+        queue.push(i);
+    }
+    let queue = fifth::List::recycle_from(queue, 10..20);
+    assert_eq!(queue.stats(), stats(10, 0, 10, 10));
+    let queue = fifth::List::recycle_from(queue, 0..4);
+    assert_eq!(queue.stats(), stats(10, 6, 4, 10));
+    let mut queue = fifth::List::recycle_from(queue, 0..6);
+    assert_eq!(queue.stats(), stats(12, 6, 6, 10));
+    assert_eq!(queue.pop(), Some(0));
+}
+
+#[test]
+fn sorted_merge() {
+    let mut a: sorted::List<_> = [1, 3, 5].into_iter().collect();
+    let mut b = sorted::List::new();
+    b.insert(2);
+    b.insert(4);
+    b.pop_min();
+    a.merge(b);
+    assert_eq!(a.stats(), stats(3, 0, 4, 4));
+    a.pop_min();
+    assert_eq!(a.stats(), stats(3, 1, 3, 4));
+}
+
+#[test]
+fn cursor_moves_nodes() {
+    let mut list: LinkedList<_> = (0..10).collect();
+    let mut cursor = list.cursor_mut();
+    cursor.move_next();
+    cursor.move_next();
+    let tail = cursor.split_after();
+    assert_eq!(tail.stats(), stats(0, 0, 8, 8));
+    assert_eq!(list.stats(), stats(10, 0, 2, 10));
+
+    let mut other: LinkedList<_> = (0..20).collect();
+    other.cursor_mut().splice_before(tail);
+    assert_eq!(other.stats(), stats(20, 0, 28, 28));
+
+    // splicing into an empty list, and splitting at the ghost
+    let mut empty = LinkedList::new();
+    empty.cursor_mut().splice_after(other);
+    assert_eq!(empty.stats(), stats(0, 0, 28, 28));
+    let all = empty.cursor_mut().split_before();
+    assert_eq!(empty.stats(), stats(0, 0, 0, 28));
+    assert_eq!(all.stats(), stats(0, 0, 28, 28));
+    assert_eq!(all.len(), 28);
+
+    let mut list: LinkedList<_> = (0..3).collect();
+    let mut cursor = list.cursor_mut();
+    cursor.move_next();
+    let front = cursor.remove_current_as_list().unwrap();
+    assert_eq!(front.stats(), stats(0, 0, 1, 1));
+    assert_eq!(list.stats(), stats(3, 0, 2, 3));
+
+    let mut other = LinkedList::new();
+    other.push_back_node(list.pop_front_node().unwrap());
+    assert_eq!(list.stats(), stats(3, 0, 1, 3));
+    assert_eq!(other.stats(), stats(0, 0, 1, 1));
+}
+
+#[test]
+fn concatenation() {
+    let lists = [(0..3).collect(), LinkedList::new(), (0..4).collect()];
+    let list: LinkedList<i32> = lists.into_iter().collect();
+    assert_eq!(list.stats(), stats(0, 0, 7, 7));
+
+    let mut a: LinkedList<_> = (0..3).collect();
+    let mut b: LinkedList<_> = (0..2).collect();
+    a.prepend(&mut b);
+    assert_eq!(a.stats(), stats(3, 0, 5, 5));
+    assert_eq!(b.stats(), stats(2, 0, 0, 2));
+
+    let sorted: LinkedList<_> = (0..3).collect();
+    let merged = sorted.merge((0..2).collect());
+    assert_eq!(merged.stats(), stats(3, 0, 5, 5));
+}
@@ -0,0 +1,56 @@
+#![cfg(all(
+    feature = "test-utils",
+    feature = "unsafe-deque",
+    feature = "sorted",
+    not(feature = "pool")
+))]
+
+use rust_too_many_lists::sixth::{LinkedList, Node};
+use rust_too_many_lists::sorted;
+use rust_too_many_lists::test_utils::{
+    assert_no_leaks, count_allocs, CountingAllocator, DropTracker,
+};
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[test]
+fn lists_drop_and_free_everything() {
+    let tracker = DropTracker::new();
+    assert_no_leaks(|| {
+        let mut list: LinkedList<_> = (0..10).map(|i| tracker.track(i)).collect();
+        list.pop_front();
+        tracker.assert_alive(9);
+        let mut cursor = list.cursor_mut();
+        cursor.move_next();
+        let tail = cursor.split_after();
+        drop(list);
+        tracker.assert_alive(8);
+        drop(tail);
+    });
+    tracker.assert_all_dropped();
+
+    let mut list = sorted::List::new();
+    for i in [3, 1, 2] {
+        list.insert(tracker.track(i));
+    }
+    assert_eq!(list.pop_min().as_deref(), Some(&1));
+    drop(list);
+    tracker.assert_all_dropped();
+}
+
+#[test]
+fn one_allocation_per_node() {
+    let (list, counts) = count_allocs(|| (0..100).collect::<LinkedList<u64>>());
+    assert_eq!(counts.allocations, 100);
+    assert_eq!(counts.frees, 0);
+    let ((), counts) = count_allocs(|| drop(list));
+    assert_eq!(counts.frees, 100);
+    assert_eq!(counts.freed_bytes, counts.frees * size_of::<Node<u64>>());
+}
+
+#[test]
+#[should_panic(expected = "1 allocations but 0 frees")]
+fn leak() {
+    assert_no_leaks(|| std::mem::forget(Box::new(1)));
+}
@@ -0,0 +1,124 @@
+#![cfg(all(
+    feature = "tracing",
+    feature = "rc-deque",
+    feature = "unsafe-queue",
+    feature = "unsafe-deque"
+))]
+
+use std::fmt::{Debug, Write};
+use std::sync::{Arc, Mutex};
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+use rust_too_many_lists::{fifth, fourth, sixth};
+
+// Writes each event as its message followed by its fields
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+struct Line<'a>(&'a mut String);
+
+impl Visit for Line<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            write!(self.0, "{value:?}").unwrap();
+        } else {
+            write!(self.0, " {}={value:?}", field.name()).unwrap();
+        }
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _span: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut line = String::new();
+        event.record(&mut Line(&mut line));
+        self.0.lock().unwrap().push(line);
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+fn record(f: impl FnOnce()) -> Vec<String> {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), f);
+    let lines = recorder.0.lock().unwrap().clone();
+    lines
+}
+
+#[test]
+fn sixth() {
+    let lines = record(|| {
+        let mut list = sixth::LinkedList::new();
+        list.push_back(1);
+        list.push_front(0);
+        list.push_back(2);
+        let mut cursor = list.cursor_mut();
+        cursor.move_next();
+        let tail = cursor.split_after();
+        cursor.splice_before(tail);
+        list.pop_back();
+        list.pop_front();
+    });
+    assert_eq!(
+        lines,
+        [
+            "push_back len=1",
+            "push_front len=2",
+            "push_back len=3",
+            "split_after moved=2 len=1",
+            "splice_before moved=2 len=3",
+            "pop_back len=2",
+            "pop_front len=1",
+            // dropping the list frees the last node
+            "pop_front len=0",
+        ]
+    );
+}
+
+#[test]
+fn fourth_and_fifth() {
+    let lines = record(|| {
+        let mut deque = fourth::List::new();
+        deque.push_front(0);
+        deque.push_back(1);
+        deque.pop_back();
+        deque.pop_front();
+        let mut queue = fifth::List::new();
+        queue.push(0);
+        queue.pop();
+    });
+    assert_eq!(
+        lines,
+        [
+            "push_front",
+            "push_back",
+            "pop_back",
+            "pop_front",
+            "push",
+            "pop"
+        ]
+    );
+}
+
+#[test]
+fn without_subscriber() {
+    let mut list = sixth::LinkedList::new();
+    list.push_back(1);
+    assert_eq!(list.pop_front(), Some(1));
+}
@@ -0,0 +1,18 @@
+#![cfg(all(
+    feature = "first",
+    feature = "persistent",
+    feature = "rc-deque",
+    feature = "unsafe-deque",
+    feature = "sorted"
+))]
+
+// Properties of the lists that only show at compile time: which lifetimes
+// they may shrink, which threads they may go to, and which cursors may
+// outlive what they point into.
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass/*.rs");
+    t.compile_fail("tests/ui/fail/*.rs");
+}
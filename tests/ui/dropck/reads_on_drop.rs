@@ -0,0 +1,16 @@
+use rust_too_many_lists::sixth::LinkedList;
+
+// reads the string it borrows when it is dropped
+struct Printer<'a>(&'a String);
+
+impl Drop for Printer<'_> {
+    fn drop(&mut self) {
+        println!("{}", self.0);
+    }
+}
+
+fn main() {
+    let mut list = LinkedList::new();
+    let s = String::from("dangling");
+    list.push_back(Printer(&s));
+}
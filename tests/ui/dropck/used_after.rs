@@ -0,0 +1,10 @@
+use rust_too_many_lists::sixth::LinkedList;
+
+fn main() {
+    let mut list = LinkedList::new();
+    {
+        let s = String::from("dangling");
+        list.push_back(&s);
+    }
+    println!("{:?}", list.front());
+}
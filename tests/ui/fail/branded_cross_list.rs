@@ -0,0 +1,15 @@
+// Each list has its own brand, so the handles of one list cannot be used with
+// another, even one of the same type.
+
+use rust_too_many_lists::branded::BrandedList;
+
+fn main() {
+    BrandedList::scope(|mut outer| {
+        let x = outer.push_back(1);
+        BrandedList::scope(|mut inner| {
+            inner.push_back(2);
+            inner.get(&x);
+            inner.remove(x);
+        });
+    });
+}
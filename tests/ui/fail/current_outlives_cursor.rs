@@ -0,0 +1,13 @@
+// The value is borrowed from the cursor, so moving the cursor away from it
+// while it is still used is rejected.
+
+use rust_too_many_lists::sixth::LinkedList;
+
+fn main() {
+    let mut list: LinkedList<i32> = (0..3).collect();
+    let mut cursor = list.cursor_mut();
+    cursor.move_next();
+    let value = cursor.current().unwrap();
+    cursor.split_after();
+    *value = 10;
+}
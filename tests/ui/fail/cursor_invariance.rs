@@ -0,0 +1,7 @@
+use rust_too_many_lists::sixth::CursorMut;
+
+fn shorten<'c, 'a>(cursor: CursorMut<'c, &'static str>) -> CursorMut<'c, &'a str> {
+    cursor
+}
+
+fn main() {}
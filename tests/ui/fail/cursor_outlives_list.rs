@@ -0,0 +1,9 @@
+use rust_too_many_lists::sixth::LinkedList;
+
+fn main() {
+    let mut cursor = {
+        let mut list: LinkedList<i32> = (0..3).collect();
+        list.cursor_mut()
+    };
+    cursor.move_next();
+}
@@ -0,0 +1,7 @@
+use rust_too_many_lists::first::IterMut;
+
+fn shorten<'i, 'a>(iter: IterMut<'i, &'static str>) -> IterMut<'i, &'a str> {
+    iter
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+// The nodes are shared through Rc, whose counts are not atomic.
+
+use rust_too_many_lists::fourth::List;
+
+fn main() {
+    let mut list = List::new();
+    list.push_back(1);
+    std::thread::spawn(move || list.pop_front());
+}
@@ -0,0 +1,10 @@
+// Otherwise, a short-lived reference could be written into a list of
+// long-lived ones.
+
+use rust_too_many_lists::sixth::IterMut;
+
+fn shorten<'i, 'a>(iter: IterMut<'i, &'static str>) -> IterMut<'i, &'a str> {
+    iter
+}
+
+fn main() {}
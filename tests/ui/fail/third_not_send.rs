@@ -0,0 +1,8 @@
+// The nodes are shared through Rc, whose counts are not atomic.
+
+use rust_too_many_lists::third::List;
+
+fn main() {
+    let list: List<i32> = List::new().prepend(1);
+    std::thread::spawn(move || list.head().copied());
+}
@@ -0,0 +1,39 @@
+// Each list of long-lived references can be used where one of shorter-lived
+// references is expected, like a Vec. Not third, though: its nodes are behind
+// the pointer family's associated type, which the compiler treats as
+// invariant.
+
+use rust_too_many_lists::{first, sixth, sorted};
+
+fn first<'a>(list: first::List<&'static str>) -> first::List<&'a str> {
+    list
+}
+
+fn first_iter<'i, 'a>(iter: first::IterRef<'i, &'static str>) -> first::IterRef<'i, &'a str> {
+    iter
+}
+
+fn first_into_iter<'a>(iter: first::IntoIter<&'static str>) -> first::IntoIter<&'a str> {
+    iter
+}
+
+fn sorted<'a>(list: sorted::List<&'static str>) -> sorted::List<&'a str> {
+    list
+}
+
+fn sixth<'a>(list: sixth::LinkedList<&'static str>) -> sixth::LinkedList<&'a str> {
+    list
+}
+
+fn sixth_iter<'i, 'a>(iter: sixth::Iter<'i, &'static str>) -> sixth::Iter<'i, &'a str> {
+    iter
+}
+
+fn sixth_into_iter<'a>(iter: sixth::IntoIter<&'static str>) -> sixth::IntoIter<&'a str> {
+    iter
+}
+
+fn main() {
+    let _ = (first, first_iter, first_into_iter);
+    let _ = (sorted, sixth, sixth_iter, sixth_into_iter);
+}
@@ -0,0 +1,12 @@
+// Once the cursor is no longer used, the list can be used again.
+
+use rust_too_many_lists::sixth::LinkedList;
+
+fn main() {
+    let mut list: LinkedList<i32> = (0..3).collect();
+    let mut cursor = list.cursor_mut();
+    cursor.move_next();
+    *cursor.current().unwrap() = 10;
+    list.push_back(3);
+    assert_eq!(list.front(), Some(&10));
+}
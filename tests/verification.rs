@@ -0,0 +1,241 @@
+#![cfg(feature = "verification")]
+
+// Where tests/model.rs tries random sequences of operations, these try every
+// sequence up to a bound, and check each value is dropped exactly once. Miri
+// also catches the uses after free, with smaller bounds:
+//
+//     cargo test --profile verification --features verification --test verification
+//     cargo +nightly miri test --features verification --test verification
+//
+// The Kani harnesses in src/fifth.rs and src/sixth.rs check the same things
+// symbolically, with cargo kani.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use rust_too_many_lists::fifth;
+use rust_too_many_lists::sixth::LinkedList;
+
+const MAX_OPS: usize = if cfg!(miri) { 3 } else { 10 };
+const MAX_LEN: usize = if cfg!(miri) { 3 } else { 8 };
+
+// Hands out values that know whether they were dropped
+#[derive(Clone, Default)]
+struct Values(Rc<RefCell<Vec<bool>>>);
+
+struct Value {
+    id: usize,
+    dropped: Values,
+}
+
+impl Values {
+    fn next(&self) -> Value {
+        let mut dropped = self.0.borrow_mut();
+        dropped.push(false);
+        Value {
+            id: dropped.len() - 1,
+            dropped: self.clone(),
+        }
+    }
+
+    fn assert_all_dropped(&self) {
+        let dropped = self.0.borrow();
+        let alive: Vec<_> = (0..dropped.len()).filter(|&id| !dropped[id]).collect();
+        assert!(alive.is_empty(), "{alive:?} were never dropped");
+    }
+}
+
+impl Drop for Value {
+    fn drop(&mut self) {
+        let mut dropped = self.dropped.0.borrow_mut();
+        assert!(!dropped[self.id], "{} was dropped twice", self.id);
+        dropped[self.id] = true;
+    }
+}
+
+fn ids<'a>(values: impl Iterator<Item = &'a Value>) -> Vec<usize> {
+    values.map(|value| value.id).collect()
+}
+
+// Calls f with every sequence of at most MAX_OPS numbers below n
+fn for_each_sequence(n: usize, mut f: impl FnMut(&[usize])) {
+    let mut ops = Vec::new();
+    loop {
+        f(&ops);
+        // counts in base n, with longer sequences after the shorter ones
+        let mut i = 0;
+        while i < ops.len() && ops[i] == n - 1 {
+            ops[i] = 0;
+            i += 1;
+        }
+        if i == ops.len() {
+            if ops.len() == MAX_OPS {
+                return;
+            }
+            ops.push(0);
+        } else {
+            ops[i] += 1;
+        }
+    }
+}
+
+fn check_sixth(list: &LinkedList<Value>, model: &VecDeque<usize>) {
+    assert_eq!(list.len(), model.len());
+    assert_eq!(ids(list.iter()), Vec::from(model.clone()));
+    let mut backward = ids(list.iter().rev());
+    backward.reverse();
+    assert_eq!(backward, Vec::from(model.clone()));
+}
+
+fn sixth_list(values: &Values, len: usize) -> (LinkedList<Value>, Vec<usize>) {
+    let list: LinkedList<_> = (0..len).map(|_| values.next()).collect();
+    let model = ids(list.iter());
+    (list, model)
+}
+
+#[test]
+fn sixth_push_and_pop() {
+    for_each_sequence(4, |ops| {
+        let values = Values::default();
+        let mut list = LinkedList::new();
+        let mut model = VecDeque::new();
+        for &op in ops {
+            match op {
+                0 => {
+                    let value = values.next();
+                    model.push_front(value.id);
+                    list.push_front(value);
+                }
+                1 => {
+                    let value = values.next();
+                    model.push_back(value.id);
+                    list.push_back(value);
+                }
+                2 => assert_eq!(list.pop_front().map(|v| v.id), model.pop_front()),
+                _ => assert_eq!(list.pop_back().map(|v| v.id), model.pop_back()),
+            }
+            check_sixth(&list, &model);
+        }
+        drop(list);
+        values.assert_all_dropped();
+    });
+}
+
+#[test]
+fn sixth_split() {
+    for len in 0..=MAX_LEN {
+        // the cursor starts at the ghost, and each move goes one further
+        for moves in 0..=len {
+            for before in [true, false] {
+                let values = Values::default();
+                let (mut list, model) = sixth_list(&values, len);
+                let mut cursor = list.cursor_mut();
+                for _ in 0..moves {
+                    cursor.move_next();
+                }
+                let (other, at) = match (before, moves) {
+                    (_, 0) => (cursor.split_before(), len),
+                    (true, _) => (cursor.split_before(), moves - 1),
+                    (false, _) => (cursor.split_after(), moves),
+                };
+                let (kept, moved) = match (before, moves) {
+                    (_, 0) => (&model[..0], &model[..]),
+                    (true, _) => (&model[at..], &model[..at]),
+                    (false, _) => (&model[..at], &model[at..]),
+                };
+                check_sixth(&list, &kept.iter().copied().collect());
+                check_sixth(&other, &moved.iter().copied().collect());
+                drop((list, other));
+                values.assert_all_dropped();
+            }
+        }
+    }
+}
+
+#[test]
+fn sixth_splice() {
+    for len in 0..=MAX_LEN {
+        for input_len in 0..=MAX_LEN - len {
+            for moves in 0..=len {
+                for before in [true, false] {
+                    let values = Values::default();
+                    let (mut list, mut model) = sixth_list(&values, len);
+                    let (input, input_model) = sixth_list(&values, input_len);
+                    let mut cursor = list.cursor_mut();
+                    for _ in 0..moves {
+                        cursor.move_next();
+                    }
+                    // at the ghost, splicing before appends and splicing
+                    // after prepends
+                    let at = match (before, moves) {
+                        (true, 0) => len,
+                        (false, 0) => 0,
+                        (true, _) => moves - 1,
+                        (false, _) => moves,
+                    };
+                    let index = cursor.index();
+                    if before {
+                        cursor.splice_before(input);
+                    } else {
+                        cursor.splice_after(input);
+                    }
+                    // what is spliced before the cursor pushes it further
+                    // from the front
+                    let index = index.map(|index| if before { index + input_len } else { index });
+                    assert_eq!(cursor.index(), index);
+                    model.splice(at..at, input_model);
+                    check_sixth(&list, &model.into_iter().collect());
+                    drop(list);
+                    values.assert_all_dropped();
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn fifth_push_and_pop() {
+    for_each_sequence(2, |ops| {
+        let values = Values::default();
+        let mut list = fifth::List::new();
+        let mut model = VecDeque::new();
+        for &op in ops {
+            if op == 0 {
+                let value = values.next();
+                model.push_back(value.id);
+                list.push(value);
+            } else {
+                assert_eq!(list.pop().map(|v| v.id), model.pop_front());
+            }
+        }
+        // popping is the only way to see the values
+        for id in model {
+            assert_eq!(list.pop().map(|v| v.id), Some(id));
+        }
+        assert!(list.pop().is_none());
+        values.assert_all_dropped();
+    });
+}
+
+#[test]
+fn fifth_recycle() {
+    for old_len in 0..=MAX_LEN {
+        for new_len in 0..=MAX_LEN {
+            let values = Values::default();
+            let mut old = fifth::List::new();
+            for _ in 0..old_len {
+                old.push(values.next());
+            }
+            let new: Vec<_> = (0..new_len).map(|_| values.next()).collect();
+            let ids = ids(new.iter());
+            let mut list = fifth::List::recycle_from(old, new);
+            for id in ids {
+                assert_eq!(list.pop().map(|v| v.id), Some(id));
+            }
+            assert!(list.pop().is_none());
+            drop(list);
+            values.assert_all_dropped();
+        }
+    }
+}
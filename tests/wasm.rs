@@ -0,0 +1,44 @@
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+// Run with wasm-pack test --node -- --features wasm
+
+use js_sys::{Array, Function};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use rust_too_many_lists::wasm::JsDeque;
+
+#[wasm_bindgen_test]
+fn push_and_pop() {
+    let mut deque = JsDeque::new();
+    assert!(deque.is_empty());
+    assert_eq!(deque.pop_front(), None);
+    deque.push_back(JsValue::from(2));
+    deque.push_back(JsValue::from("three"));
+    deque.push_front(JsValue::from(1));
+    assert_eq!(deque.length(), 3);
+    assert_eq!(deque.pop_front(), Some(JsValue::from(1)));
+    assert_eq!(deque.pop_back(), Some(JsValue::from("three")));
+    assert_eq!(deque.pop_back(), Some(JsValue::from(2)));
+    assert_eq!(deque.pop_back(), None);
+}
+
+#[wasm_bindgen_test]
+fn for_each() {
+    let mut deque = JsDeque::new();
+    for i in 0..4 {
+        deque.push_back(JsValue::from(i * 10));
+    }
+    let seen = Array::new();
+    let f = Function::new_with_args("value, i", "this.push([i, value])");
+    deque.for_each(&f.bind(&seen)).unwrap();
+    assert_eq!(seen.length(), 4);
+    let last = Array::from(&seen.get(3));
+    assert_eq!(last.get(0), JsValue::from(3));
+    assert_eq!(last.get(1), JsValue::from(30));
+    assert_eq!(deque.to_array().length(), 4);
+
+    // an exception from the callback stops the iteration
+    let throws = Function::new_with_args("value", "if (value == 10) throw value");
+    assert_eq!(deque.for_each(&throws), Err(JsValue::from(10)));
+}